@@ -0,0 +1,136 @@
+use std::{cmp, iter, ops};
+
+use crate::Bin;
+
+/// The weight in `bin`, what's taken of its size, so it's never added up past the size.
+fn load<T>(bin: &Bin<T>) -> T
+where
+    T: Clone + for<'a> ops::SubAssign<&'a T>,
+{
+    let mut load = bin.size.clone();
+    load -= &bin.capacity;
+    load
+}
+
+/// Moves and swaps items between the fullest and the emptiest bins while that narrows the gap
+/// between them, without exceeding any capacity.
+///
+/// Returns the number of improving moves made.
+pub fn balance<T>(bins: &mut [Bin<T>]) -> usize
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    let zero: T = iter::empty::<&T>().sum();
+    let mut moves = 0;
+
+    'improve: loop {
+        let mut order: Vec<usize> = (0..bins.len()).collect();
+        order.sort_by_key(|&idx| cmp::Reverse(load(&bins[idx])));
+
+        // try the pairs with the largest gap first
+        for (high_pos, &high) in order.iter().enumerate() {
+            for &low in order[high_pos + 1..].iter().rev() {
+                let mut gap = load(&bins[high]);
+                gap -= &load(&bins[low]);
+                if gap <= zero {
+                    break;
+                }
+
+                if let Some((from, to)) = find_exchange(&bins[high], &bins[low], &gap, &zero) {
+                    exchange(bins, high, low, from, to);
+                    moves += 1;
+                    continue 'improve;
+                }
+            }
+        }
+
+        break;
+    }
+
+    moves
+}
+
+/// Finds an item of `high` (and optionally one of `low` to swap with) whose exchange moves
+/// less than `gap` from `high` to `low`.
+fn find_exchange<T>(
+    high: &Bin<T>,
+    low: &Bin<T>,
+    gap: &T,
+    zero: &T,
+) -> Option<(usize, Option<usize>)>
+where
+    T: Ord + Clone + for<'a> ops::SubAssign<&'a T>,
+{
+    for (from, item) in high.items.iter().enumerate() {
+        if item < gap && item > zero && low.capacity >= *item {
+            return Some((from, None));
+        }
+
+        for (to, other) in low.items.iter().enumerate() {
+            if other >= item {
+                continue;
+            }
+
+            let mut delta = item.clone();
+            delta -= other;
+            if &delta < gap && low.capacity >= delta {
+                return Some((from, Some(to)));
+            }
+        }
+    }
+
+    None
+}
+
+fn exchange<T>(bins: &mut [Bin<T>], high: usize, low: usize, from: usize, to: Option<usize>)
+where
+    T: Clone + cmp::PartialOrd + for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    let item = bins[high].items.swap_remove(from);
+    bins[high].capacity += &item;
+
+    if let Some(to) = to {
+        let other = bins[low].items.swap_remove(to);
+        bins[low].capacity += &other;
+        bins[high].push(other);
+    }
+
+    bins[low].push(item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bin(capacity: u32, items: &[u32]) -> Bin<u32> {
+        let mut bin = Bin::new(capacity);
+        items.iter().for_each(|&item| bin.push(item));
+        bin
+    }
+
+    #[test]
+    fn evens_out_the_loads() {
+        let mut bins = [bin(10, &[5, 4, 1]), bin(10, &[])];
+        assert!(balance(&mut bins) > 0);
+        let mut loads: Vec<u32> = bins.iter().map(load).collect();
+        loads.sort_unstable();
+        assert_eq!(loads, [5, 5]);
+
+        // a swap of the 4 for the 3 moves only a unit
+        let mut bins = [bin(10, &[6, 4]), bin(10, &[5, 3])];
+        assert_eq!(balance(&mut bins), 1);
+        assert_eq!(bins.iter().map(load).collect::<Vec<_>>(), [9, 9]);
+    }
+
+    #[test]
+    fn loads_near_the_largest_weight_do_not_overflow() {
+        let mut bins = [
+            bin(u32::MAX, &[3_000_000_000, 1_000_000_000]),
+            bin(u32::MAX, &[]),
+        ];
+        assert_eq!(balance(&mut bins), 1);
+        let loads: Vec<u32> = bins.iter().map(load).collect();
+        assert_eq!(loads, [1_000_000_000, 3_000_000_000]);
+    }
+}
@@ -0,0 +1,164 @@
+use std::{fmt, hash, iter, ops, sync::Mutex, thread, time};
+
+use crate::{
+    bounds::{self, LowerBound},
+    solver::{Budget, CancellationToken, SolveOutcome, SolveStats, Solver},
+    Bin, Fitter,
+};
+
+/// A bin packing instance: pack `items` in as few bins of `capacity` as possible.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Instance<T> {
+    pub capacity: T,
+    pub items: Vec<T>,
+}
+
+/// Writes the instance in the input format: the capacity, the items, then `0`.
+impl<T: fmt::Display> fmt::Display for Instance<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.capacity)?;
+        for item in &self.items {
+            writeln!(f, "{item}")?;
+        }
+
+        writeln!(f, "0")
+    }
+}
+
+impl Instance<u32> {
+    /// Decodes an instance from fuzzer input, the way an `arbitrary::Arbitrary` implementation
+    /// would, without depending on `arbitrary`. Every input gives a valid instance: the first
+    /// two bytes are the capacity, at least 1, and each next pair of bytes an item that fits.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut words = bytes
+            .chunks(2)
+            .map(|chunk| chunk.iter().fold(0, |word, &byte| word << 8 | byte as u32));
+
+        let capacity = words.next().unwrap_or(0).max(1);
+        Self {
+            capacity,
+            items: words.map(|word| word % capacity + 1).collect(),
+        }
+    }
+}
+
+/// How [`solve_many`] runs.
+#[derive(Clone, Debug, Default)]
+pub struct BatchConfig {
+    /// Instances solved at the same time, 0 and 1 solve them one by one on the calling thread.
+    pub threads: usize,
+    /// Time limit of each instance.
+    pub timeout: Option<time::Duration>,
+    /// Stops every solve, the instances that haven't started yet return right away.
+    pub cancellation: Option<CancellationToken>,
+}
+
+/// The result of one instance of [`solve_many`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome<T> {
+    /// The packing in the fewest bins found, `None` if none was found.
+    pub bins: Option<Vec<Bin<T>>>,
+    /// Whether `bins` is proven to use the fewest bins, or to not exist.
+    pub optimal: bool,
+    pub stats: SolveStats,
+}
+
+/// Solves every instance, the outcomes are in the order of the instances.
+pub fn solve_many<T>(
+    instances: impl IntoIterator<Item = Instance<T>>,
+    config: &BatchConfig,
+) -> Vec<Outcome<T>>
+where
+    T: Ord + Clone + hash::Hash + Send + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    solve_many_with_bounds(instances, config, &[])
+}
+
+/// Like [`solve_many`], also stopping once the number of bins reaches one of `bounds`.
+pub fn solve_many_with_bounds<T>(
+    instances: impl IntoIterator<Item = Instance<T>>,
+    config: &BatchConfig,
+    bounds: &[&(dyn LowerBound<T> + Sync)],
+) -> Vec<Outcome<T>>
+where
+    T: Ord + Clone + hash::Hash + Send + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    let instances: Vec<_> = instances.into_iter().enumerate().collect();
+    let num_instances = instances.len();
+    if config.threads <= 1 {
+        return instances
+            .into_iter()
+            .map(|(_, instance)| solve_one(instance, config, bounds))
+            .collect();
+    }
+
+    let queue = Mutex::new(instances.into_iter());
+    let outcomes = Mutex::new(Vec::with_capacity(num_instances));
+    thread::scope(|scope| {
+        for _ in 0..config.threads.min(num_instances) {
+            scope.spawn(|| loop {
+                // the lock is released before solving
+                let Some((idx, instance)) = queue.lock().unwrap().next() else {
+                    break;
+                };
+
+                let outcome = solve_one(instance, config, bounds);
+                outcomes.lock().unwrap().push((idx, outcome));
+            });
+        }
+    });
+
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|&(idx, _)| idx);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// Minimizes the number of bins, starting with one bin per item.
+fn solve_one<T>(
+    instance: Instance<T>,
+    config: &BatchConfig,
+    bounds: &[&(dyn LowerBound<T> + Sync)],
+) -> Outcome<T>
+where
+    T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    let budget = Budget {
+        deadline: config.timeout.map(|timeout| time::Instant::now() + timeout),
+        cancellation: config.cancellation.clone(),
+    };
+
+    let lower_bound = bounds::lower_bound(&instance.items, &instance.capacity, bounds);
+    let mut max_bins = instance.items.len();
+    let mut solver = Fitter::with_bins(instance.items, Vec::new());
+    let mut best = None;
+    let optimal = loop {
+        if max_bins < lower_bound {
+            break true;
+        }
+
+        solver.set_bins(vec![Bin::new(instance.capacity.clone()); max_bins]);
+        match solver.solve(budget.clone()) {
+            SolveOutcome::Budget => break false,
+            SolveOutcome::Exhausted => break true,
+            SolveOutcome::Solved => {
+                let bins = solver.packed_bins();
+                let Some(fewer) = bins.len().checked_sub(1) else {
+                    best = Some(bins);
+                    break true;
+                };
+
+                max_bins = fewer;
+                best = Some(bins);
+            }
+        }
+    };
+
+    Outcome {
+        bins: best,
+        optimal,
+        stats: *solver.stats(),
+    }
+}
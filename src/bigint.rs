@@ -0,0 +1,276 @@
+use alloc::{vec, vec::Vec};
+use core::{cmp, error, fmt, iter, ops, str};
+
+/// A non-negative integer of any size, for weights that outgrow a `u64` like byte counts.
+///
+/// Kept as base 2^32 digits, least significant first, without leading zeros, so equal values
+/// have equal digits.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+/// The largest power of ten in a digit, what parsing and printing go by.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+impl BigUint {
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    fn normalize(&mut self) {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+    }
+
+    /// Multiplies by `factor` and adds `term`.
+    fn mul_add(&mut self, factor: u32, term: u32) {
+        let mut carry = term as u64;
+        for digit in &mut self.digits {
+            let value = *digit as u64 * factor as u64 + carry;
+            *digit = value as u32;
+            carry = value >> 32;
+        }
+
+        self.digits.push(carry as u32);
+        self.normalize();
+    }
+
+    /// Divides by `divisor`, returning the remainder.
+    fn div_rem(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0;
+        for digit in self.digits.iter_mut().rev() {
+            let value = (rem as u64) << 32 | *digit as u64;
+            *digit = (value / divisor as u64) as u32;
+            rem = (value % divisor as u64) as u32;
+        }
+
+        self.normalize();
+        rem
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut value = Self {
+            digits: vec![value as u32, (value >> 32) as u32],
+        };
+
+        value.normalize();
+        value
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        // without leading zeros, more digits is larger
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl<'a> iter::Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut sum, value| {
+            sum += value;
+            sum
+        })
+    }
+}
+
+impl ops::AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        if self.digits.len() < rhs.digits.len() {
+            self.digits.resize(rhs.digits.len(), 0);
+        }
+
+        let mut carry = false;
+        for (idx, digit) in self.digits.iter_mut().enumerate() {
+            let (sum, overflow) = digit.overflowing_add(rhs.digits.get(idx).copied().unwrap_or(0));
+            let (sum, carried) = sum.overflowing_add(carry as u32);
+            *digit = sum;
+            carry = overflow || carried;
+
+            if !carry && idx >= rhs.digits.len() {
+                break;
+            }
+        }
+
+        if carry {
+            self.digits.push(1);
+        }
+    }
+}
+
+/// Panics if `rhs` is larger, weights are never negative.
+impl ops::SubAssign<&BigUint> for BigUint {
+    fn sub_assign(&mut self, rhs: &BigUint) {
+        assert!(&*self >= rhs, "big integer weight underflow");
+
+        let mut borrow = false;
+        for (idx, digit) in self.digits.iter_mut().enumerate() {
+            let (diff, overflow) = digit.overflowing_sub(rhs.digits.get(idx).copied().unwrap_or(0));
+            let (diff, borrowed) = diff.overflowing_sub(borrow as u32);
+            *digit = diff;
+            borrow = overflow || borrowed;
+
+            if !borrow && idx >= rhs.digits.len() {
+                break;
+            }
+        }
+
+        self.normalize();
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem(DECIMAL_BASE));
+        }
+
+        let Some((first, rest)) = chunks.split_last() else {
+            return f.write_str("0");
+        };
+
+        write!(f, "{first}")?;
+        for chunk in rest.iter().rev() {
+            write!(f, "{chunk:0DECIMAL_DIGITS$}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBigUintError {
+    Empty,
+    InvalidDigit,
+}
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("cannot parse integer from empty string"),
+            Self::InvalidDigit => f.write_str("invalid digit found in string"),
+        }
+    }
+}
+
+impl error::Error for ParseBigUintError {}
+
+/// Parses decimal digits, with an optional `+` like the primitive integers.
+impl str::FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix('+').unwrap_or(s).as_bytes();
+        if digits.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+
+        let mut value = Self::default();
+        for chunk in digits.chunks(DECIMAL_DIGITS) {
+            let mut term = 0;
+            for &digit in chunk {
+                if !digit.is_ascii_digit() {
+                    return Err(ParseBigUintError::InvalidDigit);
+                }
+
+                term = term * 10 + (digit - b'0') as u32;
+            }
+
+            value.mul_add(10u32.pow(chunk.len() as u32), term);
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::string::ToString;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints() {
+        for s in [
+            "0",
+            "7",
+            "4294967296",
+            "18446744073709551616",
+            "1000000000000000000000000",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+
+        assert_eq!(big("+0012"), BigUint::from(12));
+        assert_eq!(big("000").digits, []);
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError::Empty));
+        assert_eq!("+".parse::<BigUint>(), Err(ParseBigUintError::Empty));
+        assert_eq!(
+            "1x".parse::<BigUint>(),
+            Err(ParseBigUintError::InvalidDigit)
+        );
+        assert_eq!(
+            "-1".parse::<BigUint>(),
+            Err(ParseBigUintError::InvalidDigit)
+        );
+    }
+
+    #[test]
+    fn carries_and_borrows_across_digits() {
+        let mut value = BigUint::from(u64::MAX);
+        value += &BigUint::from(1);
+        assert_eq!(value, big("18446744073709551616"));
+
+        value -= &BigUint::from(1);
+        assert_eq!(value, BigUint::from(u64::MAX));
+
+        value -= &BigUint::from(u64::MAX);
+        assert!(value.is_zero());
+        assert_eq!(value, BigUint::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "underflow")]
+    fn subtracting_more_panics() {
+        let mut value = BigUint::from(1);
+        value -= &big("18446744073709551616");
+    }
+
+    #[test]
+    fn orders_by_value() {
+        let mut values = ["18446744073709551616", "0", "4294967295", "4294967296", "9"].map(big);
+        values.sort();
+        let values = values.map(|value| value.to_string());
+        assert_eq!(
+            values,
+            ["0", "9", "4294967295", "4294967296", "18446744073709551616"]
+        );
+    }
+
+    #[test]
+    fn sums() {
+        let values = [u64::MAX, u64::MAX, 2].map(BigUint::from);
+        assert_eq!(values.iter().sum::<BigUint>(), big("36893488147419103232"));
+    }
+}
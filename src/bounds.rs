@@ -0,0 +1,257 @@
+//! Lower bounds on the number of bins, see [`LowerBound`].
+
+use alloc::vec::Vec;
+use core::{iter, ops};
+
+/// The fewest bins of one capacity a packing of some items could use, so a search for fewer
+/// bins can be skipped.
+pub trait LowerBound<T> {
+    /// `usize::MAX` if an item is larger than `capacity`, as no number of bins holds it.
+    fn lower_bound(&self, items: &[T], capacity: &T) -> usize;
+}
+
+/// The total weight over the capacity, rounded up.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Trivial;
+
+/// Martello and Toth's L2: the items over half the capacity each need their own bin, and the
+/// smaller items have to fit in what's left of those bins or in new ones.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MartelloToth;
+
+fn zero<T: for<'a> iter::Sum<&'a T>>() -> T {
+    iter::empty::<&T>().sum()
+}
+
+/// A sum of weights counted in whole capacities and the rest, so it is never added up past the
+/// capacity and doesn't overflow for weights near the largest value of `T`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Total<T> {
+    pub(crate) bins: usize,
+    rest: T,
+}
+
+impl<T> Total<T>
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            bins: 0,
+            rest: zero(),
+        }
+    }
+
+    /// Adds `weight`, which is at most `capacity`, and `capacity` isn't zero.
+    pub(crate) fn add(&mut self, weight: &T, capacity: &T) {
+        let mut room = capacity.clone();
+        room -= &self.rest;
+        if weight >= &room {
+            self.bins += 1;
+            self.rest = weight.clone();
+            self.rest -= &room;
+        } else {
+            self.rest += weight;
+        }
+    }
+
+    /// Takes away the smaller `other`.
+    fn sub(&mut self, other: &Self, capacity: &T) {
+        self.bins -= other.bins;
+        if self.rest < other.rest {
+            self.bins -= 1;
+            let mut borrowed = capacity.clone();
+            borrowed -= &other.rest;
+            self.add(&borrowed, capacity);
+        } else {
+            self.rest -= &other.rest;
+        }
+    }
+
+    /// The bins the total fills, rounded up.
+    fn div_ceil(&self) -> usize {
+        self.bins + usize::from(self.rest != zero())
+    }
+}
+
+/// Whether `item` is more than half of `capacity`, which it is at most.
+fn is_large<T>(item: &T, capacity: &T) -> bool
+where
+    T: Ord + Clone + for<'a> ops::SubAssign<&'a T>,
+{
+    let mut rest = capacity.clone();
+    rest -= item;
+    item > &rest
+}
+
+impl<T> LowerBound<T> for Trivial
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    fn lower_bound(&self, items: &[T], capacity: &T) -> usize {
+        if items.iter().any(|item| item > capacity) {
+            return usize::MAX;
+        }
+
+        // only empty items fit in bins without capacity
+        if capacity == &zero::<T>() {
+            return 0;
+        }
+
+        let mut total = Total::new();
+        for item in items {
+            total.add(item, capacity);
+        }
+
+        total.div_ceil()
+    }
+}
+
+impl<T> LowerBound<T> for MartelloToth
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    fn lower_bound(&self, items: &[T], capacity: &T) -> usize {
+        let trivial = Trivial.lower_bound(items, capacity);
+        if trivial == usize::MAX || trivial == 0 {
+            return trivial;
+        }
+
+        // every small item size is a threshold, and so is 0
+        let mut thresholds: Vec<&T> = items
+            .iter()
+            .filter(|item| !is_large(*item, capacity))
+            .collect();
+        thresholds.sort_unstable();
+        thresholds.dedup();
+
+        let zero = zero::<T>();
+        iter::once(&zero)
+            .chain(thresholds)
+            .map(|threshold| {
+                let mut rest = capacity.clone();
+                rest -= threshold;
+
+                // items no item of at least `threshold` fits next to
+                let alone = items.iter().filter(|&item| item > &rest).count();
+
+                let mut shared = 0;
+                let mut free = Total::new();
+                let mut small = Total::new();
+                for item in items {
+                    if item > &rest {
+                        continue;
+                    }
+
+                    if is_large(item, capacity) {
+                        shared += 1;
+                        let mut left = capacity.clone();
+                        left -= item;
+                        free.add(&left, capacity);
+                    } else if item >= threshold {
+                        small.add(item, capacity);
+                    }
+                }
+
+                let extra = if small > free {
+                    small.sub(&free, capacity);
+                    small.div_ceil()
+                } else {
+                    0
+                };
+
+                alone + shared + extra
+            })
+            .max()
+            .unwrap_or(trivial)
+            .max(trivial)
+    }
+}
+
+/// The largest of the built-in bounds and `bounds`.
+pub fn lower_bound<T, B>(items: &[T], capacity: &T, bounds: &[&B]) -> usize
+where
+    B: LowerBound<T> + ?Sized,
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    bounds
+        .iter()
+        .map(|bound| bound.lower_bound(items, capacity))
+        .fold(MartelloToth.lower_bound(items, capacity), usize::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trivial_rounds_the_total_up() {
+        assert_eq!(Trivial.lower_bound(&[], &10), 0);
+        assert_eq!(Trivial.lower_bound(&[0, 0], &10), 0);
+        assert_eq!(Trivial.lower_bound(&[3, 4, 3], &10), 1);
+        assert_eq!(Trivial.lower_bound(&[3, 4, 5], &10), 2);
+        assert_eq!(Trivial.lower_bound(&[3, 11], &10), usize::MAX);
+    }
+
+    #[test]
+    fn martello_toth_counts_large_items() {
+        // no two items over half a bin share one
+        assert_eq!(Trivial.lower_bound(&[6, 6, 6], &10), 2);
+        assert_eq!(MartelloToth.lower_bound(&[6, 6, 6], &10), 3);
+
+        // the 5s fit together but not next to a 6
+        assert_eq!(MartelloToth.lower_bound(&[6, 6, 5, 5], &10), 3);
+        assert_eq!(MartelloToth.lower_bound(&[6, 4, 6, 4], &10), 2);
+        assert_eq!(MartelloToth.lower_bound(&[4, 4, 4], &10), 2);
+        assert_eq!(MartelloToth.lower_bound(&[], &10), 0);
+        assert_eq!(MartelloToth.lower_bound(&[11], &10), usize::MAX);
+    }
+
+    #[test]
+    fn sums_near_the_largest_weight_do_not_overflow() {
+        let capacity = 4_000_000_000u32;
+        assert_eq!(Trivial.lower_bound(&[3_000_000_000; 2], &capacity), 2);
+        assert_eq!(MartelloToth.lower_bound(&[3_000_000_000; 2], &capacity), 2);
+        assert_eq!(Trivial.lower_bound(&[u32::MAX; 3], &u32::MAX), 3);
+        assert_eq!(Trivial.lower_bound(&[0, 0], &0), 0);
+
+        // four 2.1e9 items need their own bins and the two 1.9e9 items fit next to them
+        let items = [2_100_000_000, 2_100_000_000, 2_100_000_000, 2_100_000_000];
+        let items = [&items[..], &[1_900_000_000; 2]].concat();
+        assert_eq!(Trivial.lower_bound(&items, &capacity), 4);
+        assert_eq!(MartelloToth.lower_bound(&items, &capacity), 4);
+
+        // the small items add up to more than a bin, and past the room next to the large ones
+        let items = [
+            2_500_000_000,
+            2_500_000_000,
+            1_500_000_000,
+            1_500_000_000,
+            1_500_000_000,
+        ];
+        assert_eq!(MartelloToth.lower_bound(&items, &capacity), 3);
+    }
+
+    struct Fixed(usize);
+
+    impl LowerBound<u32> for Fixed {
+        fn lower_bound(&self, _: &[u32], _: &u32) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn lower_bound_takes_the_largest() {
+        let items = [6, 6, 6];
+        assert_eq!(lower_bound::<_, Fixed>(&items, &10, &[]), 3);
+        assert_eq!(lower_bound(&items, &10, &[&Fixed(1)]), 3);
+        assert_eq!(lower_bound(&items, &10, &[&Fixed(1), &Fixed(5)]), 5);
+
+        let dynamic: [&dyn LowerBound<u32>; 1] = [&Fixed(4)];
+        assert_eq!(lower_bound(&items, &10, &dynamic), 4);
+    }
+}
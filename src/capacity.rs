@@ -0,0 +1,114 @@
+use std::cmp;
+
+use crate::{Bin, Fitter};
+
+/// The total weight in `bin`, in u64 so it doesn't overflow.
+pub fn load(bin: &Bin<u32>) -> u64 {
+    bin.items.iter().map(|&item| item as u64).sum()
+}
+
+pub fn max_load(bins: &[Bin<u32>]) -> u64 {
+    bins.iter().map(load).max().unwrap_or(0)
+}
+
+/// Longest processing time first: every item goes to the least loaded of `num_bins` bins. The
+/// capacity of the bins is what's left up to the maximum load, or up to `u32::MAX` when the
+/// maximum load is larger.
+pub fn longest_processing_time(weights: &[u32], num_bins: usize) -> Vec<Bin<u32>> {
+    let mut sorted = weights.to_vec();
+    sorted.sort_by_key(|&weight| cmp::Reverse(weight));
+
+    let mut bins = vec![Bin::new(0); num_bins];
+    for weight in sorted {
+        let Some(bin) = bins.iter_mut().min_by_key(|bin| load(bin)) else {
+            break;
+        };
+
+        bin.items.push(weight);
+    }
+
+    let size = u32::try_from(max_load(&bins)).unwrap_or(u32::MAX);
+    for bin in &mut bins {
+        bin.size = size;
+        bin.capacity = (size as u64).saturating_sub(load(bin)) as u32;
+    }
+
+    bins
+}
+
+/// `bin` with the weights of the input again, a capacity past `u32::MAX` capped like in
+/// [`longest_processing_time`].
+fn narrow(bin: Bin<u64>) -> Bin<u32> {
+    let capped = |weight: u64| u32::try_from(weight).unwrap_or(u32::MAX);
+    Bin {
+        size: capped(bin.size),
+        capacity: capped(bin.capacity),
+        items: bin.items.into_iter().map(|item| item as u32).collect(),
+        label: bin.label,
+    }
+}
+
+/// Binary search for the smallest capacity that fits `weights` in as many bins as `best` has,
+/// starting from the packing in `best`. Stops early when `predicate` returns false. The
+/// capacities are tried in u64, as the smallest one can be larger than any `u32`.
+///
+/// Returns the best packing found and whether its maximum load is proven to be minimal.
+pub fn minimize_capacity(
+    weights: &[u32],
+    mut best: Vec<Bin<u32>>,
+    mut predicate: impl FnMut() -> bool,
+) -> (Vec<Bin<u32>>, bool) {
+    let num_bins = best.len();
+    let weights: Vec<u64> = weights.iter().map(|&weight| weight as u64).collect();
+    let total_weight: u64 = weights.iter().sum();
+    let max_weight = weights.iter().copied().max().unwrap_or(0);
+    let mut lower = max_weight.max(total_weight.div_ceil(num_bins.max(1) as u64));
+    let mut upper = max_load(&best);
+
+    while lower < upper {
+        let capacity = lower + (upper - lower) / 2;
+        log::info!("Trying to fit in {num_bins} bins of capacity {capacity}");
+
+        let mut solver = Fitter::new(weights.clone(), vec![capacity; num_bins]);
+        if !solver.solve_until(&mut predicate) {
+            return (best, false);
+        }
+
+        if solver.is_solved() {
+            best = solver.bins.into_iter().map(narrow).collect();
+            upper = max_load(&best);
+        } else {
+            lower = capacity + 1;
+        }
+    }
+
+    (best, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimizes_the_maximum_load() {
+        let weights = [5, 4, 3, 3, 3];
+        let best = longest_processing_time(&weights, 2);
+        assert_eq!(max_load(&best), 10);
+
+        let (best, optimal) = minimize_capacity(&weights, best, || true);
+        assert!(optimal);
+        assert_eq!(max_load(&best), 9);
+    }
+
+    #[test]
+    fn loads_past_u32_do_not_overflow() {
+        let weights = [3_000_000_000; 3];
+        let best = longest_processing_time(&weights, 2);
+        assert_eq!(max_load(&best), 6_000_000_000);
+        assert_eq!(best[0].size, u32::MAX);
+
+        let (best, optimal) = minimize_capacity(&weights, best, || true);
+        assert!(optimal);
+        assert_eq!(max_load(&best), 6_000_000_000);
+    }
+}
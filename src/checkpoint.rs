@@ -0,0 +1,431 @@
+//! A plain text checkpoint of a [`Fitter`], so a long search can be stopped and resumed later,
+//! possibly on another machine. Event hooks, pruners, the progress reporter and watch handles
+//! aren't part of it, the bins are saved in the order they are searched in.
+//!
+//! ```text
+//! fitter-checkpoint 1
+//! input <items in input order>
+//! items <unpacked items>
+//! bin <capacity> <fixed items> <symmetric> <items> [#label]   (one line per bin)
+//!                   (`\\` and whitespace in labels escaped as `\\\\` and `\\u{..}`)
+//! state <last bin or -> <next bin> <try|backtrack>     (one line per search state)
+//! exhaustive <0|1>
+//! settled <0|1>
+//! stats <nodes> <backtracks> <max depth> <restarts> <wall time ns> <fewest unpacked>
+//! ```
+
+use std::{
+    error, fmt, hash,
+    io::{self, BufRead},
+    iter, ops, str, time,
+};
+
+use crate::{progress::NoProgress, solver::SolveStats, Action, Bin, Fitter, State};
+
+const HEADER: &str = "fitter-checkpoint 1";
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    /// A line that couldn't be parsed, with its number.
+    Invalid {
+        line: usize,
+        reason: String,
+    },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => err.fmt(f),
+            Self::Invalid { line, reason } => write!(f, "invalid checkpoint line {line}: {reason}"),
+        }
+    }
+}
+
+impl error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Escapes the backslashes and whitespace of a bin label, so it stays one field.
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_whitespace() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Undoes [`escape_label`], `None` for a malformed escape.
+fn unescape_label(escaped: &str) -> Option<String> {
+    let mut label = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            label.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => label.push('\\'),
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let (hex, rest) = rest.split_once('}')?;
+                label.push(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?);
+                chars = rest.chars();
+            }
+            _ => return None,
+        }
+    }
+
+    Some(label)
+}
+
+impl<T> Fitter<T>
+where
+    T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+    T: fmt::Display + str::FromStr,
+{
+    pub fn write_checkpoint(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        writeln!(writer, "input {}", join(&self.input))?;
+        writeln!(writer, "items {}", join(&self.items))?;
+
+        for (idx, bin) in self.bins.iter().enumerate() {
+            write!(
+                writer,
+                "bin {} {} {} {}",
+                bin.capacity,
+                self.fixed[idx],
+                self.symmetric[idx] as u8,
+                join(&bin.items)
+            )?;
+
+            match &bin.label {
+                Some(label) => writeln!(writer, " #{}", escape_label(label))?,
+                None => writeln!(writer)?,
+            }
+        }
+
+        for state in &self.state_stack {
+            let last_bin = state
+                .last_bin_idx
+                .map_or_else(|| "-".to_string(), |idx| idx.to_string());
+            let action = match state.action {
+                Action::Try => "try",
+                Action::Backtrack => "backtrack",
+            };
+
+            writeln!(writer, "state {last_bin} {} {action}", state.next_bin_idx)?;
+        }
+
+        let stats = &self.stats;
+        writeln!(writer, "exhaustive {}", self.exhaustive as u8)?;
+        writeln!(writer, "settled {}", self.settled as u8)?;
+        writeln!(
+            writer,
+            "stats {} {} {} {} {} {}",
+            stats.nodes,
+            stats.backtracks,
+            stats.max_depth,
+            stats.restarts,
+            stats.wall_time.as_nanos(),
+            stats.fewest_unpacked
+        )
+    }
+
+    /// Reads a checkpoint written by [`Fitter::write_checkpoint`], the search continues where
+    /// it stopped.
+    pub fn read_checkpoint(reader: impl BufRead) -> Result<Self, CheckpointError> {
+        let mut input = Vec::new();
+        let mut items = Vec::new();
+        let mut bins = Vec::new();
+        let mut fixed = Vec::new();
+        let mut symmetric = Vec::new();
+        let mut state_stack = Vec::new();
+        let mut exhaustive = false;
+        let mut settled = false;
+        let mut stats = SolveStats::default();
+        // the lines the bins, the search states and the input came from, for the checks below
+        let (mut bin_lines, mut state_lines, mut input_line) = (Vec::new(), Vec::new(), 0);
+
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let invalid = |reason: &str| CheckpointError::Invalid {
+                line: idx + 1,
+                reason: reason.to_string(),
+            };
+
+            if idx == 0 {
+                if line.trim() != HEADER {
+                    return Err(invalid("not a fitter checkpoint"));
+                }
+
+                continue;
+            }
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let mut next = || tokens.next().ok_or_else(|| invalid("missing field"));
+            let parse_items = |tokens: &mut dyn Iterator<Item = &str>| {
+                tokens
+                    .map(|token| token.parse().map_err(|_| invalid("invalid item")))
+                    .collect::<Result<Vec<T>, _>>()
+            };
+
+            match next()? {
+                "input" => {
+                    input = parse_items(&mut line.split_whitespace().skip(1))?;
+                    input_line = idx + 1;
+                }
+                "items" => items = parse_items(&mut line.split_whitespace().skip(1))?,
+                "bin" => {
+                    let mut fields = line.split_whitespace().skip(1);
+                    let mut field = || fields.next().ok_or_else(|| invalid("missing field"));
+                    let capacity = field()?.parse().map_err(|_| invalid("invalid capacity"))?;
+                    let num_fixed = field()?.parse().map_err(|_| invalid("invalid count"))?;
+                    let is_symmetric = field()? == "1";
+
+                    let mut fields: Vec<_> = fields.collect();
+                    let label = fields.pop_if(|field| field.starts_with('#'));
+
+                    let mut bin = Bin::new(capacity);
+                    bin.items = parse_items(&mut fields.into_iter())?;
+                    bin.label = match label {
+                        Some(label) => Some(
+                            unescape_label(&label[1..]).ok_or_else(|| invalid("invalid label"))?,
+                        ),
+                        None => None,
+                    };
+                    bin.size += &bin.items.iter().sum();
+                    bins.push(bin);
+                    bin_lines.push(idx + 1);
+                    fixed.push(num_fixed);
+                    symmetric.push(is_symmetric);
+                }
+                "state" => {
+                    let last_bin_idx = match next()? {
+                        "-" => None,
+                        idx => Some(idx.parse().map_err(|_| invalid("invalid bin"))?),
+                    };
+                    let next_bin_idx = next()?.parse().map_err(|_| invalid("invalid bin"))?;
+                    let action = match next()? {
+                        "try" => Action::Try,
+                        "backtrack" => Action::Backtrack,
+                        _ => return Err(invalid("invalid action")),
+                    };
+
+                    state_stack.push(State {
+                        last_bin_idx,
+                        next_bin_idx,
+                        action,
+                    });
+                    state_lines.push(idx + 1);
+                }
+                "exhaustive" => exhaustive = next()? == "1",
+                "settled" => settled = next()? == "1",
+                "stats" => {
+                    let mut number = || -> Result<u64, CheckpointError> {
+                        next()?.parse().map_err(|_| invalid("invalid number"))
+                    };
+
+                    stats = SolveStats {
+                        nodes: number()?,
+                        backtracks: number()?,
+                        max_depth: number()? as usize,
+                        restarts: number()?,
+                        wall_time: time::Duration::from_nanos(number()?),
+                        fewest_unpacked: number()? as usize,
+                    };
+                }
+                _ => return Err(invalid("unknown line")),
+            }
+        }
+
+        let invalid = |line: usize, reason: &str| CheckpointError::Invalid {
+            line,
+            reason: reason.to_string(),
+        };
+
+        // the search pops the item of each backtrack state off its bin, and compares each bin
+        // marked symmetric to the one before it
+        let mut placed = Vec::with_capacity(bins.len());
+        for (idx, bin) in bins.iter().enumerate() {
+            if fixed[idx] > bin.items.len() {
+                return Err(invalid(bin_lines[idx], "more fixed items than items"));
+            }
+
+            if idx == 0 && symmetric[idx] {
+                return Err(invalid(bin_lines[idx], "the first bin can't be symmetric"));
+            }
+
+            placed.push(bin.items.len() - fixed[idx]);
+        }
+
+        for (pos, state) in state_stack.iter().enumerate().rev() {
+            let line = state_lines[pos];
+            if state.last_bin_idx.is_some_and(|bin| bin >= bins.len()) {
+                return Err(invalid(line, "no such bin"));
+            }
+
+            match state.action {
+                Action::Backtrack => {
+                    let bin = state.next_bin_idx.checked_sub(1);
+                    let Some(count) = bin.and_then(|bin| placed.get_mut(bin)) else {
+                        return Err(invalid(line, "no such bin"));
+                    };
+
+                    if *count == 0 {
+                        return Err(invalid(line, "no item to backtrack"));
+                    }
+
+                    *count -= 1;
+                }
+                Action::Try if pos + 1 != state_stack.len() => {
+                    return Err(invalid(line, "only the last state may be a try"));
+                }
+                Action::Try if state.next_bin_idx > bins.len() => {
+                    return Err(invalid(line, "no such bin"));
+                }
+                Action::Try => {}
+            }
+        }
+
+        // a settled search keeps the items it started from in place without states
+        if let Some(bin) = placed.iter().position(|&count| count > 0 && !settled) {
+            return Err(invalid(bin_lines[bin], "an item not placed by any state"));
+        }
+
+        // every item is in the input, either unpacked or placed by the search
+        let mut held: Vec<&T> = items.iter().collect();
+        held.extend(
+            bins.iter()
+                .zip(&fixed)
+                .flat_map(|(bin, &num)| &bin.items[num..]),
+        );
+        held.sort();
+        let mut expected: Vec<&T> = input.iter().collect();
+        expected.sort();
+        if held != expected {
+            return Err(invalid(input_line, "the items don't match the input"));
+        }
+
+        items.sort();
+        Ok(Self {
+            items,
+            bins,
+            input,
+            fixed,
+            symmetric,
+            exhaustive,
+            bin_order: Default::default(),
+            settled,
+            hooks: Vec::new(),
+            pruners: Vec::new(),
+            progress: Box::new(NoProgress),
+            watch: None,
+            stats,
+            state_stack,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{SolveLimits, SolveOutcome};
+
+    fn started() -> Fitter<u32> {
+        let bins = vec![Bin::new(13).with_label("truck 1"), Bin::new(12)];
+        let mut fitter = Fitter::with_bins(vec![5, 4, 4, 3, 3, 2, 2, 2], bins);
+        let limits = SolveLimits {
+            max_nodes: Some(3),
+            ..SolveLimits::default()
+        };
+
+        assert_eq!(fitter.solve_with_limits(limits), SolveOutcome::Budget);
+        fitter
+    }
+
+    fn checkpoint(fitter: &Fitter<u32>) -> String {
+        let mut text = Vec::new();
+        fitter.write_checkpoint(&mut text).unwrap();
+        String::from_utf8(text).unwrap()
+    }
+
+    fn read(text: &str) -> Result<Fitter<u32>, CheckpointError> {
+        Fitter::read_checkpoint(text.as_bytes())
+    }
+
+    #[test]
+    fn resumes_where_it_stopped() {
+        let mut fitter = started();
+        let text = checkpoint(&fitter);
+        let mut resumed = read(&text).unwrap();
+        assert_eq!(checkpoint(&resumed), text);
+        assert_eq!(resumed.bins[0].label.as_deref(), Some("truck 1"));
+
+        let outcome = fitter.solve_with_limits(SolveLimits::default());
+        assert_eq!(outcome, SolveOutcome::Solved);
+        assert_eq!(resumed.solve_with_limits(SolveLimits::default()), outcome);
+        assert_eq!(resumed.bins, fitter.bins);
+        assert_eq!(resumed.solution(), fitter.solution());
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        for label in ["truck 1", "a\tb\\c", "\\u{20}", ""] {
+            assert_eq!(unescape_label(&escape_label(label)).as_deref(), Some(label));
+            assert!(!escape_label(label).contains(char::is_whitespace));
+        }
+
+        assert_eq!(unescape_label("a\\x"), None);
+        assert_eq!(unescape_label("a\\u{zz}"), None);
+    }
+
+    #[test]
+    fn rejects_states_out_of_bounds() {
+        let text = checkpoint(&started());
+        let corrupt = |from: &str, to: &str| {
+            assert!(text.contains(from), "{from:?} in {text}");
+            text.replacen(from, to, 1)
+        };
+
+        for text in [
+            corrupt("state 0 1 backtrack", "state 0 9 backtrack"),
+            corrupt("state 0 1 backtrack", "state 9 1 backtrack"),
+            corrupt("state 0 1 backtrack", "state 0 0 backtrack"),
+            corrupt("state 0 1 backtrack\n", ""),
+            corrupt("state - 0 try", "state - 0 try\nstate 0 1 backtrack"),
+            corrupt("bin 12 0 0", "bin 12 9 0"),
+            corrupt("bin 0 0 0", "bin 0 0 1"),
+            corrupt("input 5", "input 6"),
+        ] {
+            assert!(
+                matches!(read(&text), Err(CheckpointError::Invalid { .. })),
+                "{text}"
+            );
+        }
+    }
+}
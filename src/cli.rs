@@ -0,0 +1,35 @@
+//! The parts of the command line tool beyond its arguments and the default mode: the formats
+//! it reads and writes, its subcommands and its other modes.
+
+pub mod bench;
+pub mod bounds;
+pub mod bpplib;
+pub mod check;
+pub mod completions;
+pub mod config;
+pub mod constrained;
+pub mod count;
+pub mod cover;
+pub mod crosscheck;
+pub mod csv;
+pub mod decimal;
+pub mod dot;
+pub mod geom2d;
+pub mod interactive;
+pub mod jobs;
+pub mod json;
+pub mod knapsack;
+pub mod labels;
+pub mod makespan;
+pub mod min_capacity;
+pub mod nested;
+pub mod overflow;
+pub mod plain;
+pub mod priorities;
+pub mod repack;
+pub mod shrink;
+pub mod split;
+pub mod stats;
+pub mod svg;
+pub mod validate;
+pub mod weights;
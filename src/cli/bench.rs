@@ -0,0 +1,92 @@
+use std::{fs, io, iter, path, time};
+
+use fitter::batch;
+
+use crate::{has_instance_left, parse_instance, InputArgs, INTERRUPT};
+
+/// Solves every instance in the files of `dir`, in file name order, and prints a row for each
+/// with how it ended, the fewest bins found, the time taken and the nodes searched.
+pub fn run(dir: &path::Path, timeout: time::Duration, jobs: usize) -> anyhow::Result<()> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    let mut names = Vec::new();
+    let mut instances = Vec::new();
+    for path in &paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let mut stream = io::BufReader::new(fs::File::open(path)?);
+        let mut count = 0;
+        while has_instance_left(&mut stream)? {
+            match parse_instance::<u32>(&mut stream, &InputArgs::default()) {
+                Ok((capacity, items)) => instances.push(batch::Instance { capacity, items }),
+                Err(err) => {
+                    log::warn!("Skipping {name}: {err}");
+                    break;
+                }
+            }
+
+            count += 1;
+            names.push(match count {
+                1 => name.to_string(),
+                _ => format!("{name}#{count}"),
+            });
+        }
+    }
+
+    let config = batch::BatchConfig {
+        threads: jobs,
+        timeout: Some(timeout),
+        cancellation: INTERRUPT.get().cloned(),
+    };
+
+    let rows: Vec<[String; 5]> = names
+        .into_iter()
+        .zip(batch::solve_many(instances, &config))
+        .map(|(name, outcome)| {
+            let status = match (&outcome.bins, outcome.optimal) {
+                (Some(_), true) => "OPTIMAL",
+                (Some(_), false) => "SAT",
+                (None, true) => "UNSAT",
+                (None, false) => "UNKNOWN",
+            };
+
+            let bins = outcome
+                .bins
+                .map_or("-".to_string(), |bins| bins.len().to_string());
+            let time = format!("{:.3}", outcome.stats.wall_time.as_secs_f64());
+            [
+                name,
+                status.to_string(),
+                bins,
+                time,
+                outcome.stats.nodes.to_string(),
+            ]
+        })
+        .collect();
+
+    let header = ["instance", "status", "bins", "time", "nodes"].map(String::from);
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[col].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in iter::once(&header).chain(&rows) {
+        let [name, cells @ ..] = row;
+        let mut line = format!("{name:<0$}", widths[0]);
+        for (cell, width) in cells.iter().zip(&widths[1..]) {
+            line += &format!("  {cell:>width$}");
+        }
+
+        println!("{line}");
+    }
+
+    Ok(())
+}
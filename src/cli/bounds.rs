@@ -0,0 +1,56 @@
+use std::io::BufRead;
+
+use fitter::{
+    bounds::{self, LowerBound},
+    heuristics::{FitRule, Greedy},
+    solver::{Budget, Solver},
+};
+
+use super::json::Json;
+use crate::{parse_instance, require_format, Args, Format};
+
+/// The lower and upper bounds on the number of bins for `--bound-only`.
+pub fn print(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    require_format(
+        args,
+        "--bound-only",
+        &[Format::Text, Format::Json, Format::Jsonl],
+    )?;
+    let (capacity, weights) = parse_instance::<u32>(stream, &args.input)?;
+    if let Some(&item) = weights.iter().find(|&&weight| weight > capacity) {
+        anyhow::bail!("item {item} is larger than the bin capacity {capacity}");
+    }
+
+    let lower = [
+        ("L1", bounds::Trivial.lower_bound(&weights, &capacity)),
+        ("L2", bounds::MartelloToth.lower_bound(&weights, &capacity)),
+    ];
+
+    let upper = [("FFD", FitRule::First), ("BFD", FitRule::Best)].map(|(name, rule)| {
+        let mut greedy = Greedy::new(weights.clone(), vec![capacity; weights.len()], rule);
+        greedy.solve(Budget::unlimited());
+        let used = greedy.bins.iter().filter(|bin| !bin.is_empty()).count();
+        (name, used)
+    });
+
+    let to_json = |bounds: &[(&str, usize)]| {
+        Json::object(bounds.iter().map(|&(name, bound)| (name, bound.into())))
+    };
+
+    match args.format {
+        Format::Json | Format::Jsonl => {
+            let result = Json::object([("lower", to_json(&lower)), ("upper", to_json(&upper))]);
+            match args.format {
+                Format::Json => println!("{result:#}"),
+                _ => println!("{result}"),
+            }
+        }
+        _ => {
+            for (name, bound) in lower.iter().chain(&upper) {
+                println!("b {name} {bound}");
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::{collections::VecDeque, error, io::BufRead, str};
+
+use crate::{is_comment, EOFError};
+
+/// Parses the BPPLIB and OR-Library layout: the number of items, the bin capacity, then the
+/// weight of each item, usually one per line.
+pub fn parse_instance<B, I>(reader: &mut impl BufRead) -> anyhow::Result<(Vec<B>, Vec<I>)>
+where
+    B: str::FromStr,
+    B::Err: error::Error + Send + Sync + 'static,
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    let mut tokens = VecDeque::new();
+    let mut next_token = || -> anyhow::Result<String> {
+        while tokens.is_empty() {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                Err(EOFError)?;
+            }
+
+            if !is_comment(&line) {
+                tokens.extend(line.split_whitespace().map(str::to_owned));
+            }
+        }
+
+        Ok(tokens.pop_front().unwrap())
+    };
+
+    let count = next_token()?.parse::<usize>().map_err(anyhow::Error::new)?;
+    let capacity = next_token()?.parse::<B>().map_err(anyhow::Error::new)?;
+    let mut items = Vec::with_capacity(count);
+    for _ in 0..count {
+        items.push(next_token()?.parse::<I>()?);
+    }
+
+    log::trace!("count={count}");
+    Ok((vec![capacity], items))
+}
@@ -0,0 +1,43 @@
+use std::{io::BufRead, path};
+
+use fitter::{batch, verify};
+
+use crate::{parse_multi_instance, parse_solution_file, InputArgs};
+
+/// Checks the packing in `path` against the instance, listing every violation on an `e` line.
+pub fn run(stream: &mut impl BufRead, path: &path::Path, input: &InputArgs) -> anyhow::Result<()> {
+    let (capacities, items) = parse_multi_instance::<u32>(stream, input)?;
+    let [capacity] = capacities[..] else {
+        anyhow::bail!("expected a single bin capacity, found {}", capacities.len());
+    };
+
+    // checked in u64, so the load of an overfull bin is counted in full
+    let capacity = capacity as u64;
+    let bins: Vec<_> = parse_solution_file(path)?
+        .into_iter()
+        .map(|items| {
+            let items: Vec<u64> = items.into_iter().map(u64::from).collect();
+            fitter::Bin {
+                size: capacity,
+                capacity: capacity.saturating_sub(items.iter().sum()),
+                items,
+                label: None,
+            }
+        })
+        .collect();
+
+    let items = items.into_iter().map(u64::from).collect();
+    let instance = batch::Instance { capacity, items };
+    let violations = verify::violations(&bins, &instance);
+    if violations.is_empty() {
+        println!("s VALID");
+        println!("o {}", bins.iter().filter(|bin| !bin.is_empty()).count());
+    } else {
+        println!("s INVALID");
+        violations
+            .iter()
+            .for_each(|violation| println!("e {violation}"));
+    }
+
+    Ok(())
+}
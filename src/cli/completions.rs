@@ -0,0 +1,259 @@
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// An option of a command for [`print`].
+struct CompletedOption {
+    long: Vec<String>,
+    short: Option<char>,
+    /// The first line of the help.
+    help: String,
+    /// Whether the option takes a value, completed from `values` if there are, as a file
+    /// otherwise.
+    takes_value: bool,
+    values: Vec<String>,
+    /// Whether the option can be repeated, like `-vv`.
+    counted: bool,
+}
+
+/// The options of `command` that aren't hidden or positional.
+fn completed_options(command: &clap::Command) -> Vec<CompletedOption> {
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+        .map(|arg| {
+            let long = arg.get_long().into_iter().map(str::to_string);
+            let aliases = arg.get_visible_aliases().into_iter().flatten();
+            let help = arg.get_help().map(ToString::to_string).unwrap_or_default();
+            let takes_value = arg.get_action().takes_values();
+            CompletedOption {
+                long: long.chain(aliases.map(str::to_string)).collect(),
+                short: arg.get_short(),
+                help: help.lines().next().unwrap_or_default().to_string(),
+                takes_value,
+                values: match takes_value {
+                    true => arg
+                        .get_possible_values()
+                        .iter()
+                        .filter(|value| !value.is_hide_set())
+                        .map(|value| value.get_name().to_string())
+                        .collect(),
+                    false => Vec::new(),
+                },
+                counted: matches!(arg.get_action(), clap::ArgAction::Count),
+            }
+        })
+        .collect()
+}
+
+/// Writes the completion script of the `completions` subcommand for `shell`, made from the
+/// options and subcommands of `command`.
+pub fn print(shell: Shell, mut command: clap::Command) {
+    command.build();
+    let name = command.get_name().to_string();
+    let subcommands: Vec<(String, String, Vec<CompletedOption>)> = command
+        .get_subcommands()
+        .map(|subcommand| {
+            let about = subcommand.get_about().map(ToString::to_string);
+            let about = about
+                .unwrap_or_default()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            (
+                subcommand.get_name().to_string(),
+                about,
+                completed_options(subcommand),
+            )
+        })
+        .collect();
+    let options = completed_options(&command);
+
+    match shell {
+        Shell::Bash => print_bash_completions(&name, &options, &subcommands),
+        Shell::Zsh => print_zsh_completions(&name, &options, &subcommands),
+        Shell::Fish => print_fish_completions(&name, &options, &subcommands),
+    }
+}
+
+/// The `--long` and `-s` forms of each of `options`.
+fn option_words(options: &[CompletedOption]) -> Vec<String> {
+    options
+        .iter()
+        .flat_map(|option| {
+            let long = option.long.iter().map(|long| format!("--{long}"));
+            long.chain(option.short.map(|short| format!("-{short}")))
+        })
+        .collect()
+}
+
+fn print_bash_completions(
+    name: &str,
+    options: &[CompletedOption],
+    subcommands: &[(String, String, Vec<CompletedOption>)],
+) {
+    let function = format!("_{}", name.replace('-', "_"));
+    let names: Vec<&str> = subcommands.iter().map(|(name, ..)| name.as_str()).collect();
+    println!("{function}() {{");
+    println!(
+        "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\""
+    );
+    println!("    local command=\"\" word");
+    println!("    for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do");
+    println!("        case \"$word\" in");
+    println!("            {}) command=\"$word\" ;;", names.join("|"));
+    println!("        esac");
+    println!("    done");
+    println!();
+
+    let all = options
+        .iter()
+        .chain(subcommands.iter().flat_map(|(_, _, options)| options));
+    println!("    case \"$prev\" in");
+    for option in all.filter(|option| !option.values.is_empty()) {
+        let words = option_words(std::slice::from_ref(option)).join("|");
+        println!("        {words})");
+        let values = option.values.join(" ");
+        println!("            COMPREPLY=($(compgen -W \"{values}\" -- \"$cur\"))");
+        println!("            return ;;");
+    }
+    println!("    esac");
+    println!();
+
+    println!("    local words");
+    println!("    case \"$command\" in");
+    for (subcommand, _, options) in subcommands {
+        println!(
+            "        {subcommand}) words=\"{}\" ;;",
+            option_words(options).join(" ")
+        );
+    }
+    let words = option_words(options).join(" ");
+    println!("        *) words=\"{words} {}\" ;;", names.join(" "));
+    println!("    esac");
+    println!();
+    println!("    if [[ \"$cur\" == -* || -z \"$command\" ]]; then");
+    println!("        COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))");
+    println!("    fi");
+    println!("    COMPREPLY+=($(compgen -f -- \"$cur\"))");
+    println!("}}");
+    println!();
+    println!("complete -F {function} {name}");
+}
+
+/// `text` in zsh's single quotes, with the characters of `_arguments` specs escaped too.
+fn zsh_quoted(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+/// The `_arguments` specs of `options`.
+fn zsh_specs(options: &[CompletedOption]) -> Vec<String> {
+    let mut specs = Vec::new();
+    for option in options {
+        let help = zsh_quoted(&option.help);
+        let action = match (&option.values[..], option.takes_value) {
+            (_, false) => String::new(),
+            ([], true) => ":value:_files".to_string(),
+            (values, true) => format!(":value:({})", values.join(" ")),
+        };
+        let repeat = if option.counted { "*" } else { "" };
+        for long in &option.long {
+            let equals = if option.takes_value { "=" } else { "" };
+            specs.push(format!("'{repeat}--{long}{equals}[{help}]{action}'"));
+        }
+
+        if let Some(short) = option.short {
+            specs.push(format!("'{repeat}-{short}[{help}]{action}'"));
+        }
+    }
+
+    specs
+}
+
+fn print_zsh_completions(
+    name: &str,
+    options: &[CompletedOption],
+    subcommands: &[(String, String, Vec<CompletedOption>)],
+) {
+    let function = format!("_{}", name.replace('-', "_"));
+    println!("#compdef {name}");
+    println!();
+    println!("{function}() {{");
+    println!("    local context state state_descr line");
+    println!("    typeset -A opt_args");
+    println!("    _arguments -C \\");
+    for spec in zsh_specs(options) {
+        println!("        {spec} \\");
+    }
+    println!("        '1: :->command' \\");
+    println!("        '*:: :->args'");
+    println!();
+    println!("    case $state in");
+    println!("        command)");
+    let commands: Vec<String> = subcommands
+        .iter()
+        .map(|(name, about, _)| format!("'{name}:{}'", zsh_quoted(about)))
+        .collect();
+    println!("            local commands=({})", commands.join(" "));
+    println!("            _describe command commands");
+    println!("            _files ;;");
+    println!("        args)");
+    println!("            case $line[1] in");
+    for (subcommand, _, options) in subcommands {
+        let specs = zsh_specs(options);
+        println!(
+            "                {subcommand}) _arguments {} '*:file:_files' ;;",
+            specs.join(" ")
+        );
+    }
+    println!("                *) _files ;;");
+    println!("            esac ;;");
+    println!("    esac");
+    println!("}}");
+    println!();
+    println!("{function} \"$@\"");
+}
+
+/// The `complete` lines of `options` for fish, under `condition`.
+fn print_fish_options(name: &str, condition: &str, options: &[CompletedOption]) {
+    for option in options {
+        let mut line = format!("complete -c {name} -n '{condition}'");
+        for long in &option.long {
+            line += &format!(" -l {long}");
+        }
+
+        if let Some(short) = option.short {
+            line += &format!(" -s {short}");
+        }
+
+        match (&option.values[..], option.takes_value) {
+            (_, false) => {}
+            ([], true) => line += " -r -F",
+            (values, true) => line += &format!(" -x -a '{}'", values.join(" ")),
+        }
+
+        line += &format!(" -d '{}'", option.help.replace('\'', "\\'"));
+        println!("{line}");
+    }
+}
+
+fn print_fish_completions(
+    name: &str,
+    options: &[CompletedOption],
+    subcommands: &[(String, String, Vec<CompletedOption>)],
+) {
+    print_fish_options(name, "__fish_use_subcommand", options);
+    for (subcommand, about, options) in subcommands {
+        let about = about.replace('\'', "\\'");
+        println!("complete -c {name} -n __fish_use_subcommand -a {subcommand} -d '{about}'");
+        let condition = format!("__fish_seen_subcommand_from {subcommand}");
+        print_fish_options(name, &condition, options);
+    }
+}
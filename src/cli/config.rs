@@ -0,0 +1,138 @@
+use std::{env, ffi, fs, iter, path};
+
+/// The config file `--config` names in `argv`, or the one in the config directory if there is.
+pub fn path(argv: &[ffi::OsString]) -> Option<path::PathBuf> {
+    let mut given = argv.iter().skip(1);
+    while let Some(arg) = given.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--" {
+            break;
+        }
+
+        if arg == "--config" {
+            return given.next().map(path::PathBuf::from);
+        }
+
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.into());
+        }
+    }
+
+    let dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => path::PathBuf::from(dir),
+        None => path::PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(dir.join("fitter.toml")).filter(|path| path.is_file())
+}
+
+/// The options set in the config file at `path` as arguments. Only the `key = value` lines of
+/// TOML are read, with a string, number, boolean or bare word value.
+pub fn args(path: &path::Path) -> anyhow::Result<Vec<ffi::OsString>> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("can't read the config {}: {err}", path.display()))?;
+
+    let mut args = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let invalid = |message: &str| anyhow::anyhow!("{}:{}: {message}", path.display(), idx + 1);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(invalid("expected key = value, tables aren't supported"));
+        };
+
+        let key = key.trim().replace('_', "-");
+        let value = parse_value(value.trim())
+            .ok_or_else(|| invalid("expected a string, number, boolean or word"))?;
+        match (key.as_str(), value.as_str()) {
+            (_, "false") => {}
+            (_, "true") => args.push(format!("--{key}").into()),
+            // the counted flags of the log level
+            ("verbose" | "quiet", count) => {
+                let count: usize = count
+                    .parse()
+                    .map_err(|_| invalid("expected the number of times to repeat the flag"))?;
+                args.extend(iter::repeat_n(format!("--{key}").into(), count));
+            }
+            (_, value) => args.push(format!("--{key}={value}").into()),
+        }
+    }
+
+    Ok(args)
+}
+
+/// The TOML string, number or boolean `value`, or a bare word, with the quotes of a string, the
+/// underscores of a number and any comment after it taken off.
+fn parse_value(value: &str) -> Option<String> {
+    let mut chars = value.chars();
+    match chars.next()? {
+        '\'' => {
+            let (string, rest) = chars.as_str().split_once('\'')?;
+            is_comment(rest).then(|| string.to_string())
+        }
+        '"' => {
+            let mut string = String::new();
+            while let Some(char) = chars.next() {
+                match char {
+                    '"' => return is_comment(chars.as_str()).then_some(string),
+                    '\\' => string.push(match chars.next()? {
+                        'n' => '\n',
+                        't' => '\t',
+                        other => other,
+                    }),
+                    other => string.push(other),
+                }
+            }
+
+            None
+        }
+        _ => {
+            let value = value.split('#').next()?.trim();
+            let bare = value
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || "+-._".contains(char));
+
+            // underscores only separate the digits of numbers, words keep theirs
+            let digits = value.trim_start_matches(['+', '-']);
+            match digits.starts_with(|char: char| char.is_ascii_digit()) {
+                true => bare.then(|| value.replace('_', "")),
+                false => bare.then(|| value.to_string()),
+            }
+        }
+    }
+}
+
+/// Whether `rest` is empty but for a comment.
+fn is_comment(rest: &str) -> bool {
+    let rest = rest.trim();
+    rest.is_empty() || rest.starts_with('#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_values() {
+        let value = |text| parse_value(text);
+        assert_eq!(value("\"a # b\" # comment"), Some("a # b".into()));
+        assert_eq!(
+            value(r#""line\n\"quoted\"""#),
+            Some("line\n\"quoted\"".into())
+        );
+        assert_eq!(value("'C:\\dir' "), Some("C:\\dir".into()));
+        assert_eq!(value("1_000 # ms"), Some("1000".into()));
+        assert_eq!(value("-1_0.5"), Some("-10.5".into()));
+        assert_eq!(value("true"), Some("true".into()));
+        assert_eq!(value("max_rects"), Some("max_rects".into()));
+        assert_eq!(value("my_file.txt"), Some("my_file.txt".into()));
+
+        assert_eq!(value("\"open"), None);
+        assert_eq!(value("'a' b"), None);
+        assert_eq!(value("[1, 2]"), None);
+        assert_eq!(value("a/b"), None);
+    }
+}
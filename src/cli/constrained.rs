@@ -0,0 +1,220 @@
+use core::fmt;
+use std::{io::BufRead, time};
+
+use fitter::{
+    constrained::{
+        ClassConstraint, ColorConstraint, ConstrainedPacker, Constraint, FragileConstraint,
+        GroupConstraint, MinFillConstraint, TemporalConstraint,
+    },
+    SolutionState,
+};
+
+use crate::{
+    budget, label_ids, parse_bins_instance, parse_input, parse_instance, print_status,
+    solve_deadline, Args, FragileItem, GroupLimit, LabeledItem, MinFill, TemporalItem,
+};
+
+/// The bin count optimization loop of [`crate::solve_single_input`] over a [`ConstrainedPacker`].
+fn solve_constrained(
+    weights: &[u32],
+    bin_capacity: u32,
+    constraint: impl Constraint,
+    args: &Args,
+) -> SolutionState<Vec<Vec<usize>>> {
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+    let mut solution = SolutionState::Unknown;
+    let mut max_bins = weights.len();
+    let total_weight: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    loop {
+        log::info!("Trying to fit in {max_bins} bins");
+
+        if constraint.additive() && total_weight > bin_capacity as u64 * max_bins as u64 {
+            solution.insert(SolutionState::Unsolvable);
+            break;
+        }
+
+        let packer =
+            ConstrainedPacker::new(weights.to_vec(), vec![bin_capacity; max_bins], &constraint);
+        let budget = budget(deadline);
+        let packing = packer.solve_until(|| budget.remains());
+
+        match packing {
+            SolutionState::Solved(bins) => {
+                let bins: Vec<_> = bins.into_iter().filter(|bin| !bin.is_empty()).collect();
+                max_bins = bins.len().saturating_sub(1);
+                solution = SolutionState::Solved(bins);
+                if max_bins == 0 || !args.minimize {
+                    break;
+                }
+            }
+            SolutionState::Unsolvable => {
+                solution.insert(SolutionState::Unsolvable);
+                break;
+            }
+            SolutionState::Unknown => break,
+        }
+    }
+
+    solution
+}
+
+fn print_constrained_solution(
+    solution: &SolutionState<Vec<Vec<usize>>>,
+    items: &[impl fmt::Display],
+    args: &Args,
+) {
+    match solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(bins) => {
+            print_status("SAT");
+
+            if args.values {
+                for bin in bins {
+                    let line = bin
+                        .iter()
+                        .map(|&idx| items[idx].to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+
+                    println!("v {}", line);
+                }
+            }
+        }
+    }
+}
+
+pub fn solve_colors(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (bin_capacity, items) = parse_instance::<LabeledItem>(stream, &args.input)?;
+    let weights: Vec<u32> = items.iter().map(|item| item.weight).collect();
+
+    let mut constraint = ColorConstraint::new(label_ids(&items));
+    constraint.max_per_bin = args.max_per_color;
+    constraint.alternate = args.alternate_colors;
+
+    let mut solution = solve_constrained(&weights, bin_capacity, &constraint, args);
+    if let SolutionState::Solved(bins) = &mut solution {
+        bins.iter_mut()
+            .for_each(|bin| *bin = constraint.arrange(bin));
+    }
+
+    print_constrained_solution(&solution, &items, args);
+
+    Ok(())
+}
+
+pub fn solve_classes(
+    stream: &mut impl BufRead,
+    max_classes: usize,
+    args: &Args,
+) -> anyhow::Result<()> {
+    let (bin_capacity, items) = parse_instance::<LabeledItem>(stream, &args.input)?;
+    let weights: Vec<u32> = items.iter().map(|item| item.weight).collect();
+
+    let constraint = ClassConstraint::new(label_ids(&items), max_classes);
+    let solution = solve_constrained(&weights, bin_capacity, &constraint, args);
+    print_constrained_solution(&solution, &items, args);
+
+    Ok(())
+}
+
+pub fn solve_min_fill(
+    stream: &mut impl BufRead,
+    min_fill: MinFill,
+    args: &Args,
+) -> anyhow::Result<()> {
+    let (bin_capacity, weights) = parse_input(stream, &args.input)?;
+
+    let constraint = MinFillConstraint::new(weights.clone(), min_fill.of(bin_capacity));
+    let solution = solve_constrained(&weights, bin_capacity, &constraint, args);
+    print_constrained_solution(&solution, &weights, args);
+
+    Ok(())
+}
+
+pub fn solve_fragile(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (bin_capacity, items) = parse_instance::<FragileItem>(stream, &args.input)?;
+    let weights: Vec<u32> = items.iter().map(|item| item.weight).collect();
+    let fragility = items.iter().map(|item| item.threshold).collect();
+
+    let constraint = FragileConstraint::new(weights.clone(), fragility);
+    let solution = solve_constrained(&weights, bin_capacity, &constraint, args);
+    print_constrained_solution(&solution, &items, args);
+
+    Ok(())
+}
+
+pub fn solve_temporal(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (bin_capacity, items) = parse_instance::<TemporalItem>(stream, &args.input)?;
+    let weights: Vec<u32> = items.iter().map(|item| item.weight).collect();
+    let intervals = items.iter().map(|item| (item.start, item.end)).collect();
+
+    let constraint = TemporalConstraint::new(weights.clone(), intervals, bin_capacity);
+    let solution = solve_constrained(&weights, bin_capacity, &constraint, args);
+    print_constrained_solution(&solution, &items, args);
+
+    Ok(())
+}
+
+pub fn solve_groups(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (bins, weights) = parse_bins_instance::<LabeledItem, u32>(stream, &args.input)?;
+    let capacities: Vec<u32> = bins.iter().map(|bin| bin.weight).collect();
+
+    // unlabeled bins share a group without limits
+    let labels = label_ids(&bins);
+    let unlabeled = labels.iter().flatten().max().map_or(0, |&group| group + 1);
+    let groups = labels
+        .iter()
+        .map(|label| label.unwrap_or(unlabeled))
+        .collect();
+
+    let group_of = |limit: &GroupLimit| {
+        bins.iter()
+            .zip(&labels)
+            .find(|(bin, _)| bin.label.as_ref() == Some(&limit.group))
+            .and_then(|(_, &group)| group)
+            .ok_or_else(|| anyhow::anyhow!("unknown group {:?}", limit.group))
+    };
+
+    let mut constraint = GroupConstraint::new(weights.clone(), groups);
+    for limit in &args.group_bins {
+        constraint.max_bins[group_of(limit)?] = Some(limit.limit as usize);
+    }
+
+    for limit in &args.group_weight {
+        constraint.max_weight[group_of(limit)?] = Some(limit.limit);
+    }
+
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+    let mut solution = SolutionState::Unknown;
+    loop {
+        let packer = ConstrainedPacker::new(weights.clone(), capacities.clone(), &constraint);
+        let budget = budget(deadline);
+        let packing = packer.solve_until(|| budget.remains());
+
+        match packing {
+            SolutionState::Solved(bins) => {
+                let open = bins.iter().filter(|bin| !bin.is_empty()).count();
+                log::info!("Found a packing in {open} bins");
+
+                solution = SolutionState::Solved(bins);
+                if open == 0 || !args.minimize {
+                    break;
+                }
+
+                constraint.max_open = Some(open - 1);
+            }
+            SolutionState::Unsolvable => {
+                solution.insert(SolutionState::Unsolvable);
+                break;
+            }
+            SolutionState::Unknown => break,
+        }
+    }
+
+    print_constrained_solution(&solution, &weights, args);
+
+    Ok(())
+}
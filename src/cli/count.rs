@@ -0,0 +1,27 @@
+use std::{io::BufRead, time};
+
+use crate::{budget, parse_multi_instance, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, cap: Option<usize>, args: &Args) -> anyhow::Result<()> {
+    let (capacities, weights) = parse_multi_instance::<u32>(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let mut solver = fitter::Fitter::new(weights, capacities);
+    let budget = budget(deadline);
+    let (count, complete) = solver.count_solutions(cap, || budget.remains());
+
+    log::info!("Counted {count} packings in {:?}", solve_start.elapsed());
+    match (count, complete) {
+        (0, true) => print_status("UNSAT"),
+        (0, false) => print_status("UNKNOWN"),
+        _ => print_status("SAT"),
+    }
+
+    println!("o {count}");
+    if !complete {
+        log::warn!("Stopped before counting all packings");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::{io::BufRead, time};
+
+use fitter::SolutionState;
+
+use crate::{budget, parse_input, print_solution, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (threshold, weights) = parse_input(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let coverer = fitter::covering::Coverer::new(weights, threshold);
+    let mut best = coverer.greedy();
+    let upper_bound = coverer.upper_bound();
+
+    'optimize: while best.len() < upper_bound {
+        let num_bins = best.len() + 1;
+        log::info!("Trying to cover {num_bins} bins");
+
+        let budget = budget(deadline);
+        let solution = coverer.cover(num_bins, || budget.remains());
+
+        match solution {
+            SolutionState::Solved(bins) => best = bins,
+            SolutionState::Unknown | SolutionState::Unsolvable => break 'optimize,
+        }
+    }
+
+    print_status("SAT");
+    println!("o {}", best.len());
+
+    if args.values {
+        let bins = best.into_iter().map(|items| fitter::Bin {
+            size: threshold,
+            capacity: threshold,
+            items,
+            label: None,
+        });
+
+        print_solution(&bins.collect::<Vec<_>>());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::{io::BufRead, time};
+
+use fitter::{
+    batch,
+    bounds::{self, LowerBound},
+    solver::SolveOutcome,
+    verify,
+};
+
+use crate::{budget, engine, packed_bins, parse_instance, Algorithm, Args};
+
+/// Minimizes the bins of the instance at the start of `stream` with every engine for the
+/// `crosscheck` subcommand, writing an `e` line for each disagreement. Returns whether they all
+/// agree.
+pub fn run(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<bool> {
+    let (capacity, items) = parse_instance::<u32>(stream, &args.input)?;
+    let instance = batch::Instance { capacity, items };
+    let lower_bound = bounds::MartelloToth.lower_bound(&instance.items, &capacity);
+
+    let mut results = Vec::new();
+    for &algorithm in <Algorithm as clap::ValueEnum>::value_variants() {
+        let name = clap::ValueEnum::to_possible_value(&algorithm)
+            .expect("no engine is hidden")
+            .get_name()
+            .to_string();
+
+        let start = time::Instant::now();
+        let deadline = args.timeout.map(|timeout| start + timeout.into());
+        let (bins, optimal) = minimize_with(algorithm, &instance, deadline, args.seed);
+        let status = match (&bins, optimal) {
+            (Some(_), true) => "OPTIMAL",
+            (Some(_), false) => "SAT",
+            (None, true) => "UNSAT",
+            (None, false) => "UNKNOWN",
+        };
+
+        let used = bins
+            .as_ref()
+            .map_or("-".to_string(), |bins| bins.len().to_string());
+        let time = start.elapsed().as_secs_f64();
+        println!("r {name} {status} {used} {time:.3}");
+        results.push((name, bins, optimal));
+    }
+
+    let mut errors = Vec::new();
+    for (name, bins, _) in &results {
+        let Some(bins) = bins else {
+            continue;
+        };
+
+        if let Err(violation) = verify::verify(bins, &instance) {
+            errors.push(format!("{name} packs the items wrongly: {violation}"));
+        }
+
+        if bins.len() < lower_bound {
+            let used = bins.len();
+            errors.push(format!(
+                "{name} packs into {used} bins, below the lower bound {lower_bound}"
+            ));
+        }
+    }
+
+    for (idx, (exact, optimum, proven)) in results.iter().enumerate() {
+        if !proven {
+            continue;
+        }
+
+        let optimum = optimum.as_ref().map(Vec::len);
+        for (other, (name, bins, optimal)) in results.iter().enumerate() {
+            let used = bins.as_ref().map(Vec::len);
+            let error = match (used, optimum) {
+                // each pair of proofs only once
+                (Some(used), Some(optimum)) if *optimal => match used != optimum && other > idx {
+                    true => format!("{exact} proves {optimum} bins optimal, {name} proves {used}"),
+                    false => continue,
+                },
+                (Some(used), Some(optimum)) if used < optimum => format!(
+                    "{name} packs into {used} bins, fewer than the {optimum} {exact} proves optimal"
+                ),
+                (Some(used), None) => {
+                    format!("{name} packs into {used} bins, {exact} proves there is no packing")
+                }
+                _ => continue,
+            };
+
+            errors.push(error);
+        }
+    }
+
+    match errors.is_empty() {
+        true => println!("s CONSISTENT"),
+        false => println!("s INCONSISTENT"),
+    }
+
+    errors.iter().for_each(|error| println!("e {error}"));
+    Ok(errors.is_empty())
+}
+
+/// The packing of `instance` in the fewest bins `algorithm` finds by `deadline`, trying one bin
+/// fewer after each packing, and whether it proved there is none with fewer.
+fn minimize_with(
+    algorithm: Algorithm,
+    instance: &batch::Instance<u32>,
+    deadline: Option<time::Instant>,
+    seed: u64,
+) -> (Option<Vec<fitter::Bin<u32>>>, bool) {
+    let mut best = None;
+    let mut max_bins = instance.items.len();
+    loop {
+        let bins = vec![fitter::Bin::new(instance.capacity); max_bins];
+        let mut engine = engine(algorithm, instance.items.clone(), bins, seed);
+        match engine.solve(budget(deadline)) {
+            SolveOutcome::Solved => {
+                let packed = packed_bins(engine.bins());
+                let fewer = packed.len().checked_sub(1);
+                best = Some(packed);
+                match fewer {
+                    Some(fewer) => max_bins = fewer,
+                    None => return (best, true),
+                }
+            }
+            SolveOutcome::Exhausted => return (best, algorithm.is_exact()),
+            SolveOutcome::Budget => return (best, false),
+        }
+    }
+}
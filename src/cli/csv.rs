@@ -0,0 +1,180 @@
+use std::{error, io::BufRead, str};
+
+use crate::parse_item;
+
+/// Parses a CSV export with a row for each item, see [`crate::InputFormat::Csv`], up to the end
+/// of `reader`.
+pub fn parse_instance<B, I>(
+    reader: &mut impl BufRead,
+    capacity: Option<&str>,
+) -> anyhow::Result<(Vec<B>, Vec<I>)>
+where
+    B: str::FromStr,
+    B::Err: error::Error + Send + Sync + 'static,
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    let Some(capacity) = capacity else {
+        anyhow::bail!("CSV input needs the bin capacity in --capacity");
+    };
+
+    let capacity = capacity.parse::<B>().map_err(anyhow::Error::new)?;
+    let mut columns = None;
+    let mut items = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = record(&line);
+        if columns.is_none() {
+            // without a header the columns are in the default order
+            let header = Columns::from_header(&record);
+            columns = Some(header.unwrap_or_default());
+            if header.is_some() {
+                continue;
+            }
+        }
+
+        let Columns {
+            id,
+            weight,
+            value,
+            group,
+        } = columns.unwrap();
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+
+        let Some(weight) = field(Some(weight)) else {
+            anyhow::bail!("line {} has no weight", idx + 1);
+        };
+
+        let extras = [(value, ","), (group, "#")]
+            .into_iter()
+            .filter_map(|(column, prefix)| Some(format!("{prefix}{}", field(column)?)))
+            .collect::<Vec<_>>();
+        items.push(parse_item(field(id), weight, &extras)?);
+    }
+
+    Ok((vec![capacity], items))
+}
+
+/// Where the columns of a CSV input are.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Columns {
+    /// The names of the items.
+    id: Option<usize>,
+    weight: usize,
+    value: Option<usize>,
+    group: Option<usize>,
+}
+
+impl Columns {
+    /// The columns named in `header`, if it is one: a record with a `weight` field.
+    fn from_header(header: &[String]) -> Option<Self> {
+        let find = |name: &str| {
+            header
+                .iter()
+                .position(|field| field.trim().eq_ignore_ascii_case(name))
+        };
+
+        Some(Self {
+            id: find("id"),
+            weight: find("weight")?,
+            value: find("value"),
+            group: find("group"),
+        })
+    }
+}
+
+impl Default for Columns {
+    /// `id,weight,value,group`
+    fn default() -> Self {
+        Self {
+            id: Some(0),
+            weight: 1,
+            value: Some(2),
+            group: Some(3),
+        }
+    }
+}
+
+/// Splits a line of CSV into its fields, unquoting the quoted ones.
+fn record(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+
+    fields
+}
+
+/// Writes the `--format csv` table: each item with its position in the input, its weight, and
+/// the position and label of its bin, both empty for the items that weren't packed.
+pub fn print_assignment(weights: &[u32], item_bins: &[Option<usize>], bins: &[fitter::Bin<u32>]) {
+    println!("item,weight,bin,label");
+    for (idx, (weight, bin)) in weights.iter().zip(item_bins).enumerate() {
+        let label = bin
+            .and_then(|bin| bins[bin].label.as_deref())
+            .map(field)
+            .unwrap_or_default();
+        let bin = bin.map(|bin| bin.to_string()).unwrap_or_default();
+        println!("{idx},{weight},{bin},{label}");
+    }
+}
+
+/// Quotes `field` if it has commas, quotes or line breaks in it.
+fn field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_records() {
+        assert_eq!(record("a,1,,x"), ["a", "1", "", "x"]);
+        assert_eq!(record(r#""a, ""b""",2"#), ["a, \"b\"", "2"]);
+        assert_eq!(record(""), [""]);
+        assert_eq!(field("a, \"b\""), r#""a, ""b""""#);
+        assert_eq!(field("plain"), "plain");
+    }
+
+    #[test]
+    fn finds_columns_by_header() {
+        let header = record("Weight, ID ,group");
+        let columns = Columns::from_header(&header).unwrap();
+        assert_eq!(
+            columns,
+            Columns {
+                id: Some(1),
+                weight: 0,
+                value: None,
+                group: Some(2),
+            }
+        );
+
+        assert_eq!(Columns::from_header(&record("a,4,5")), None);
+    }
+}
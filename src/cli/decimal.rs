@@ -0,0 +1,67 @@
+use std::{io::BufRead, str};
+
+use fitter::knapsack;
+
+use crate::{parse_bins_instance, solve_items, Args, Format, Named};
+
+/// Solves an instance of decimal weights for `--decimal`, scaled by the power of ten that makes
+/// them all whole, each item written as it was given.
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (capacities, items) = parse_bins_instance::<String, String>(stream, &args.input)?;
+    let items: Vec<(Option<&str>, &str)> = items
+        .iter()
+        .map(|item| match item.rsplit_once(':') {
+            Some((name, weight)) => (Some(name), weight),
+            None => (None, item.as_str()),
+        })
+        .collect();
+
+    let numbers = capacities.iter().map(String::as_str);
+    let numbers = numbers.chain(items.iter().map(|&(_, weight)| weight));
+    let digits = numbers.map(decimal_digits).max().unwrap_or(0);
+    let capacities = capacities
+        .iter()
+        .map(|capacity| scale_decimal(capacity, digits))
+        .collect::<anyhow::Result<_>>()?;
+
+    let items = items
+        .into_iter()
+        .map(|(name, weight)| {
+            let scaled = scale_decimal(weight, digits)?;
+            // the weight as given, unless it is already whole
+            let name = name.or((digits > 0).then_some(weight));
+            Ok(Named {
+                name: name.map(str::to_string),
+                item: knapsack::Item::new(scaled, scaled as u64),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let scale = format!("1{}", "0".repeat(digits));
+    match args.format {
+        Format::Text => println!("c scale {scale}"),
+        _ => log::info!("Scaled the weights by {scale}"),
+    }
+
+    solve_items(capacities, items, args)
+}
+
+/// The digits after the decimal point of `number`.
+fn decimal_digits(number: &str) -> usize {
+    number
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+/// `number` times ten to the power of `digits`, at least its own digits after the point.
+fn scale_decimal(number: &str, digits: usize) -> anyhow::Result<u32> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let digit_chars = whole.chars().chain(fraction.chars());
+    if whole.len() + fraction.len() == 0 || !digit_chars.clone().all(|c| c.is_ascii_digit()) {
+        anyhow::bail!("invalid decimal {number:?}");
+    }
+
+    format!("{whole}{fraction:0<digits$}")
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{number} scaled to a whole number is too large"))
+}
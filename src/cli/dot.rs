@@ -0,0 +1,38 @@
+use crate::bin_positions;
+
+/// Writes the `--format dot` graph: a cluster for each of `bins`, labeled with the bin and its
+/// load, holding a node for each of its items named after `names` or its weight.
+pub fn print(input: &[u32], names: &[Option<String>], bins: &[fitter::Bin<u32>]) {
+    println!("graph packing {{");
+    println!("  node [shape=box]");
+    for (bin_idx, (bin, positions)) in bins.iter().zip(bin_positions(input, bins)).enumerate() {
+        let label = match &bin.label {
+            Some(label) => format!("#{label}"),
+            None => format!("bin {bin_idx}"),
+        };
+
+        println!("  subgraph cluster_{bin_idx} {{");
+        println!(
+            "    label={}",
+            dot_string(&format!("{label} ({}/{})", bin.used(), bin.size))
+        );
+
+        for idx in positions {
+            let name = match &names[idx] {
+                Some(name) => name.clone(),
+                None => input[idx].to_string(),
+            };
+
+            println!("    item_{idx} [label={}]", dot_string(&name));
+        }
+
+        println!("  }}");
+    }
+
+    println!("}}");
+}
+
+/// `text` as a quoted DOT string.
+fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
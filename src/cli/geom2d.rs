@@ -0,0 +1,59 @@
+use std::{io::BufRead, time};
+
+use fitter::geom2d;
+
+use crate::{budget, parse_input_2d, print_status, solve_deadline, Args};
+
+fn print_sheets(sheets: &[geom2d::Sheet]) {
+    for sheet in sheets {
+        let line = sheet
+            .placements
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        println!("v {}", line);
+    }
+}
+
+pub fn solve(stream: &mut impl BufRead, method: geom2d::Method, args: &Args) -> anyhow::Result<()> {
+    let (sheet, rects) = parse_input_2d(stream)?;
+    let packer = geom2d::Packer::new(sheet, method).with_rotation(args.rotate);
+    let solve_start = time::Instant::now();
+    let budget = budget(solve_deadline(args, solve_start));
+
+    if args.strip {
+        match packer.pack_strip_until(&rects, || budget.remains()) {
+            None => print_status("UNSAT"),
+            Some(strip) => {
+                print_status("SAT");
+                println!("o {}", strip.size.height);
+
+                if args.values {
+                    print_sheets(&[strip]);
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    match packer.pack_until(&rects, || budget.remains()) {
+        None => print_status("UNSAT"),
+        Some(sheets) => {
+            print_status("SAT");
+            log::info!(
+                "Packed {} rectangles in {} sheets",
+                rects.len(),
+                sheets.len()
+            );
+
+            if args.values {
+                print_sheets(&sheets);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::{
+    fs,
+    io::{self, BufRead},
+    time,
+};
+
+use fitter::solver::SolveOutcome;
+
+use crate::budget;
+
+/// How far [`solve`] runs before asking for the next command.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pause {
+    Step,
+    /// Until the backtracks reach this many.
+    Backtrack(u64),
+    Never,
+}
+
+/// The search of `solver` for `--interactive`, stepped one placement or backtrack at a time
+/// with the bins shown on stderr after each.
+pub fn solve(
+    solver: &mut fitter::Fitter<u32>,
+    deadline: Option<time::Instant>,
+) -> anyhow::Result<SolveOutcome> {
+    // stdin may be the instance, the commands come from the terminal itself
+    let terminal = fs::File::open("/dev/tty")
+        .map_err(|err| anyhow::anyhow!("--interactive needs a terminal: {err}"))?;
+    let mut terminal = io::BufReader::new(terminal);
+    let budget = budget(deadline);
+    let mut pause = Pause::Step;
+    let mut step = 0;
+    loop {
+        let stats = solver.stats();
+        if pause == Pause::Step || pause == Pause::Backtrack(stats.backtracks) {
+            eprintln!(
+                "c step {step}: {} items left, {} nodes, {} backtracks",
+                solver.items.len(),
+                stats.nodes,
+                stats.backtracks
+            );
+            print_search_state(solver);
+
+            eprint!("[enter] step, [b] next backtrack, [c] continue, [q] quit: ");
+            let mut command = String::new();
+            if terminal.read_line(&mut command)? == 0 {
+                command.push('q');
+            }
+
+            pause = match command.trim() {
+                "b" => Pause::Backtrack(stats.backtracks + 1),
+                "c" => Pause::Never,
+                "q" => return Ok(SolveOutcome::Budget),
+                _ => Pause::Step,
+            };
+        }
+
+        if !budget.remains() {
+            return Ok(SolveOutcome::Budget);
+        }
+
+        step += 1;
+        if !solver.step() {
+            break;
+        }
+    }
+
+    print_search_state(solver);
+    Ok(match solver.is_solved() {
+        true => SolveOutcome::Solved,
+        false => SolveOutcome::Exhausted,
+    })
+}
+
+/// The non-empty bins of `solver` with their loads, and the items it has left, on stderr.
+fn print_search_state(solver: &fitter::Fitter<u32>) {
+    for (idx, bin) in solver.bins.iter().enumerate() {
+        if bin.is_empty() {
+            continue;
+        }
+
+        let items: Vec<String> = bin.items.iter().map(u32::to_string).collect();
+        let load: u64 = bin.items.iter().map(|&item| item as u64).sum();
+        eprintln!("c bin {idx} [{load}/{}]: {}", bin.size, items.join(" "));
+    }
+
+    let empty = solver.bins.iter().filter(|bin| bin.is_empty()).count();
+    let items: Vec<String> = solver.items.iter().map(u32::to_string).collect();
+    eprintln!("c {empty} empty bins, left: {}", items.join(" "));
+}
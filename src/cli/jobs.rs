@@ -0,0 +1,115 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, Read},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use crate::{
+    has_instance_left, interrupted, parse_bins_instance, parse_input_2d, solve_instance, Args,
+    CAPTURED,
+};
+
+/// Solves the instances of `stream` on `--jobs` threads, writing out the output of each
+/// instance once the ones before it are written.
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (sender, instances) = mpsc::sync_channel::<(usize, Vec<u8>)>(args.jobs);
+    // dropped with the last worker, so the reader stops once the printer fails
+    let instances = Arc::new(Mutex::new(instances));
+    let (done, outputs) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..args.jobs {
+            let (instances, done) = (Arc::clone(&instances), done.clone());
+            scope.spawn(move || loop {
+                let next = instances.lock().unwrap().recv();
+                let Ok((idx, text)) = next else {
+                    break;
+                };
+
+                CAPTURED.set(Some(String::new()));
+                let result = solve_instance(&mut io::Cursor::new(text), args);
+                let output = CAPTURED.take().unwrap_or_default();
+                if done.send((idx, result.map(|_| output))).is_err() {
+                    break;
+                }
+            });
+        }
+
+        drop((instances, done));
+        let printer = scope.spawn(move || {
+            let mut pending = BTreeMap::new();
+            let mut next = 0;
+            for (idx, output) in outputs {
+                pending.insert(idx, output);
+                while let Some(output) = pending.remove(&next) {
+                    print!("{}", output?);
+                    next += 1;
+                }
+            }
+
+            anyhow::Ok(())
+        });
+
+        let mut idx = 0;
+        while has_instance_left(stream)? && !interrupted() {
+            let text = next_instance(stream, args)?;
+            if sender.send((idx, text)).is_err() {
+                // the printer stopped at an error, it's reported below
+                break;
+            }
+
+            idx += 1;
+        }
+
+        drop(sender);
+        printer.join().unwrap()
+    })
+}
+
+/// Reads the text of the instance at the start of `stream`, without solving it.
+fn next_instance(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<Vec<u8>> {
+    let mut recorder = Recorder {
+        inner: stream,
+        recorded: Vec::new(),
+    };
+
+    match args.geom2d {
+        Some(_) => {
+            parse_input_2d(&mut recorder)?;
+        }
+        None => {
+            parse_bins_instance::<String, String>(&mut recorder, &args.input)?;
+        }
+    }
+
+    Ok(recorder.recorded)
+}
+
+/// Reads through `inner`, keeping a copy of what was read.
+struct Recorder<'a, R> {
+    inner: &'a mut R,
+    recorded: Vec<u8>,
+}
+
+impl<R: BufRead> Read for Recorder<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let amount = self.fill_buf()?.read(buf)?;
+        self.consume(amount);
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead> BufRead for Recorder<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // what is consumed was filled before, so this doesn't read
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.recorded.extend_from_slice(&buf[..amount]);
+        }
+
+        self.inner.consume(amount);
+    }
+}
@@ -0,0 +1,551 @@
+use std::{
+    error, fmt,
+    io::{self, BufRead},
+    str, time,
+};
+
+use fitter::{solver::SolveStats, SolutionState};
+
+use crate::{parse_item, EOFError};
+
+/// Whether the start of `reader` is a JSON object.
+pub fn looks_like(reader: &mut impl BufRead) -> io::Result<bool> {
+    let buf = reader.fill_buf()?;
+    Ok(buf.trim_ascii_start().starts_with(b"{"))
+}
+
+/// Reads the lines of the JSON value at the start of `reader`, leaving the values after it.
+fn read_text(reader: &mut impl BufRead) -> anyhow::Result<String> {
+    let mut text = String::new();
+    let (mut depth, mut quoted, mut escaped) = (0usize, false, false);
+    loop {
+        let start = text.len();
+        if reader.read_line(&mut text)? == 0 {
+            Err(EOFError)?;
+        }
+
+        for c in text[start..].chars() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                '{' | '[' if !quoted => depth += 1,
+                '}' | ']' if !quoted => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        if depth == 0 && !quoted && !text.trim().is_empty() {
+            return Ok(text);
+        }
+    }
+}
+
+/// A number or a string of a JSON instance, as it would be written in the plain format.
+fn scalar(value: &Json) -> anyhow::Result<String> {
+    match value {
+        Json::Number(number) => Ok(number.to_string()),
+        Json::String(text) => Ok(text.clone()),
+        _ => anyhow::bail!("expected a number or a string, found {value}"),
+    }
+}
+
+/// An item or a bin as its name, its weight or capacity and the extras for [`parse_item`].
+type ItemParts = (Option<String>, String, Vec<String>);
+
+/// Parses a JSON instance, see [`crate::InputFormat::Json`] and `instance.schema.json`.
+pub fn parse_instance<B, I>(reader: &mut impl BufRead) -> anyhow::Result<(Vec<B>, Vec<I>)>
+where
+    B: str::FromStr,
+    B::Err: error::Error + Send + Sync + 'static,
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    let (bins, items) = instance(&read_text(reader)?)?;
+    let bins = bins
+        .iter()
+        .map(|(_, capacity, extras)| {
+            parse_item::<B>(None, capacity, extras).map_err(anyhow::Error::new)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut parsed = Vec::with_capacity(items.len());
+    for (name, weight, extras) in &items {
+        parsed.push(parse_item::<I>(name.as_deref(), weight, extras)?);
+    }
+
+    Ok((bins, parsed))
+}
+
+/// The bins and the items of the JSON instance in `text`.
+fn instance(text: &str) -> anyhow::Result<(Vec<ItemParts>, Vec<ItemParts>)> {
+    let instance: Json = text.parse()?;
+    let field = |value: &Json, key: &str, prefix: &str| {
+        value
+            .get(key)
+            .map(|field| scalar(field).map(|field| format!("{prefix}{field}")))
+            .transpose()
+    };
+
+    let bins = match (instance.get("capacity"), instance.get("bins")) {
+        (Some(capacity), None) => vec![capacity],
+        (None, Some(Json::Array(bins))) => bins.iter().collect(),
+        _ => anyhow::bail!("expected a \"capacity\" or a list of \"bins\""),
+    };
+
+    let bins = bins
+        .into_iter()
+        .map(|bin| match bin.get("capacity") {
+            Some(capacity) => Ok((
+                None,
+                scalar(capacity)?,
+                Vec::from_iter(field(bin, "label", "#")?),
+            )),
+            None => Ok((None, scalar(bin)?, Vec::new())),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let Some(Json::Array(items)) = instance.get("items") else {
+        anyhow::bail!("expected a list of \"items\"");
+    };
+
+    let items = items
+        .iter()
+        .map(|item| {
+            let Json::Object(_) = item else {
+                return Ok((None, scalar(item)?, Vec::new()));
+            };
+
+            let Some(weight) = item.get("weight") else {
+                anyhow::bail!("item {item} has no \"weight\"");
+            };
+
+            let interval = match (field(item, "start", "@")?, field(item, "end", "-")?) {
+                (Some(start), Some(end)) => Some(start + &end),
+                _ => None,
+            };
+
+            let extras = [
+                field(item, "value", ",")?,
+                field(item, "group", "#")?,
+                field(item, "priority", "^")?,
+                field(item, "fragile", "!")?,
+                interval,
+            ];
+
+            let name = item.get("label").map(scalar).transpose()?;
+            Ok((
+                name,
+                scalar(weight)?,
+                extras.into_iter().flatten().collect(),
+            ))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok((bins, items))
+}
+
+/// The result of the default mode for `--format json`.
+pub fn result(
+    solution: &SolutionState<Vec<fitter::Bin<u32>>>,
+    stats: Option<&SolveStats>,
+    elapsed: time::Duration,
+) -> Json {
+    let (status, bins) = match solution {
+        SolutionState::Unknown => ("UNKNOWN", Json::Null),
+        SolutionState::Unsolvable => ("UNSAT", Json::Null),
+        SolutionState::Solved(bins) => {
+            let bins = bins.iter().map(|bin| {
+                Json::object([
+                    ("items", Json::from(bin.items.clone())),
+                    ("load", bin.used().into()),
+                    ("utilization", bin.utilization().into()),
+                ])
+            });
+
+            ("SAT", Json::Array(bins.collect()))
+        }
+    };
+
+    let stats = stats.map_or(Json::Null, |stats| {
+        Json::object([
+            ("nodes", stats.nodes.into()),
+            ("backtracks", stats.backtracks.into()),
+            ("max_depth", stats.max_depth.into()),
+            ("restarts", stats.restarts.into()),
+            ("wall_time", stats.wall_time.as_secs_f64().into()),
+        ])
+    });
+
+    Json::object([
+        ("status", status.into()),
+        ("bins", bins),
+        ("stats", stats),
+        ("time", elapsed.as_secs_f64().into()),
+    ])
+}
+
+/// A JSON value, written on one line with `{}` and indented with `{:#}`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Self {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pretty = f.alternate();
+        let newline = |f: &mut fmt::Formatter<'_>, indent: usize| match pretty {
+            true => write!(f, "\n{:1$}", "", indent * 2),
+            false => Ok(()),
+        };
+
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) if value.is_finite() => write!(f, "{value}"),
+            Json::Number(_) => f.write_str("null"),
+            Json::String(value) => write_json_string(f, value),
+            Json::Array(values) if values.is_empty() => f.write_str("[]"),
+            // lists of numbers stay on one line
+            Json::Array(values) if values.iter().all(|value| matches!(value, Json::Number(_))) => {
+                f.write_str("[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(if pretty { ", " } else { "," })?;
+                    }
+
+                    value.write(f, indent)?;
+                }
+
+                f.write_str("]")
+            }
+            Json::Array(values) => {
+                f.write_str("[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+
+                    newline(f, indent + 1)?;
+                    value.write(f, indent + 1)?;
+                }
+
+                newline(f, indent)?;
+                f.write_str("]")
+            }
+            Json::Object(fields) if fields.is_empty() => f.write_str("{}"),
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+
+                    newline(f, indent + 1)?;
+                    write_json_string(f, key)?;
+                    f.write_str(if pretty { ": " } else { ":" })?;
+                    value.write(f, indent + 1)?;
+                }
+
+                newline(f, indent)?;
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn to_u32(&self) -> anyhow::Result<u32> {
+        match *self {
+            Json::Number(value)
+                if value.fract() == 0. && (0. ..=u32::MAX as f64).contains(&value) =>
+            {
+                Ok(value as u32)
+            }
+            _ => anyhow::bail!("expected a weight, found {self}"),
+        }
+    }
+}
+
+impl str::FromStr for Json {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> anyhow::Result<Self> {
+        let mut parser = JsonParser { text, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            anyhow::bail!("unexpected {:?} after the JSON value", parser.rest());
+        }
+
+        Ok(value)
+    }
+}
+
+/// Recursive descent over the text of a JSON value, `pos` is a byte offset into `text`.
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips `token` if the text goes on with it.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> anyhow::Result<()> {
+        if !self.eat(token) {
+            anyhow::bail!("expected {token:?} at byte {}", self.pos);
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self) -> anyhow::Result<Json> {
+        self.skip_whitespace();
+        if self.eat("null") {
+            Ok(Json::Null)
+        } else if self.eat("true") {
+            Ok(Json::Bool(true))
+        } else if self.eat("false") {
+            Ok(Json::Bool(false))
+        } else if self.rest().starts_with('"') {
+            self.string().map(Json::String)
+        } else if self.eat("[") {
+            let mut values = Vec::new();
+            if !self.eat("]") {
+                loop {
+                    values.push(self.value()?);
+                    if self.eat("]") {
+                        break;
+                    }
+
+                    self.expect(",")?;
+                }
+            }
+
+            Ok(Json::Array(values))
+        } else if self.eat("{") {
+            let mut fields = Vec::new();
+            if !self.eat("}") {
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    if self.eat("}") {
+                        break;
+                    }
+
+                    self.expect(",")?;
+                }
+            }
+
+            Ok(Json::Object(fields))
+        } else {
+            let rest = self.rest();
+            let len = rest
+                .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+                .unwrap_or(rest.len());
+            let number = rest[..len]
+                .parse()
+                .map_err(|_| anyhow::anyhow!("unexpected {:?} at byte {}", rest, self.pos))?;
+            self.pos += len;
+            Ok(Json::Number(number))
+        }
+    }
+
+    fn string(&mut self) -> anyhow::Result<String> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        Some(c) => c,
+                        None => break,
+                    };
+
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+
+        anyhow::bail!("unterminated string at byte {}", self.pos)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn write_json_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+
+    f.write_str("\"")
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Self {
+        Json::Number(value.into())
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(values: Vec<T>) -> Self {
+        Json::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_writes_values() {
+        let text = r#"{"capacity":10,"items":[4,{"weight":"3","label":"a \"b\"\n"}],"x":null}"#;
+        let value: Json = text.parse().unwrap();
+        assert_eq!(value.to_string(), text);
+        assert_eq!(value.get("capacity").unwrap().to_u32().unwrap(), 10);
+        assert!(value.get("missing").is_none());
+
+        let pretty = format!("{:#}", Json::object([("items", Json::from(vec![1u32, 2]))]));
+        assert_eq!(pretty, "{\n  \"items\": [1, 2]\n}");
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        for text in ["", "{", "[1,]", "{\"a\" 1}", "\"open", "1 2", "nul"] {
+            assert!(text.parse::<Json>().is_err(), "{text:?}");
+        }
+
+        assert!(Json::Number(1.5).to_u32().is_err());
+        assert!(Json::Number(-1.).to_u32().is_err());
+    }
+
+    #[test]
+    fn reads_instances() {
+        let text = r#"{"bins": [10, {"capacity": 8, "label": "van"}], "items": [3, {"weight": 4, "value": 7, "label": "box"}]}"#;
+        let (bins, items) = instance(text).unwrap();
+        assert_eq!(bins[1], (None, "8".to_string(), vec!["#van".to_string()]));
+        assert_eq!(
+            items,
+            [
+                (None, "3".to_string(), vec![]),
+                (
+                    Some("box".to_string()),
+                    "4".to_string(),
+                    vec![",7".to_string()]
+                ),
+            ]
+        );
+
+        assert!(instance(r#"{"items": []}"#).is_err());
+        assert!(instance(r#"{"capacity": 1, "items": [{"value": 1}]}"#).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use std::{io::BufRead, time};
+
+use fitter::knapsack;
+
+use crate::{budget, parse_multi_instance, print_rejected, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (capacities, items) = parse_multi_instance::<knapsack::Item>(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let knapsack = knapsack::Knapsack::with_capacities(items, capacities);
+    let budget = budget(deadline);
+    let selection = knapsack.solve_until(|| budget.remains());
+
+    log::info!(
+        "Selected {} items weighing {} (optimal={})",
+        selection.items.len(),
+        selection.weight,
+        selection.optimal
+    );
+
+    print_status("SAT");
+    println!("o {}", selection.value);
+
+    if args.values {
+        for bin in &selection.bins {
+            let line = bin
+                .iter()
+                .map(|&idx| knapsack.items[idx].to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            println!("v {}", line);
+        }
+    }
+
+    let rejected: Vec<knapsack::Item> = (0..knapsack.items.len())
+        .filter(|idx| selection.items.binary_search(idx).is_err())
+        .map(|idx| knapsack.items[idx])
+        .collect();
+
+    print_rejected(&rejected);
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::{io::BufRead, time};
+
+use fitter::solver::{SolveOutcome, Solver};
+
+use super::csv;
+use crate::{
+    budget, parse_bins_instance, print_solution, print_status, progress_reporter, require_format,
+    solve_deadline, Args, Format, LabeledItem,
+};
+
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    require_format(args, "--labels", &[Format::Text, Format::Csv])?;
+    let (bins, weights) = parse_bins_instance::<LabeledItem, u32>(stream, &args.input)?;
+    let bins = bins
+        .into_iter()
+        .map(|bin| match bin.label {
+            Some(label) => fitter::Bin::new(bin.weight).with_label(label),
+            None => fitter::Bin::new(bin.weight),
+        })
+        .collect();
+
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let mut solver = fitter::Fitter::with_bins(weights.clone(), bins);
+    solver.set_bin_order(args.bin_order);
+    solver.set_progress(progress_reporter(args));
+    let outcome = solver.solve(budget(deadline));
+
+    if args.format == Format::Csv {
+        let item_bins = match outcome {
+            SolveOutcome::Solved => solver.solution().item_bins,
+            _ => vec![None; solver.items.len()],
+        };
+
+        csv::print_assignment(&weights, &item_bins, &solver.bins);
+        return Ok(());
+    }
+
+    match outcome {
+        SolveOutcome::Budget => print_status("UNKNOWN"),
+        SolveOutcome::Exhausted => print_status("UNSAT"),
+        SolveOutcome::Solved => {
+            print_status("SAT");
+
+            if args.values {
+                // in the order the bins were tried in
+                let bins: Vec<_> = solver
+                    .bins
+                    .into_iter()
+                    .filter(|bin| !bin.is_empty())
+                    .collect();
+                print_solution(&bins);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::{io::BufRead, time};
+
+use fitter::capacity;
+
+use crate::{budget, parse_input, print_solution, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (num_bins, weights) = parse_input(stream, &args.input)?;
+    let num_bins = num_bins as usize;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    if num_bins == 0 {
+        print_status(if weights.is_empty() { "SAT" } else { "UNSAT" });
+        return Ok(());
+    }
+
+    let best = capacity::longest_processing_time(&weights, num_bins);
+    let budget = budget(deadline);
+    let (best, optimal) = capacity::minimize_capacity(&weights, best, || budget.remains());
+
+    log::info!(
+        "Maximum load is {} (optimal={optimal})",
+        capacity::max_load(&best)
+    );
+
+    print_status("SAT");
+    println!("o {}", capacity::max_load(&best));
+
+    if args.values {
+        print_solution(&best);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use std::{io::BufRead, time};
+
+use fitter::capacity;
+
+use crate::{budget, parse_input, print_solution, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, num_bins: usize, args: &Args) -> anyhow::Result<()> {
+    let (max_capacity, weights) = parse_input(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    if num_bins == 0 {
+        print_status(if weights.is_empty() { "SAT" } else { "UNSAT" });
+        return Ok(());
+    }
+
+    let best = capacity::longest_processing_time(&weights, num_bins);
+    let budget = budget(deadline);
+    let (best, optimal) = capacity::minimize_capacity(&weights, best, || budget.remains());
+
+    let min_capacity = capacity::max_load(&best);
+    match (min_capacity <= max_capacity as u64, optimal) {
+        (true, _) => {
+            print_status("SAT");
+            println!("o {min_capacity}");
+
+            if args.values {
+                print_solution(&best);
+            }
+        }
+        (false, true) => print_status("UNSAT"),
+        (false, false) => print_status("UNKNOWN"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::{io::BufRead, time};
+
+use fitter::{nested, SolutionState};
+
+use crate::{budget, parse_input, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, pallet_capacity: u32, args: &Args) -> anyhow::Result<()> {
+    let (box_capacity, items) = parse_input(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let instance = nested::NestedInstance {
+        items,
+        box_capacity,
+        pallet_capacity,
+        boxes_per_pallet: args.boxes_per_pallet,
+    };
+
+    let budget = budget(deadline);
+    let solution = match args.joint {
+        true => instance.solve_joint(|| budget.remains()),
+        false => instance.solve_pipeline(|| budget.remains()),
+    };
+
+    match solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(solution) => {
+            print_status("SAT");
+            println!("o {}", solution.pallets.len());
+            log::info!(
+                "Packed {} items in {} boxes on {} pallets",
+                instance.items.len(),
+                solution.num_boxes(),
+                solution.pallets.len()
+            );
+
+            if args.values {
+                print!("{solution}");
+            }
+        }
+    }
+
+    Ok(())
+}
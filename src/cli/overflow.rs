@@ -0,0 +1,82 @@
+use std::{io::BufRead, time};
+
+use fitter::{
+    soft,
+    solver::{SolveOutcome, Solver},
+    SolutionState,
+};
+
+use crate::{
+    budget, parse_input, print_solution, print_status, progress_reporter, solve_deadline, Args,
+};
+
+pub fn solve(stream: &mut impl BufRead, overflow: u32, args: &Args) -> anyhow::Result<()> {
+    let (bin_capacity, weights) = parse_input(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+    let Some(limit) = bin_capacity.checked_add(overflow) else {
+        anyhow::bail!("the bin capacity {bin_capacity} plus the overflow {overflow} is too large");
+    };
+
+    // the fewest bins that fit the items with the full overflow
+    let total_weight: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    let mut num_bins = total_weight.div_ceil(limit.max(1) as u64) as usize;
+    let mut solution = SolutionState::Unknown;
+    while num_bins <= weights.len() {
+        log::info!("Trying to fit in {num_bins} bins of capacity {limit}");
+
+        let mut solver = fitter::Fitter::new(weights.clone(), vec![limit; num_bins]);
+        solver.set_progress(progress_reporter(args));
+        let outcome = solver.solve(budget(deadline));
+
+        if outcome == SolveOutcome::Budget {
+            break;
+        }
+
+        if solver.is_solved() {
+            let packer = soft::SoftPacker::new(weights.clone(), bin_capacity, overflow)
+                .with_penalty(args.overflow_penalty);
+
+            let budget = budget(deadline);
+            solution = packer.solve_until(num_bins, || budget.remains());
+
+            break;
+        }
+
+        num_bins += 1;
+    }
+
+    if num_bins > weights.len() {
+        solution.insert(SolutionState::Unsolvable);
+    }
+
+    match solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(packing) => {
+            print_status("SAT");
+            println!("o {}", packing.penalty);
+            log::info!(
+                "Overflow of {} (optimal={})",
+                packing.overflow,
+                packing.optimal
+            );
+
+            if args.values {
+                let bins = packing.bins.into_iter().map(|items| {
+                    let load: u64 = items.iter().map(|&item| item as u64).sum();
+                    fitter::Bin {
+                        size: bin_capacity,
+                        capacity: (bin_capacity as u64).saturating_sub(load) as u32,
+                        items,
+                        label: None,
+                    }
+                });
+
+                print_solution(&bins.collect::<Vec<_>>());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use core::fmt;
+use std::{error, io::BufRead, str};
+
+use crate::{has_data_left, has_instance_left, is_comment, EOFError};
+
+/// Parses the capacities on the first line, then the items up to a 0, skipping comments. An
+/// item followed by `x <count>` is repeated that many times. An optional
+/// `p bpp <items> <capacity>` header before the capacities is checked against them.
+pub fn parse_instance<B, I>(reader: &mut impl BufRead) -> anyhow::Result<(Vec<B>, Vec<I>)>
+where
+    B: str::FromStr + fmt::Debug,
+    B::Err: error::Error + Send + Sync + 'static,
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    let mut line = String::new();
+    let mut header = None;
+    let bin_capacities = loop {
+        if !has_instance_left(reader)? {
+            Err(EOFError)?;
+        }
+
+        line.clear();
+        reader.read_line(&mut line)?;
+        if is_comment(&line) {
+            continue;
+        }
+
+        let trimmed_line = line.trim();
+        log::trace!("trimmed_line={trimmed_line:?}");
+        if let Some(fields) = trimmed_line.strip_prefix("p ") {
+            header = Some(parse_header(fields)?);
+            continue;
+        }
+
+        if let Some((_, capacity)) = &header {
+            if trimmed_line != capacity {
+                anyhow::bail!(
+                    "the capacities {trimmed_line:?} don't match the header's {capacity}"
+                );
+            }
+        }
+
+        let capacities = trimmed_line
+            .split_whitespace()
+            .map(str::parse::<B>)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        log::trace!("capacities={capacities:?}");
+        break capacities;
+    };
+
+    let mut items = Vec::new();
+    // the last item, which an `x <count>` after it repeats
+    let mut last: Option<String> = None;
+    let mut repeat = false;
+    'outer: loop {
+        if !has_data_left(reader)? {
+            Err(EOFError)?;
+        }
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        if is_comment(&line) {
+            continue;
+        }
+
+        for num in line.split_whitespace() {
+            log::trace!("num={num:?}");
+            if repeat {
+                repeat = false;
+                let item = last.take().expect("an item is before each x");
+                let copies = parse_copies(num, items.len())?;
+                for _ in 1..copies {
+                    items.push(item.parse::<I>()?);
+                }
+
+                continue;
+            }
+
+            if num == "x" {
+                if last.is_none() {
+                    anyhow::bail!("expected an item before x");
+                }
+
+                repeat = true;
+                continue;
+            }
+
+            if num.parse::<u32>().is_ok_and(|num| num == 0) {
+                break 'outer;
+            }
+
+            items.push(num.parse::<I>()?);
+            last = Some(num.to_string());
+        }
+    }
+
+    if let Some((count, _)) = header {
+        if items.len() != count {
+            anyhow::bail!("the header declares {count} items, found {}", items.len());
+        }
+    }
+
+    Ok((bin_capacities, items))
+}
+
+/// The most items `<item> x <count>` may bring an instance up to, each copy is packed on its
+/// own.
+const MAX_ITEMS: usize = 1 << 20;
+
+/// The count of an `<item> x <count>` in the plain format, at least one copy, after `items`
+/// items including the one repeated.
+pub fn parse_copies(count: &str, items: usize) -> anyhow::Result<usize> {
+    let copies = match count.parse() {
+        Ok(copies) if copies > 0 => copies,
+        _ => anyhow::bail!("invalid count {count:?}, expected a positive number of copies"),
+    };
+
+    if items.saturating_add(copies - 1) > MAX_ITEMS {
+        anyhow::bail!("{copies} copies would make more than {MAX_ITEMS} items");
+    }
+
+    Ok(copies)
+}
+
+/// The number of items and the capacity of a `p bpp <items> <capacity>` header, from the
+/// `fields` after the `p`.
+pub fn parse_header(fields: &str) -> anyhow::Result<(usize, String)> {
+    let invalid =
+        || anyhow::anyhow!("invalid header \"p {fields}\", expected p bpp <items> <capacity>");
+    let [kind, count, capacity] = fields.split_whitespace().collect::<Vec<_>>()[..] else {
+        return Err(invalid());
+    };
+
+    if kind != "bpp" {
+        return Err(invalid());
+    }
+
+    let count = count.parse().map_err(|_| invalid())?;
+    Ok((count, capacity.to_string()))
+}
@@ -0,0 +1,50 @@
+use std::{io::BufRead, time};
+
+use fitter::{priority, SolutionState};
+
+use crate::{
+    budget, parse_multi_instance, print_items, print_status, solve_deadline, Args, PrioritizedItem,
+};
+
+pub fn solve(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (capacities, items) = parse_multi_instance::<PrioritizedItem>(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let packer = priority::TieredPacker::new(
+        items.iter().map(|item| item.weight).collect(),
+        items.iter().map(|item| item.priority).collect(),
+        capacities,
+    );
+
+    if packer.values().is_none() {
+        anyhow::bail!("too many priority tiers to rank the items");
+    }
+
+    let budget = budget(deadline);
+    let packing = packer.solve_until(|| budget.remains());
+
+    match packing {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(packing) => {
+            print_status("SAT");
+            print_items('o', packing.packed.iter().map(|&(_, count)| count));
+            log::info!(
+                "Packed {:?} items per priority (optimal={})",
+                packing.packed,
+                packing.optimal
+            );
+
+            if args.values {
+                for bin in &packing.bins {
+                    print_items('v', bin.iter().map(|&idx| items[idx]));
+                }
+            }
+
+            print_items('u', packing.rejected.iter().map(|&idx| items[idx]));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use std::{io::BufRead, path, time};
+
+use fitter::{repack, SolutionState};
+
+use crate::{
+    budget, parse_fixed_bins, parse_input, print_items, print_status, solve_deadline, Args,
+};
+
+pub fn solve(stream: &mut impl BufRead, path: &path::Path, args: &Args) -> anyhow::Result<()> {
+    let (bin_capacity, weights) = parse_input(stream, &args.input)?;
+    let current = parse_fixed_bins(path)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let total_weight: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    let fewest_bins = total_weight.div_ceil(bin_capacity.max(1) as u64) as usize;
+    let mut num_bins = fewest_bins.max(current.len());
+    let repacker = repack::Repacker::new(bin_capacity, current, weights);
+
+    // open more bins only when the added items don't fit otherwise
+    let mut solution = SolutionState::Unknown;
+    while num_bins <= repacker.current.len() + repacker.items.len() {
+        log::info!("Trying to repack in {num_bins} bins");
+
+        let budget = budget(deadline);
+        solution = repacker.solve_until(num_bins, || budget.remains());
+
+        if !matches!(solution, SolutionState::Unsolvable) {
+            break;
+        }
+
+        num_bins += 1;
+    }
+
+    match solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(repacking) => {
+            print_status("SAT");
+            println!("o {}", repacking.moves);
+            if !repacking.optimal {
+                log::warn!("Timed out before proving the number of moves is minimal");
+            }
+
+            if args.values {
+                for bin in &repacking.bins {
+                    print_items('v', bin);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::{io::Write, process, time};
+
+use fitter::{
+    batch,
+    heuristics::{FitRule, Greedy},
+    solver::{Budget, SolveOutcome, Solver},
+    verify,
+};
+
+use crate::{interrupted, Interesting, INTERRUPT};
+
+/// Shrinks `instance` for the `shrink` subcommand, first removing runs of items, halving their
+/// length each time none of them can go, then halving the weights one at a time, each change
+/// kept only if the instance stays `interesting`. Stops early if the run is interrupted.
+pub fn run(
+    instance: batch::Instance<u32>,
+    mut interesting: impl FnMut(&batch::Instance<u32>) -> anyhow::Result<bool>,
+) -> anyhow::Result<batch::Instance<u32>> {
+    if !interesting(&instance)? {
+        anyhow::bail!("the instance isn't interesting to begin with");
+    }
+
+    let mut best = instance;
+    let mut run = best.items.len() / 2;
+    while run > 0 && !interrupted() {
+        let mut start = 0;
+        while start < best.items.len() && !interrupted() {
+            let mut candidate = best.clone();
+            let end = (start + run).min(candidate.items.len());
+            candidate.items.drain(start..end);
+            if candidate.items.is_empty() || !interesting(&candidate)? {
+                start += run;
+                continue;
+            }
+
+            best = candidate;
+            log::info!("Shrunk to {} items", best.items.len());
+        }
+
+        run /= 2;
+    }
+
+    let mut shrinking = true;
+    while shrinking && !interrupted() {
+        shrinking = false;
+        for idx in 0..best.items.len() {
+            let mut candidate = best.clone();
+            candidate.items[idx] /= 2;
+            if candidate.items[idx] == 0 || !interesting(&candidate)? {
+                continue;
+            }
+
+            best = candidate;
+            shrinking = true;
+            log::info!(
+                "Shrunk to a total weight of {}",
+                best.items.iter().map(|&item| item as u64).sum::<u64>()
+            );
+        }
+    }
+
+    Ok(best)
+}
+
+/// Whether `instance` is still `interesting` to the `shrink` subcommand, the searches run for at
+/// most `timeout`.
+pub fn is_interesting(
+    interesting: &Interesting,
+    timeout: Option<time::Duration>,
+    instance: &batch::Instance<u32>,
+) -> anyhow::Result<bool> {
+    if let Some(command) = &interesting.command {
+        let mut child = process::Command::new("sh")
+            .args(["-c", command])
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::null())
+            .stderr(process::Stdio::null())
+            .spawn()?;
+
+        let items: Vec<String> = instance.items.iter().map(u32::to_string).collect();
+        let text = format!("{}\n{}\n0\n", instance.capacity, items.join(" "));
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // the command may well exit without reading all of it
+        let _ = stdin.write_all(text.as_bytes());
+        drop(stdin);
+        return Ok(!child.wait()?.success());
+    }
+
+    let config = batch::BatchConfig {
+        threads: 1,
+        timeout: interesting
+            .slower_than
+            .map_or(timeout, |limit| Some(limit.into())),
+        cancellation: INTERRUPT.get().cloned(),
+    };
+
+    let [outcome] = &batch::solve_many([instance.clone()], &config)[..] else {
+        unreachable!("one outcome per instance");
+    };
+
+    if interesting.slower_than.is_some() {
+        return Ok(!outcome.optimal && !interrupted());
+    }
+
+    let bins = vec![instance.capacity; instance.items.len()];
+    let mut ffd = Greedy::new(instance.items.clone(), bins, FitRule::First);
+    let ffd_packed = ffd.solve(Budget::unlimited()) == SolveOutcome::Solved;
+    let ffd_bins = ffd.bins.iter().filter(|bin| !bin.is_empty()).count();
+    Ok(match &outcome.bins {
+        Some(bins) if verify::verify(bins, instance).is_err() => true,
+        Some(bins) => outcome.optimal && ffd_packed && bins.len() > ffd_bins,
+        None => outcome.optimal && ffd_packed,
+    })
+}
@@ -0,0 +1,76 @@
+use std::{io::BufRead, time};
+
+use fitter::SolutionState;
+
+use crate::{budget, parse_input, print_status, solve_deadline, Args};
+
+pub fn solve(stream: &mut impl BufRead, max_fragments: usize, args: &Args) -> anyhow::Result<()> {
+    let (bin_capacity, weights) = parse_input(stream, &args.input)?;
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    // every fragment in its own bin
+    let mut upper_bound = 0;
+    for &weight in &weights {
+        let fragments = weight.div_ceil(bin_capacity.max(1)) as usize;
+        if bin_capacity == 0 && weight > 0 || fragments > max_fragments.max(1) {
+            print_status("UNSAT");
+            return Ok(());
+        }
+
+        upper_bound += fragments;
+    }
+
+    let total_weight: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    let lower_bound = total_weight.div_ceil(bin_capacity.max(1) as u64) as usize;
+
+    let mut solution = SolutionState::Unknown;
+    for num_bins in lower_bound..=upper_bound {
+        log::info!("Trying to fit in {num_bins} bins");
+
+        let packer = fitter::splitting::SplitPacker::new(
+            weights.clone(),
+            vec![bin_capacity; num_bins],
+            max_fragments,
+        )
+        .with_split_penalty(args.split_penalty);
+
+        let budget = budget(deadline);
+        let packing = packer.solve_until(|| budget.remains());
+
+        match packing {
+            SolutionState::Unsolvable => continue,
+            packing => {
+                solution = packing;
+                break;
+            }
+        }
+    }
+
+    match solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(packing) => {
+            print_status("SAT");
+            println!("o {}", packing.penalty);
+            log::info!("{} splits (optimal={})", packing.splits, packing.optimal);
+
+            if args.values {
+                for bin in packing.bins.iter().filter(|bin| !bin.is_empty()) {
+                    let line = bin
+                        .iter()
+                        .map(|fragment| match weights[fragment.item] {
+                            weight if weight == fragment.amount => weight.to_string(),
+                            weight => format!("{}({weight})", fragment.amount),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+
+                    println!("v {}", line);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
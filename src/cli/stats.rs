@@ -0,0 +1,115 @@
+use std::{cmp, collections::BTreeMap, io::BufRead};
+
+use fitter::bounds::{self, LowerBound};
+
+use super::json::Json;
+use crate::{parse_instance, Args, Format};
+
+/// The most buckets of the weight histogram of the `stats` subcommand.
+const HISTOGRAM_BUCKETS: u32 = 10;
+
+/// The width of the bar of the fullest bucket.
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Describes the instance at the start of `stream` for the `stats` subcommand.
+pub fn print(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let (capacity, weights) = parse_instance::<u32>(stream, &args.input)?;
+    let total: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    let mut counts: BTreeMap<u32, usize> = BTreeMap::new();
+    for &weight in &weights {
+        *counts.entry(weight).or_default() += 1;
+    }
+
+    let mut fields: Vec<(&str, Json)> = vec![
+        ("capacity", capacity.into()),
+        ("items", weights.len().into()),
+        ("total_weight", total.into()),
+    ];
+
+    let (Some((&min, _)), Some((&max, _))) = (counts.first_key_value(), counts.last_key_value())
+    else {
+        return print_description(args, fields, &[]);
+    };
+
+    let (&most_repeated, &copies) = counts
+        .iter()
+        .max_by_key(|&(&weight, &count)| (count, cmp::Reverse(weight)))
+        .expect("there are items");
+    fields.extend([
+        ("min", min.into()),
+        ("max", max.into()),
+        ("mean", (total as f64 / weights.len() as f64).into()),
+        (
+            "L1",
+            bounds::Trivial.lower_bound(&weights, &capacity).into(),
+        ),
+        (
+            "L2",
+            bounds::MartelloToth.lower_bound(&weights, &capacity).into(),
+        ),
+        ("distinct", counts.len().into()),
+        ("duplicates", (weights.len() - counts.len()).into()),
+        ("most_repeated", most_repeated.into()),
+        ("copies", copies.into()),
+    ]);
+
+    let width = (max - min) / HISTOGRAM_BUCKETS + 1;
+    let mut histogram = vec![0; ((max - min) / width + 1) as usize];
+    for (&weight, &count) in &counts {
+        histogram[((weight - min) / width) as usize] += count;
+    }
+
+    let buckets: Vec<(u32, u32, usize)> = histogram
+        .into_iter()
+        .enumerate()
+        .map(|(idx, count)| {
+            let from = min + idx as u32 * width;
+            (from, from + width - 1, count)
+        })
+        .collect();
+
+    print_description(args, fields, &buckets)
+}
+
+/// Writes the `stats` subcommand's description of an instance, a line for each of `fields` and
+/// each bucket of the histogram, or a JSON object with `--format json`.
+fn print_description(
+    args: &Args,
+    fields: Vec<(&str, Json)>,
+    histogram: &[(u32, u32, usize)],
+) -> anyhow::Result<()> {
+    if let Format::Json | Format::Jsonl = args.format {
+        let buckets = histogram.iter().map(|&(from, to, count)| {
+            Json::object([
+                ("from", from.into()),
+                ("to", to.into()),
+                ("count", count.into()),
+            ])
+        });
+
+        let mut fields = fields;
+        fields.push(("histogram", Json::Array(buckets.collect())));
+        match args.format {
+            Format::Json => println!("{:#}", Json::object(fields)),
+            _ => println!("{}", Json::object(fields)),
+        }
+
+        return Ok(());
+    }
+
+    for (name, value) in fields {
+        println!("{name} {value}");
+    }
+
+    let fullest = histogram
+        .iter()
+        .map(|&(_, _, count)| count)
+        .max()
+        .unwrap_or(1);
+    for &(from, to, count) in histogram {
+        let bar = "#".repeat(count * HISTOGRAM_WIDTH / fullest);
+        println!("histogram {from}-{to} {count} {bar}");
+    }
+
+    Ok(())
+}
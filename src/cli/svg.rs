@@ -0,0 +1,83 @@
+use crate::bin_positions;
+
+/// The height of the fullest bin in the `--format svg` diagram, the others are scaled to it.
+const SVG_BIN_HEIGHT: f64 = 300.;
+const SVG_BIN_WIDTH: f64 = 80.;
+const SVG_MARGIN: f64 = 20.;
+/// Room under the bins for their labels and fills.
+const SVG_FOOTER: f64 = 40.;
+
+/// Writes the `--format svg` diagram: a column for each of `bins` with its items stacked from
+/// the bottom, each named after `names` or its weight, and the bin's label and fill under it.
+pub fn print(input: &[u32], names: &[Option<String>], bins: &[fitter::Bin<u32>]) {
+    let largest = bins.iter().map(|bin| bin.size).max().unwrap_or(0).max(1);
+    let scale = SVG_BIN_HEIGHT / largest as f64;
+    let width = SVG_MARGIN + bins.len() as f64 * (SVG_BIN_WIDTH + SVG_MARGIN);
+    let height = SVG_MARGIN + SVG_BIN_HEIGHT + SVG_FOOTER;
+    println!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="sans-serif" font-size="12" text-anchor="middle">"#
+    );
+
+    let bottom = SVG_MARGIN + SVG_BIN_HEIGHT;
+    for (bin_idx, (bin, positions)) in bins.iter().zip(bin_positions(input, bins)).enumerate() {
+        let x = SVG_MARGIN + bin_idx as f64 * (SVG_BIN_WIDTH + SVG_MARGIN);
+        let center = x + SVG_BIN_WIDTH / 2.;
+        let size = bin.size as f64 * scale;
+        println!("<g>");
+
+        let mut top = bottom;
+        for (&weight, idx) in bin.items.iter().zip(positions) {
+            let item_height = weight as f64 * scale;
+            top -= item_height;
+            let (name, title) = match &names[idx] {
+                Some(name) => (xml_escape(name), format!("{} ({weight})", xml_escape(name))),
+                None => (weight.to_string(), weight.to_string()),
+            };
+
+            // a different hue for each item of the input, spread around the color wheel
+            let hue = idx * 137 % 360;
+            println!(
+                r#"<rect x="{x}" y="{top:.2}" width="{SVG_BIN_WIDTH}" height="{item_height:.2}" fill="hsl({hue}, 60%, 75%)" stroke="white"><title>{title}</title></rect>"#
+            );
+
+            if item_height >= 14. {
+                let y = top + item_height / 2. + 4.;
+                println!(r#"<text x="{center}" y="{y:.2}">{name}</text>"#);
+            }
+        }
+
+        let y = bottom - size;
+        println!(
+            r#"<rect x="{x}" y="{y:.2}" width="{SVG_BIN_WIDTH}" height="{size:.2}" fill="none" stroke="black"/>"#
+        );
+
+        let label = match &bin.label {
+            Some(label) => format!("#{}", xml_escape(label)),
+            None => bin_idx.to_string(),
+        };
+        let fill = bin.utilization() * 100.;
+        println!(r#"<text x="{center}" y="{}">{label}</text>"#, bottom + 16.);
+        println!(
+            r#"<text x="{center}" y="{}">{fill:.0}%</text>"#,
+            bottom + 32.
+        );
+        println!("</g>");
+    }
+
+    println!("</svg>");
+}
+
+/// The `--format svg` diagram for an instance without a packing to draw, with just `status`.
+pub fn print_message(status: &str) {
+    println!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="40" font-family="sans-serif" font-size="12"><text x="{SVG_MARGIN}" y="{SVG_MARGIN}">{status}</text></svg>"#
+    );
+}
+
+/// Escapes the characters of `text` that are markup in XML.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,254 @@
+use std::{
+    fs,
+    io::{self, BufRead},
+    path,
+};
+
+use fitter::knapsack;
+
+use super::{
+    json,
+    plain::{parse_copies, parse_header},
+};
+use crate::{has_instance_left, is_comment, parse_multi_instance, InputArgs, InputFormat, Named};
+
+/// A problem the `validate` subcommand found in an instance.
+struct Lint {
+    /// The line of the problem, from 1, if it is known.
+    line: Option<usize>,
+    error: bool,
+    message: String,
+}
+
+impl Lint {
+    fn error(line: Option<usize>, message: String) -> Self {
+        Self {
+            line,
+            error: true,
+            message,
+        }
+    }
+
+    fn warning(line: Option<usize>, message: String) -> Self {
+        Self {
+            line,
+            error: false,
+            message,
+        }
+    }
+}
+
+/// Checks the instances in `path`, or stdin, for the `validate` subcommand, writing each problem
+/// on a `path:line: error: message` line. Fails if any of them is an error.
+pub fn run(path: Option<&path::Path>, input: &InputArgs) -> anyhow::Result<()> {
+    let text = match path {
+        Some(path) => fs::read_to_string(path)?,
+        None => io::read_to_string(io::stdin())?,
+    };
+
+    let name = path.map_or("<stdin>".into(), |path| path.display().to_string());
+    let mut reader = io::Cursor::new(text.as_bytes());
+    let plain = match input.input_format {
+        InputFormat::Plain => true,
+        InputFormat::Auto => !json::looks_like(&mut reader)?,
+        _ => false,
+    };
+
+    let mut lints = match plain {
+        true => lint_plain(&text),
+        false => lint_parsed(&mut reader, input),
+    };
+    lints.sort_by_key(|lint| lint.line);
+
+    for lint in &lints {
+        let severity = if lint.error { "error" } else { "warning" };
+        match lint.line {
+            Some(line) => println!("{name}:{line}: {severity}: {}", lint.message),
+            None => println!("{name}: {severity}: {}", lint.message),
+        }
+    }
+
+    match lints.iter().filter(|lint| lint.error).count() {
+        0 if lints.is_empty() => println!("{name}: ok"),
+        0 => {}
+        errors => anyhow::bail!("{errors} errors in {name}"),
+    }
+
+    Ok(())
+}
+
+/// The problems of the plain instances in `text`, with their lines. Unlike
+/// [`super::plain::parse_instance`], which stops at the first, each problem is found.
+fn lint_plain(text: &str) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    // the line the instance being read starts on and its largest capacity, once it has one
+    let mut instance: Option<(usize, u32)> = None;
+    // the line of the header of the instance with the items and capacity it declares
+    let mut header: Option<(usize, usize, String)> = None;
+    let mut count = 0;
+    // whether the last token is an item an `x <count>` may repeat, and whether it did
+    let (mut after_item, mut repeat) = (false, false);
+    for (idx, line) in text.lines().enumerate() {
+        let number = Some(idx + 1);
+        if is_comment(line) {
+            continue;
+        }
+
+        let Some((_, largest)) = instance else {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(fields) = line.trim().strip_prefix("p ") {
+                match parse_header(fields) {
+                    Ok((items, capacity)) => header = Some((idx + 1, items, capacity)),
+                    Err(err) => lints.push(Lint::error(number, err.to_string())),
+                }
+
+                continue;
+            }
+
+            if let Some((_, _, capacity)) = &header {
+                if line.trim() != capacity {
+                    let message = format!("the capacities don't match the header's {capacity}");
+                    lints.push(Lint::error(number, message));
+                }
+            }
+
+            count = 0;
+            let mut largest = 0;
+            for capacity in line.split_whitespace() {
+                match capacity.parse::<u32>() {
+                    Ok(0) => lints.push(Lint::warning(
+                        number,
+                        "bin capacity 0, a repeated 0 after the items starts another instance"
+                            .to_string(),
+                    )),
+                    Ok(capacity) => largest = largest.max(capacity),
+                    Err(err) => lints.push(Lint::error(
+                        number,
+                        format!("invalid bin capacity {capacity:?}: {err}"),
+                    )),
+                }
+            }
+
+            instance = Some((idx + 1, largest));
+            continue;
+        };
+
+        let mut tokens = line.split_whitespace();
+        for token in tokens.by_ref() {
+            if repeat {
+                repeat = false;
+                match parse_copies(token, count) {
+                    Ok(copies) => count += copies - 1,
+                    Err(err) => lints.push(Lint::error(number, err.to_string())),
+                }
+
+                continue;
+            }
+
+            if token == "x" {
+                match after_item {
+                    true => repeat = true,
+                    false => lints.push(Lint::error(number, "expected an item before x".into())),
+                }
+
+                after_item = false;
+                continue;
+            }
+
+            if token.parse::<u32>().is_ok_and(|num| num == 0) {
+                if let Some((line, items, _)) =
+                    header.take().filter(|&(_, items, _)| items != count)
+                {
+                    let message = format!("the header declares {items} items, found {count}");
+                    lints.push(Lint::error(Some(line), message));
+                }
+
+                instance = None;
+                break;
+            }
+
+            count += 1;
+            after_item = true;
+            let lint = match token.parse::<Named<knapsack::Item>>() {
+                Ok(named) if named.item.weight == 0 => {
+                    Lint::error(number, format!("item {token:?} has a weight of 0"))
+                }
+                // a capacity of 0 is already warned about
+                Ok(named) if largest > 0 && named.item.weight > largest => Lint::error(
+                    number,
+                    format!("item {token:?} is larger than the bin capacity {largest}"),
+                ),
+                Ok(_) => continue,
+                Err(_) if token.starts_with('-') || token.contains(":-") => {
+                    Lint::error(number, format!("item {token:?} has a negative weight"))
+                }
+                Err(err) => Lint::error(number, format!("invalid item {token:?}: {err}")),
+            };
+
+            lints.push(lint);
+        }
+
+        let ignored = tokens.count();
+        if instance.is_none() && ignored > 0 {
+            lints.push(Lint::warning(
+                number,
+                format!("{ignored} items after the 0 ending the instance are ignored"),
+            ));
+        }
+    }
+
+    if let Some((start, _)) = instance {
+        lints.push(Lint::error(
+            Some(start),
+            "the items of the instance starting here don't end with a 0".to_string(),
+        ));
+    }
+
+    lints
+}
+
+/// The problems of the instances in `reader` in the other formats, which only get as far as
+/// the first error of each instance, without lines.
+fn lint_parsed(reader: &mut impl BufRead, input: &InputArgs) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    for instance in 1.. {
+        match has_instance_left(reader) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => {
+                lints.push(Lint::error(None, err.to_string()));
+                break;
+            }
+        }
+
+        let (capacities, items) = match parse_multi_instance::<Named<knapsack::Item>>(reader, input)
+        {
+            Ok(instance) => instance,
+            Err(err) => {
+                lints.push(Lint::error(None, format!("instance {instance}: {err}")));
+                break;
+            }
+        };
+
+        let largest = capacities.iter().copied().max().unwrap_or(0);
+        for (idx, named) in items.iter().enumerate() {
+            let weight = named.item.weight;
+            let message = match weight {
+                0 => format!("instance {instance}: item {} has a weight of 0", idx + 1),
+                _ if weight > largest => format!(
+                    "instance {instance}: item {} of weight {weight} is larger than the bin \
+                     capacity {largest}",
+                    idx + 1
+                ),
+                _ => continue,
+            };
+
+            lints.push(Lint::error(None, message));
+        }
+    }
+
+    lints
+}
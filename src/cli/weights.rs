@@ -0,0 +1,85 @@
+use core::fmt;
+use std::{error, hash, io::BufRead, iter, ops, str, time};
+
+use fitter::{
+    solver::{SolveOutcome, Solver},
+    SolutionState,
+};
+
+use crate::{
+    budget, parse_bins_instance, print_solution, print_stats, print_status, progress_reporter,
+    solve_deadline, Args,
+};
+
+/// Solves an instance with weights of type `T`, like [`Float`], [`Rational`] or [`Span`].
+pub fn solve<T>(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()>
+where
+    T: Ord + Clone + hash::Hash + Default + fmt::Display + fmt::Debug + str::FromStr,
+    T: for<'a> iter::Sum<&'a T> + for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+    T::Err: error::Error + Send + Sync + 'static,
+{
+    let (capacities, weights) = parse_bins_instance::<T, T>(stream, &args.input)?;
+    let [bin_capacity] = &capacities[..] else {
+        anyhow::bail!("expected a single bin capacity, found {}", capacities.len());
+    };
+
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let total_weight: T = weights.iter().sum();
+    let mut solution = SolutionState::Unknown;
+    let mut max_bins = args
+        .bins
+        .map_or(weights.len(), |bins| bins.min(weights.len()));
+    let mut solver = fitter::Fitter::new(weights, Vec::new());
+    solver.set_progress(progress_reporter(args));
+    loop {
+        log::info!("Trying to fit in {max_bins} bins");
+
+        let mut total_size = T::default();
+        (0..max_bins).for_each(|_| total_size += bin_capacity);
+        if total_weight > total_size {
+            solution.insert(SolutionState::Unsolvable);
+            break;
+        }
+
+        solver.set_bins(vec![fitter::Bin::new(bin_capacity.clone()); max_bins]);
+        let outcome = solver.solve(budget(deadline));
+
+        if outcome == SolveOutcome::Budget {
+            break;
+        }
+
+        if !solver.is_solved() {
+            solution.insert(SolutionState::Unsolvable);
+            break;
+        }
+
+        let bins = solver.packed_bins();
+        let enough = args.min_bins.is_some_and(|min_bins| bins.len() <= min_bins);
+        max_bins = bins.len().saturating_sub(1);
+        solution = SolutionState::Solved(bins);
+        if max_bins == 0 || !args.minimize || enough {
+            break;
+        }
+    }
+
+    match &solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(solution) => {
+            print_status("SAT");
+
+            if args.values {
+                print_solution(solution);
+            }
+        }
+    }
+
+    if let Some(format) = args.stats {
+        let elapsed = solve_start.elapsed();
+        print_stats(format, &solution, Some(solver.stats()), elapsed, None);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! Wall-clock time, which only exists with the `std` feature. Without it no time passes.
+
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Stopwatch(std::time::Instant);
+
+#[cfg(feature = "std")]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self(std::time::Instant::now())
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Stopwatch;
+
+#[cfg(not(feature = "std"))]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
@@ -0,0 +1,203 @@
+use alloc::{vec, vec::Vec};
+use core::{iter, ops};
+
+use crate::{
+    fits_in_total,
+    solver::{Budget, SolveOutcome, Solver},
+    Bin,
+};
+
+/// Bin completion: fills one bin at a time, branching on which items complete it instead of
+/// on where each item goes. Only maximal completions are tried, those no remaining item still
+/// fits in, as any packing can be turned into one made of them.
+pub struct BinCompletion<T> {
+    pub items: Vec<T>,
+    pub bins: Vec<Bin<T>>,
+}
+
+impl<T> BinCompletion<T>
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    pub fn new(items: Vec<T>, bin_capacities: impl IntoIterator<Item = T>) -> Self {
+        Self::with_bins(items, bin_capacities.into_iter().map(Bin::new).collect())
+    }
+
+    /// Pack `items` around the items already in `bins`, which are never moved.
+    pub fn with_bins(mut items: Vec<T>, bins: Vec<Bin<T>>) -> Self {
+        items.sort_by(|a, b| b.cmp(a));
+        Self { items, bins }
+    }
+
+    /// Completes the bins from `bin` on with the items not yet `used`.
+    fn pack(&mut self, bin: usize, used: &mut [bool], budget: &Budget) -> SolveOutcome {
+        if !budget.remains() {
+            return SolveOutcome::Budget;
+        }
+
+        let free: Vec<usize> = (0..self.items.len()).filter(|&idx| !used[idx]).collect();
+        if free.is_empty() {
+            return SolveOutcome::Solved;
+        }
+
+        let Some(current) = self.bins.get(bin) else {
+            return SolveOutcome::Exhausted;
+        };
+
+        let free_weights = free.iter().map(|&idx| &self.items[idx]);
+        let free_capacities = self.bins[bin..].iter().map(|bin| &bin.capacity);
+        if !fits_in_total(free_weights, free_capacities) {
+            return SolveOutcome::Exhausted;
+        }
+
+        // when the bins left are interchangeable, the largest item may as well go in this one
+        let interchangeable = self.bins[bin..]
+            .iter()
+            .all(|other| other.is_empty() && other.capacity == current.capacity);
+        let (forced, candidates) = match interchangeable {
+            true if !current.fits(&self.items[free[0]]) => return SolveOutcome::Exhausted,
+            true => (Some(free[0]), &free[1..]),
+            false => (None, &free[..]),
+        };
+
+        if let Some(largest) = forced {
+            used[largest] = true;
+            self.bins[bin].push(self.items[largest].clone());
+        }
+
+        let mut stack = vec![Frame {
+            pos: 0,
+            excluded: None,
+            state: State::Enter,
+        }];
+        let mut outcome = SolveOutcome::Exhausted;
+        while self.next_completion(bin, candidates, &mut stack, used) {
+            outcome = match budget.remains() {
+                true => self.pack(bin + 1, used, budget),
+                false => SolveOutcome::Budget,
+            };
+
+            if outcome != SolveOutcome::Exhausted {
+                break;
+            }
+        }
+
+        if outcome == SolveOutcome::Solved {
+            return outcome;
+        }
+
+        for frame in stack {
+            if frame.state == State::Included {
+                self.bins[bin].pop();
+                used[candidates[frame.pos]] = false;
+            }
+        }
+
+        if let Some(largest) = forced {
+            self.bins[bin].pop();
+            used[largest] = false;
+        }
+
+        outcome
+    }
+
+    /// Puts the next maximal completion of `bin` with the `candidates` in it, largest items
+    /// first, taking back the previous one. Returns false once there are no more.
+    ///
+    /// The `stack` walks the choices to put each candidate in or leave it out, without recursing
+    /// as deep as there are items.
+    fn next_completion(
+        &mut self,
+        bin: usize,
+        candidates: &[usize],
+        stack: &mut Vec<Frame>,
+        used: &mut [bool],
+    ) -> bool {
+        while let Some(frame) = stack.last_mut() {
+            let Some(&item) = candidates.get(frame.pos) else {
+                let excluded = frame.excluded;
+                stack.pop();
+                if excluded.is_none_or(|idx| !self.bins[bin].fits(&self.items[idx])) {
+                    return true;
+                }
+
+                continue;
+            };
+
+            if frame.state == State::Enter && self.bins[bin].fits(&self.items[item]) {
+                used[item] = true;
+                self.bins[bin].push(self.items[item].clone());
+                frame.state = State::Included;
+                let child = Frame {
+                    pos: frame.pos + 1,
+                    excluded: frame.excluded,
+                    state: State::Enter,
+                };
+                stack.push(child);
+                continue;
+            }
+
+            if frame.state == State::Excluded {
+                stack.pop();
+                continue;
+            }
+
+            if frame.state == State::Included {
+                self.bins[bin].pop();
+                used[item] = false;
+            }
+
+            // leaving out one copy of a weight leaves out the ones after it, the completions
+            // with an earlier copy are the same
+            let next = candidates[frame.pos..]
+                .iter()
+                .position(|&idx| self.items[idx] != self.items[item])
+                .map_or(candidates.len(), |copies| frame.pos + copies);
+            frame.state = State::Excluded;
+            let child = Frame {
+                pos: next,
+                excluded: Some(candidates[next - 1]),
+                state: State::Enter,
+            };
+            stack.push(child);
+        }
+
+        false
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    Enter,
+    Included,
+    Excluded,
+}
+
+/// A candidate of [`BinCompletion::next_completion`] and whether it was put in the bin.
+struct Frame {
+    pos: usize,
+    /// The smallest candidate left out before this one.
+    excluded: Option<usize>,
+    state: State,
+}
+
+impl<T> Solver<T> for BinCompletion<T>
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    fn solve(&mut self, budget: Budget) -> SolveOutcome {
+        let mut used = vec![false; self.items.len()];
+        let outcome = self.pack(0, &mut used, &budget);
+        if outcome == SolveOutcome::Solved {
+            self.items.clear();
+        }
+
+        outcome
+    }
+
+    fn bins(&self) -> &[Bin<T>] {
+        &self.bins
+    }
+}
@@ -0,0 +1,666 @@
+use std::collections::HashMap;
+
+use crate::SolutionState;
+
+/// A side constraint on the contents of a bin, items are referred to by their index.
+pub trait Constraint {
+    /// Whether `item` may be added to a bin already holding `bin`.
+    fn allows(&self, bin: &[usize], item: usize) -> bool;
+
+    /// Whether a bin is acceptable once all items are placed.
+    fn accepts(&self, _bin: &[usize]) -> bool {
+        true
+    }
+
+    /// Whether the partial packing in `bins` can still be completed with the `unplaced` items,
+    /// returning false prunes the branch.
+    fn viable(&self, _bins: &[Vec<usize>], _unplaced: &[usize]) -> bool {
+        true
+    }
+
+    /// Whether the weights of the items in a bin add up against its capacity. Constraints that
+    /// return false are responsible for checking the capacity in [`Constraint::allows`].
+    fn additive(&self) -> bool {
+        true
+    }
+
+    /// Whether the empty bins `a` and `b` are interchangeable when they have the same capacity,
+    /// only one of them is tried.
+    fn interchangeable(&self, _a: usize, _b: usize) -> bool {
+        true
+    }
+}
+
+/// Backtracking search for bin packing with side constraints.
+pub struct ConstrainedPacker<C> {
+    pub weights: Vec<u32>,
+    pub capacities: Vec<u32>,
+    pub constraint: C,
+}
+
+impl<C: Constraint> ConstrainedPacker<C> {
+    pub fn new(weights: Vec<u32>, capacities: Vec<u32>, constraint: C) -> Self {
+        Self {
+            weights,
+            capacities,
+            constraint,
+        }
+    }
+
+    /// Returns the item indices in each bin.
+    pub fn solve_until(&self, predicate: impl FnMut() -> bool) -> SolutionState<Vec<Vec<usize>>> {
+        let mut order: Vec<usize> = (0..self.weights.len()).collect();
+        order.sort_by_key(|&idx| std::cmp::Reverse(self.weights[idx]));
+
+        let mut search = ConstrainedSearch {
+            packer: self,
+            order,
+            remaining: self.capacities.iter().map(|&c| c as u64).collect(),
+            remaining_weight: self.weights.iter().map(|&w| w as u64).sum(),
+            bins: vec![Vec::new(); self.capacities.len()],
+            predicate,
+            aborted: false,
+        };
+
+        if search.search(0) {
+            SolutionState::Solved(search.bins)
+        } else if search.aborted {
+            SolutionState::Unknown
+        } else {
+            SolutionState::Unsolvable
+        }
+    }
+}
+
+struct ConstrainedSearch<'a, C, P> {
+    packer: &'a ConstrainedPacker<C>,
+    order: Vec<usize>,
+    remaining: Vec<u64>,
+    remaining_weight: u64,
+    bins: Vec<Vec<usize>>,
+    predicate: P,
+    aborted: bool,
+}
+
+impl<C: Constraint, P: FnMut() -> bool> ConstrainedSearch<'_, C, P> {
+    fn capacity_left(&self, bin_idx: usize) -> u64 {
+        let load: u64 = self.bins[bin_idx]
+            .iter()
+            .map(|&item| self.packer.weights[item] as u64)
+            .sum();
+
+        (self.packer.capacities[bin_idx] as u64).saturating_sub(load)
+    }
+
+    fn search(&mut self, pos: usize) -> bool {
+        if !(self.predicate)() {
+            self.aborted = true;
+            return false;
+        }
+
+        if !self
+            .packer
+            .constraint
+            .viable(&self.bins, &self.order[pos..])
+        {
+            return false;
+        }
+
+        let Some(&item) = self.order.get(pos) else {
+            let constraint = &self.packer.constraint;
+            return self.bins.iter().all(|bin| constraint.accepts(bin));
+        };
+
+        let additive = self.packer.constraint.additive();
+        if additive && self.remaining.iter().sum::<u64>() < self.remaining_weight {
+            return false;
+        }
+
+        let weight = self.packer.weights[item] as u64;
+        for bin_idx in 0..self.bins.len() {
+            if additive && self.remaining[bin_idx] < weight
+                || !self.packer.constraint.allows(&self.bins[bin_idx], item)
+            {
+                continue;
+            }
+
+            // empty bins of the same capacity are interchangeable
+            let capacity = self.packer.capacities[bin_idx];
+            if self.bins[bin_idx].is_empty()
+                && (0..bin_idx).any(|prev| {
+                    self.bins[prev].is_empty()
+                        && self.packer.capacities[prev] == capacity
+                        && self.packer.constraint.interchangeable(prev, bin_idx)
+                })
+            {
+                continue;
+            }
+
+            self.remaining[bin_idx] = self.remaining[bin_idx].saturating_sub(weight);
+            self.remaining_weight -= weight;
+            self.bins[bin_idx].push(item);
+
+            if self.search(pos + 1) {
+                return true;
+            }
+
+            self.bins[bin_idx].pop();
+            self.remaining_weight += weight;
+            self.remaining[bin_idx] = self.capacity_left(bin_idx);
+
+            if self.aborted {
+                return false;
+            }
+        }
+
+        false
+    }
+}
+
+/// Items of the same color are limited to `max_per_bin` per bin and, with `alternate`, a bin
+/// must be arrangeable so that no two consecutive items share a color. Uncolored items never
+/// conflict.
+pub struct ColorConstraint {
+    pub colors: Vec<Option<usize>>,
+    pub max_per_bin: Option<usize>,
+    pub alternate: bool,
+}
+
+impl ColorConstraint {
+    pub fn new(colors: Vec<Option<usize>>) -> Self {
+        Self {
+            colors,
+            max_per_bin: None,
+            alternate: false,
+        }
+    }
+
+    fn color_counts(&self, bin: &[usize]) -> HashMap<usize, usize> {
+        let mut counts = HashMap::new();
+        for color in bin.iter().filter_map(|&item| self.colors[item]) {
+            *counts.entry(color).or_default() += 1;
+        }
+
+        counts
+    }
+
+    /// Orders the items of a bin so that no two consecutive items share a color, if possible.
+    pub fn arrange(&self, bin: &[usize]) -> Vec<usize> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of_color = HashMap::new();
+        for &item in bin {
+            match self.colors[item] {
+                Some(color) => {
+                    let group = *group_of_color.entry(color).or_insert_with(|| {
+                        groups.push(Vec::new());
+                        groups.len() - 1
+                    });
+
+                    groups[group].push(item);
+                }
+                None => groups.push(vec![item]),
+            }
+        }
+
+        // always continue with the largest group that differs from the previous one
+        let mut arranged = Vec::with_capacity(bin.len());
+        let mut prev_group = None;
+        while arranged.len() < bin.len() {
+            let group = (0..groups.len())
+                .filter(|&group| !groups[group].is_empty())
+                .max_by_key(|&group| (Some(group) != prev_group, groups[group].len()))
+                .unwrap();
+
+            arranged.push(groups[group].pop().unwrap());
+            prev_group = Some(group);
+        }
+
+        arranged
+    }
+}
+
+impl Constraint for ColorConstraint {
+    fn allows(&self, bin: &[usize], item: usize) -> bool {
+        let (Some(max_per_bin), Some(color)) = (self.max_per_bin, self.colors[item]) else {
+            return true;
+        };
+
+        let same_color = bin
+            .iter()
+            .filter(|&&other| self.colors[other] == Some(color))
+            .count();
+
+        same_color < max_per_bin
+    }
+
+    fn accepts(&self, bin: &[usize]) -> bool {
+        if !self.alternate {
+            return true;
+        }
+
+        let most_common = self.color_counts(bin).into_values().max().unwrap_or(0);
+        most_common <= bin.len() - most_common + 1
+    }
+}
+
+/// Bins may hold items of at most `max_classes` distinct classes, unlabeled items don't count
+/// towards the limit.
+pub struct ClassConstraint {
+    pub classes: Vec<Option<usize>>,
+    pub max_classes: usize,
+}
+
+impl ClassConstraint {
+    pub fn new(classes: Vec<Option<usize>>, max_classes: usize) -> Self {
+        Self {
+            classes,
+            max_classes,
+        }
+    }
+
+    pub fn num_classes(&self, bin: &[usize]) -> usize {
+        let mut classes: Vec<usize> = bin.iter().filter_map(|&item| self.classes[item]).collect();
+        classes.sort_unstable();
+        classes.dedup();
+        classes.len()
+    }
+}
+
+impl Constraint for ClassConstraint {
+    fn allows(&self, bin: &[usize], item: usize) -> bool {
+        let Some(class) = self.classes[item] else {
+            return true;
+        };
+
+        let is_new = !bin.iter().any(|&other| self.classes[other] == Some(class));
+        !is_new || self.num_classes(bin) < self.max_classes
+    }
+}
+
+/// A bin holding a fragile item may not be loaded beyond the item's fragility threshold.
+pub struct FragileConstraint {
+    pub weights: Vec<u32>,
+    pub fragility: Vec<Option<u32>>,
+}
+
+impl FragileConstraint {
+    pub fn new(weights: Vec<u32>, fragility: Vec<Option<u32>>) -> Self {
+        Self { weights, fragility }
+    }
+
+    /// The lowest fragility threshold in the bin, if any.
+    pub fn threshold(&self, bin: &[usize]) -> Option<u32> {
+        bin.iter().filter_map(|&item| self.fragility[item]).min()
+    }
+}
+
+impl Constraint for FragileConstraint {
+    fn allows(&self, bin: &[usize], item: usize) -> bool {
+        let threshold = self
+            .threshold(bin)
+            .into_iter()
+            .chain(self.fragility[item])
+            .min();
+        let Some(threshold) = threshold else {
+            return true;
+        };
+
+        let load: u64 = bin
+            .iter()
+            .chain([&item])
+            .map(|&other| self.weights[other] as u64)
+            .sum();
+
+        load <= threshold as u64
+    }
+}
+
+/// Every bin that isn't empty has to be loaded to at least `min_fill`.
+pub struct MinFillConstraint {
+    pub weights: Vec<u32>,
+    pub min_fill: u64,
+}
+
+impl MinFillConstraint {
+    pub fn new(weights: Vec<u32>, min_fill: u64) -> Self {
+        Self { weights, min_fill }
+    }
+
+    fn load(&self, bin: &[usize]) -> u64 {
+        bin.iter().map(|&item| self.weights[item] as u64).sum()
+    }
+}
+
+impl Constraint for MinFillConstraint {
+    fn allows(&self, _bin: &[usize], _item: usize) -> bool {
+        true
+    }
+
+    fn accepts(&self, bin: &[usize]) -> bool {
+        bin.is_empty() || self.load(bin) >= self.min_fill
+    }
+
+    /// The unplaced items have to make up for the underfilled bins.
+    fn viable(&self, bins: &[Vec<usize>], unplaced: &[usize]) -> bool {
+        let deficit: u64 = bins
+            .iter()
+            .filter(|bin| !bin.is_empty())
+            .map(|bin| self.min_fill.saturating_sub(self.load(bin)))
+            .sum();
+
+        deficit <= self.load(unplaced)
+    }
+}
+
+/// Items only occupy their bin during the half-open interval `[start, end)`, the load of a bin
+/// may not exceed `capacity` at any instant.
+pub struct TemporalConstraint {
+    pub weights: Vec<u32>,
+    pub intervals: Vec<(u32, u32)>,
+    pub capacity: u32,
+}
+
+impl TemporalConstraint {
+    pub fn new(weights: Vec<u32>, intervals: Vec<(u32, u32)>, capacity: u32) -> Self {
+        Self {
+            weights,
+            intervals,
+            capacity,
+        }
+    }
+
+    fn overlaps(&self, a: usize, b: usize) -> bool {
+        let (a_start, a_end) = self.intervals[a];
+        let (b_start, b_end) = self.intervals[b];
+        a_start < b_end && b_start < a_end
+    }
+
+    /// The highest load of the bin during the interval of `item`.
+    pub fn peak_load(&self, bin: &[usize], item: usize) -> u64 {
+        let (start, end) = self.intervals[item];
+        let overlapping: Vec<usize> = bin
+            .iter()
+            .copied()
+            .filter(|&other| self.overlaps(item, other))
+            .collect();
+
+        // the load only increases at the start of an item
+        let instants = overlapping
+            .iter()
+            .map(|&other| self.intervals[other].0)
+            .filter(|&instant| start < instant && instant < end)
+            .chain([start]);
+
+        instants
+            .map(|instant| {
+                overlapping
+                    .iter()
+                    .filter(|&&other| {
+                        let (other_start, other_end) = self.intervals[other];
+                        other_start <= instant && instant < other_end
+                    })
+                    .map(|&other| self.weights[other] as u64)
+                    .sum()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Constraint for TemporalConstraint {
+    fn allows(&self, bin: &[usize], item: usize) -> bool {
+        self.peak_load(bin, item) + self.weights[item] as u64 <= self.capacity as u64
+    }
+
+    fn additive(&self) -> bool {
+        false
+    }
+}
+
+/// Bins belong to groups (e.g. the bins of a warehouse), each group may open at most
+/// `max_bins[group]` of its bins and hold at most `max_weight[group]` in total.
+pub struct GroupConstraint {
+    pub weights: Vec<u32>,
+    /// The group of each bin.
+    pub groups: Vec<usize>,
+    pub max_bins: Vec<Option<usize>>,
+    pub max_weight: Vec<Option<u64>>,
+    /// Limit on the bins opened across all groups.
+    pub max_open: Option<usize>,
+}
+
+impl GroupConstraint {
+    pub fn new(weights: Vec<u32>, groups: Vec<usize>) -> Self {
+        let num_groups = groups.iter().max().map_or(0, |&group| group + 1);
+        Self {
+            weights,
+            groups,
+            max_bins: vec![None; num_groups],
+            max_weight: vec![None; num_groups],
+            max_open: None,
+        }
+    }
+
+    /// The number of open bins and the total weight in each group.
+    pub fn usage(&self, bins: &[Vec<usize>]) -> Vec<(usize, u64)> {
+        let mut usage = vec![(0, 0); self.max_bins.len()];
+        for (bin, &group) in bins.iter().zip(&self.groups) {
+            if !bin.is_empty() {
+                usage[group].0 += 1;
+                usage[group].1 += bin
+                    .iter()
+                    .map(|&item| self.weights[item] as u64)
+                    .sum::<u64>();
+            }
+        }
+
+        usage
+    }
+}
+
+impl Constraint for GroupConstraint {
+    fn allows(&self, _bin: &[usize], _item: usize) -> bool {
+        true
+    }
+
+    fn viable(&self, bins: &[Vec<usize>], _unplaced: &[usize]) -> bool {
+        let usage = self.usage(bins);
+        let open: usize = usage.iter().map(|&(open, _)| open).sum();
+        let within_limits = usage.iter().enumerate().all(|(group, &(open, weight))| {
+            self.max_bins[group].is_none_or(|max_bins| open <= max_bins)
+                && self.max_weight[group].is_none_or(|max_weight| weight <= max_weight)
+        });
+
+        within_limits && self.max_open.is_none_or(|max_open| open <= max_open)
+    }
+
+    fn interchangeable(&self, a: usize, b: usize) -> bool {
+        self.groups[a] == self.groups[b]
+    }
+}
+
+impl<C: Constraint + ?Sized> Constraint for &C {
+    fn allows(&self, bin: &[usize], item: usize) -> bool {
+        (**self).allows(bin, item)
+    }
+
+    fn accepts(&self, bin: &[usize]) -> bool {
+        (**self).accepts(bin)
+    }
+
+    fn viable(&self, bins: &[Vec<usize>], unplaced: &[usize]) -> bool {
+        (**self).viable(bins, unplaced)
+    }
+
+    fn additive(&self) -> bool {
+        (**self).additive()
+    }
+
+    fn interchangeable(&self, a: usize, b: usize) -> bool {
+        (**self).interchangeable(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve<C: Constraint>(
+        weights: &[u32],
+        capacities: &[u32],
+        constraint: C,
+    ) -> SolutionState<Vec<Vec<usize>>> {
+        ConstrainedPacker::new(weights.to_vec(), capacities.to_vec(), constraint)
+            .solve_until(|| true)
+    }
+
+    #[test]
+    fn limits_the_items_of_a_color_per_bin() {
+        let mut colors = ColorConstraint::new(vec![Some(0), Some(0), Some(0), Some(1)]);
+        colors.max_per_bin = Some(2);
+        let SolutionState::Solved(bins) = solve(&[5, 5, 5, 5], &[20, 20], &colors) else {
+            panic!("two items of color 0 fit in each bin");
+        };
+
+        for bin in &bins {
+            let same_color = bin.iter().filter(|&&item| colors.colors[item] == Some(0));
+            assert!(same_color.count() <= 2, "{bins:?}");
+        }
+
+        colors.max_per_bin = Some(1);
+        assert_eq!(
+            solve(&[5, 5, 5, 5], &[20, 20], &colors),
+            SolutionState::Unsolvable
+        );
+    }
+
+    #[test]
+    fn alternates_the_colors_in_a_bin() {
+        let mut colors = ColorConstraint::new(vec![Some(0), Some(0), Some(1), None]);
+        colors.alternate = true;
+        let SolutionState::Solved(bins) = solve(&[1, 1, 1, 1], &[10], &colors) else {
+            panic!("0 1 0 and the uncolored item alternate");
+        };
+
+        let arranged = colors.arrange(&bins[0]);
+        assert_eq!(arranged.len(), 4);
+        for pair in arranged.windows(2) {
+            let (a, b) = (colors.colors[pair[0]], colors.colors[pair[1]]);
+            assert!(a.is_none() || a != b, "{arranged:?}");
+        }
+
+        colors.colors = vec![Some(0), Some(0), Some(0), Some(1)];
+        assert_eq!(
+            solve(&[1, 1, 1, 1], &[10], &colors),
+            SolutionState::Unsolvable
+        );
+    }
+
+    #[test]
+    fn items_only_conflict_while_their_intervals_overlap() {
+        let temporal = TemporalConstraint::new(vec![6, 6, 3], vec![(0, 5), (5, 10), (2, 7)], 10);
+        assert_eq!(temporal.peak_load(&[0, 1], 2), 6);
+        assert!(matches!(
+            solve(&[6, 6], &[10], &temporal),
+            SolutionState::Solved(_)
+        ));
+
+        let overlapping = TemporalConstraint::new(vec![6, 6], vec![(0, 5), (4, 10)], 10);
+        assert_eq!(
+            solve(&[6, 6], &[10], &overlapping),
+            SolutionState::Unsolvable
+        );
+        assert!(matches!(
+            solve(&[6, 6], &[10, 10], &overlapping),
+            SolutionState::Solved(_)
+        ));
+    }
+
+    #[test]
+    fn limits_the_classes_per_bin() {
+        let classes = ClassConstraint::new(vec![Some(0), Some(1), Some(2), None], 2);
+        assert_eq!(
+            solve(&[1, 1, 1, 1], &[10], &classes),
+            SolutionState::Unsolvable
+        );
+
+        let SolutionState::Solved(bins) = solve(&[1, 1, 1, 1], &[10, 10], &classes) else {
+            panic!("the three classes fit in two bins");
+        };
+
+        assert!(
+            bins.iter().all(|bin| classes.num_classes(bin) <= 2),
+            "{bins:?}"
+        );
+
+        // unlabeled items don't count towards the limit
+        let classes = ClassConstraint::new(vec![Some(0), Some(1), None, None], 2);
+        assert!(matches!(
+            solve(&[1, 1, 1, 1], &[10], &classes),
+            SolutionState::Solved(_)
+        ));
+    }
+
+    #[test]
+    fn fragile_items_lower_the_capacity_of_their_bin() {
+        let fragile = FragileConstraint::new(vec![5, 5, 2], vec![Some(8), None, Some(9)]);
+        assert_eq!(fragile.threshold(&[0, 1, 2]), Some(8));
+        assert_eq!(fragile.threshold(&[1]), None);
+        assert_eq!(
+            solve(&[5, 5, 2], &[20], &fragile),
+            SolutionState::Unsolvable
+        );
+
+        let SolutionState::Solved(bins) = solve(&[5, 5, 2], &[20, 20], &fragile) else {
+            panic!("the fragile item fits with the light one");
+        };
+
+        for bin in &bins {
+            let load: u32 = bin.iter().map(|&item| fragile.weights[item]).sum();
+            assert!(fragile
+                .threshold(bin)
+                .is_none_or(|threshold| load <= threshold));
+        }
+    }
+
+    #[test]
+    fn used_bins_are_filled_to_the_minimum() {
+        let min_fill = MinFillConstraint::new(vec![6, 3, 3], 10);
+        let SolutionState::Solved(bins) = solve(&[6, 3, 3], &[12, 12], &min_fill) else {
+            panic!("all three items fill one bin");
+        };
+
+        assert!(bins.iter().all(|bin| min_fill.accepts(bin)), "{bins:?}");
+        assert_eq!(bins.iter().filter(|bin| !bin.is_empty()).count(), 1);
+
+        let min_fill = MinFillConstraint::new(vec![6, 3, 3], 13);
+        assert_eq!(
+            solve(&[6, 3, 3], &[12, 12], &min_fill),
+            SolutionState::Unsolvable
+        );
+    }
+
+    #[test]
+    fn groups_limit_their_bins_and_weight() {
+        let mut groups = GroupConstraint::new(vec![5, 5, 5], vec![0, 0, 1]);
+        groups.max_bins[0] = Some(1);
+        let SolutionState::Solved(bins) = solve(&[5, 5, 5], &[10, 10, 10], &groups) else {
+            panic!("one bin of group 0 and the bin of group 1 hold the items");
+        };
+
+        assert_eq!(groups.usage(&bins), vec![(1, 10), (1, 5)]);
+
+        groups.max_weight[1] = Some(4);
+        assert_eq!(
+            solve(&[5, 5, 5], &[10, 10, 10], &groups),
+            SolutionState::Unsolvable
+        );
+
+        let mut groups = GroupConstraint::new(vec![5, 5, 5], vec![0, 0, 1]);
+        groups.max_open = Some(1);
+        assert_eq!(
+            solve(&[5, 5, 5], &[10, 10, 10], &groups),
+            SolutionState::Unsolvable
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::{cmp, iter, ops};
+
+use crate::{bounds::Total, SolutionState};
+
+/// Bin covering: every bin has to be filled to at least `threshold`, maximize the number of
+/// covered bins. Items that don't help cover a bin are left out.
+pub struct Coverer<T> {
+    pub items: Vec<T>,
+    pub threshold: T,
+}
+
+impl<T> Coverer<T>
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    pub fn new(mut items: Vec<T>, threshold: T) -> Self {
+        items.sort_by(|a, b| b.cmp(a));
+        Self { items, threshold }
+    }
+
+    pub fn covers(&self, bin: &[T]) -> bool {
+        let mut fill = iter::empty::<&T>().sum();
+        bin.iter()
+            .for_each(|item| fill_up(&mut fill, item, &self.threshold));
+        fill >= self.threshold
+    }
+
+    /// Number of bins the total weight could cover at most.
+    pub fn upper_bound(&self) -> usize {
+        let zero: T = iter::empty::<&T>().sum();
+        if self.threshold <= zero {
+            return self.items.len();
+        }
+
+        total(&self.items, &self.threshold).bins
+    }
+
+    /// Fills bins with the largest items first, opening a new bin once the current one is
+    /// covered.
+    pub fn greedy(&self) -> Vec<Vec<T>> {
+        let mut bins = Vec::new();
+        let mut current = Vec::new();
+        let mut fill: T = iter::empty::<&T>().sum();
+
+        // the largest item that doesn't overshoot, or the smallest one otherwise
+        let mut remaining = self.items.clone();
+        while !remaining.is_empty() {
+            let mut deficit = self.threshold.clone();
+            deficit -= &cmp::min(fill.clone(), self.threshold.clone());
+
+            let idx = remaining
+                .iter()
+                .position(|item| item <= &deficit)
+                .unwrap_or(remaining.len() - 1);
+
+            let item = remaining.remove(idx);
+            fill_up(&mut fill, &item, &self.threshold);
+            current.push(item);
+
+            if fill >= self.threshold {
+                bins.push(std::mem::take(&mut current));
+                fill = iter::empty::<&T>().sum();
+            }
+        }
+
+        bins
+    }
+
+    /// Searches for a way to cover `num_bins` bins while `predicate` returns true.
+    pub fn cover(
+        &self,
+        num_bins: usize,
+        predicate: impl FnMut() -> bool,
+    ) -> SolutionState<Vec<Vec<T>>> {
+        let suffix_sums = (0..=self.items.len())
+            .map(|idx| total(&self.items[idx..], &self.threshold))
+            .collect();
+
+        let mut search = CoverSearch {
+            items: &self.items,
+            threshold: &self.threshold,
+            suffix_sums,
+            fills: vec![iter::empty::<&T>().sum(); num_bins],
+            bins: vec![Vec::new(); num_bins],
+            predicate,
+            aborted: false,
+        };
+
+        if search.search(0) {
+            SolutionState::Solved(search.bins)
+        } else if search.aborted {
+            SolutionState::Unknown
+        } else {
+            SolutionState::Unsolvable
+        }
+    }
+}
+
+/// Adds `item` to `fill`, stopping at `threshold` as a bin isn't covered any better past it, and
+/// so the fill never overflows.
+fn fill_up<T>(fill: &mut T, item: &T, threshold: &T)
+where
+    T: Ord + Clone + for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    let mut deficit = threshold.clone();
+    deficit -= cmp::min(&*fill, threshold);
+    match item >= &deficit {
+        true => *fill = cmp::max(&*fill, threshold).clone(),
+        false => *fill += item,
+    }
+}
+
+/// The total weight of `items` in whole thresholds, counting at most a threshold for each item
+/// as it only goes in one bin.
+fn total<T>(items: &[T], threshold: &T) -> Total<T>
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    let mut total = Total::new();
+    for item in items {
+        total.add(cmp::min(item, threshold), threshold);
+    }
+
+    total
+}
+
+struct CoverSearch<'a, T, P> {
+    items: &'a [T],
+    threshold: &'a T,
+    suffix_sums: Vec<Total<T>>,
+    fills: Vec<T>,
+    bins: Vec<Vec<T>>,
+    predicate: P,
+    aborted: bool,
+}
+
+impl<T, P> CoverSearch<'_, T, P>
+where
+    T: Ord + Clone + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+    P: FnMut() -> bool,
+{
+    /// What the bins still need to be covered, in whole thresholds.
+    fn deficit(&self) -> Total<T> {
+        let mut deficit = Total::new();
+        for fill in self.fills.iter().filter(|fill| *fill < self.threshold) {
+            let mut missing = self.threshold.clone();
+            missing -= fill;
+            deficit.add(&missing, self.threshold);
+        }
+
+        deficit
+    }
+
+    fn search(&mut self, idx: usize) -> bool {
+        if !(self.predicate)() {
+            self.aborted = true;
+            return false;
+        }
+
+        if self.fills.iter().all(|fill| fill >= self.threshold) {
+            return true;
+        }
+
+        let deficit = self.deficit();
+        if self.suffix_sums[idx] < deficit {
+            return false;
+        }
+
+        let item = &self.items[idx];
+        for bin_idx in 0..self.fills.len() {
+            let fill = &self.fills[bin_idx];
+            if fill >= self.threshold {
+                continue;
+            }
+
+            // bins with the same fill are interchangeable
+            if self.fills[..bin_idx].contains(fill) {
+                continue;
+            }
+
+            let before = fill.clone();
+            fill_up(&mut self.fills[bin_idx], item, self.threshold);
+            self.bins[bin_idx].push(item.clone());
+
+            if self.search(idx + 1) {
+                return true;
+            }
+
+            self.bins[bin_idx].pop();
+            self.fills[bin_idx] = before;
+
+            if self.aborted {
+                return false;
+            }
+        }
+
+        // leave the item out
+        self.search(idx + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covers_the_most_bins() {
+        let coverer = Coverer::new(vec![7, 5, 5, 3, 2, 2, 1], 8);
+        assert_eq!(coverer.upper_bound(), 3);
+        assert!(coverer.covers(&[5, 3]));
+        assert!(!coverer.covers(&[5, 2]));
+
+        let SolutionState::Solved(bins) = coverer.cover(3, || true) else {
+            panic!("7 1, 5 3 and 5 2 2 cover three bins");
+        };
+        assert!(bins.iter().all(|bin| coverer.covers(bin)));
+        assert_eq!(coverer.cover(4, || true), SolutionState::Unsolvable);
+        assert_eq!(coverer.cover(3, || false), SolutionState::Unknown);
+    }
+
+    #[test]
+    fn fills_near_the_largest_weight_do_not_overflow() {
+        let coverer = Coverer::new(vec![3_000_000_000u32; 3], 4_000_000_000);
+        assert!(coverer.covers(&[3_000_000_000; 2]));
+        assert_eq!(coverer.upper_bound(), 2);
+        assert_eq!(coverer.greedy().len(), 1);
+        assert_eq!(coverer.cover(2, || true), SolutionState::Unsolvable);
+
+        // a bin takes at most a threshold of an item toward the bound
+        let coverer = Coverer::new(vec![u32::MAX, u32::MAX, 1], 2);
+        assert_eq!(coverer.upper_bound(), 2);
+        assert!(matches!(
+            coverer.cover(2, || true),
+            SolutionState::Solved(_)
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+use std::{cmp, fmt, hash, iter, num, ops, str};
+
+/// A floating-point weight that can be packed by [`crate::Fitter`].
+///
+/// Values are ordered with [`f64::total_cmp`] and rounded to multiples of [`Float::EPSILON`]
+/// after every operation, so rounding errors don't make a bin reject an item that exactly fills
+/// it (e.g. `0.1` and `0.2` in a bin of `0.3`).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Float(f64);
+
+impl Float {
+    pub const EPSILON: f64 = 1e-9;
+
+    pub fn new(value: f64) -> Self {
+        // dividing by the exact scale rounds to the closest float, like parsing would
+        let scale = Self::EPSILON.recip().round();
+        Self((value * scale).round() / scale + 0.)
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl From<f64> for Float {
+    fn from(value: f64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Float {}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl hash::Hash for Float {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl<'a> iter::Sum<&'a Float> for Float {
+    fn sum<I: Iterator<Item = &'a Float>>(iter: I) -> Self {
+        Self::new(iter.map(|value| value.0).sum())
+    }
+}
+
+impl ops::AddAssign<&Float> for Float {
+    fn add_assign(&mut self, rhs: &Float) {
+        *self = Self::new(self.0 + rhs.0);
+    }
+}
+
+impl ops::SubAssign<&Float> for Float {
+    fn sub_assign(&mut self, rhs: &Float) {
+        *self = Self::new(self.0 - rhs.0);
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseFloatError {
+    Invalid(num::ParseFloatError),
+    NotFinite,
+}
+
+impl fmt::Display for ParseFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => err.fmt(f),
+            Self::NotFinite => f.write_str("weights have to be finite"),
+        }
+    }
+}
+
+impl std::error::Error for ParseFloatError {}
+
+impl str::FromStr for Float {
+    type Err = ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: f64 = s.parse().map_err(ParseFloatError::Invalid)?;
+        if !value.is_finite() {
+            return Err(ParseFloatError::NotFinite);
+        }
+
+        Ok(Self::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounding_lets_items_fill_a_bin_exactly() {
+        let mut bin = crate::Bin::new(Float::new(0.3));
+        assert!(bin.try_push(Float::new(0.1)).is_ok());
+        assert!(bin.try_push(Float::new(0.2)).is_ok());
+        assert_eq!(bin.capacity, Float::new(0.));
+        assert!(bin.try_push(Float::new(1e-6)).is_err());
+
+        let total: Float = [0.1, 0.2].map(Float::new).iter().sum();
+        assert_eq!(total, Float::new(0.3));
+        assert_eq!(total.to_string(), "0.3");
+    }
+
+    #[test]
+    fn rounds_to_multiples_of_epsilon() {
+        assert_eq!(Float::new(1. + Float::EPSILON / 4.), Float::new(1.));
+        assert_ne!(Float::new(1. + Float::EPSILON), Float::new(1.));
+        assert!(Float::new(0.1) < Float::new(0.1 + Float::EPSILON));
+
+        // no negative zero to tell apart
+        let mut zero = Float::new(0.5);
+        zero -= &Float::new(0.5);
+        assert_eq!(zero.get().to_bits(), 0f64.to_bits());
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!("1.5".parse(), Ok(Float::new(1.5)));
+        assert_eq!("inf".parse::<Float>(), Err(ParseFloatError::NotFinite));
+        assert_eq!("NaN".parse::<Float>(), Err(ParseFloatError::NotFinite));
+        assert!(matches!(
+            "x".parse::<Float>(),
+            Err(ParseFloatError::Invalid(_))
+        ));
+    }
+}
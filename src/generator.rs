@@ -0,0 +1,126 @@
+//! Seeded random instances from the classic benchmark families.
+
+use std::str;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::batch::Instance;
+
+/// A family of random instances, see [`generate`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Family {
+    /// `count` items uniform in `min..=max`.
+    Uniform {
+        capacity: u32,
+        count: usize,
+        min: u32,
+        max: u32,
+    },
+    /// `triplets` groups of three items between a quarter and a half of the capacity that fill
+    /// a bin exactly, so the optimum is `triplets` bins.
+    Triplets { capacity: u32, triplets: usize },
+    /// `count` items from a Weibull distribution, clamped to `1..=capacity`.
+    Weibull {
+        capacity: u32,
+        count: usize,
+        shape: f64,
+        scale: f64,
+    },
+}
+
+impl Family {
+    /// Falkenauer's uniform class: items in `20..=100`, capacity 150.
+    pub fn falkenauer_u(count: usize) -> Self {
+        Self::Uniform {
+            capacity: 150,
+            count,
+            min: 20,
+            max: 100,
+        }
+    }
+
+    /// The same family with about `count` items, triplets round it down to a multiple of 3.
+    pub fn with_count(mut self, count: usize) -> Self {
+        match &mut self {
+            Self::Uniform { count: old, .. } | Self::Weibull { count: old, .. } => *old = count,
+            Self::Triplets { triplets, .. } => *triplets = count / 3,
+        }
+
+        self
+    }
+
+    /// Falkenauer's triplet class: capacity 1000, `count` is rounded down to a multiple of 3.
+    pub fn falkenauer_t(count: usize) -> Self {
+        Self::Triplets {
+            capacity: 1000,
+            triplets: count / 3,
+        }
+    }
+}
+
+/// The same `seed` gives the same instance.
+pub fn generate(family: Family, seed: u64) -> Instance<u32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    match family {
+        Family::Uniform {
+            capacity,
+            count,
+            min,
+            max,
+        } => Instance {
+            capacity,
+            items: (0..count).map(|_| rng.gen_range(min..=max)).collect(),
+        },
+        Family::Triplets { capacity, triplets } => {
+            let quarter = capacity / 4;
+            let mut items = Vec::with_capacity(3 * triplets);
+            for _ in 0..triplets {
+                // Falkenauer draws the first item from 380..=490 of 1000
+                let first = rng.gen_range(capacity * 38 / 100..=capacity * 49 / 100);
+                let second = rng.gen_range(quarter..=capacity - first - quarter);
+                items.extend([first, second, capacity - first - second]);
+            }
+
+            items.shuffle(&mut rng);
+            Instance { capacity, items }
+        }
+        Family::Weibull {
+            capacity,
+            count,
+            shape,
+            scale,
+        } => {
+            let items = (0..count)
+                .map(|_| {
+                    let uniform: f64 = rng.gen();
+                    let weight = scale * (-(1. - uniform).ln()).powf(1. / shape);
+                    (weight.round() as u32).clamp(1, capacity)
+                })
+                .collect();
+
+            Instance { capacity, items }
+        }
+    }
+}
+
+/// Parses the name of a family with its usual parameters and 120 items: `falkenauer-u`,
+/// `falkenauer-t` or `weibull`.
+impl str::FromStr for Family {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "falkenauer-u" | "uniform" => Ok(Self::falkenauer_u(120)),
+            "falkenauer-t" | "triplets" => Ok(Self::falkenauer_t(120)),
+            "weibull" => Ok(Self::Weibull {
+                capacity: 1000,
+                count: 120,
+                shape: 3.,
+                scale: 300.,
+            }),
+            _ => Err(format!(
+                "unknown class {s:?}, expected falkenauer-u, falkenauer-t or weibull"
+            )),
+        }
+    }
+}
@@ -0,0 +1,816 @@
+use std::{fmt, str};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rect {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    pub fn rotated(&self) -> Self {
+        Self::new(self.height, self.width)
+    }
+
+    pub fn fits_in(&self, other: &Rect) -> bool {
+        self.width <= other.width && self.height <= other.height
+    }
+
+    fn orientations(&self, allow_rotation: bool) -> impl Iterator<Item = (Rect, bool)> {
+        let rotated = (allow_rotation && self.width != self.height).then(|| (self.rotated(), true));
+        [(*self, false)].into_iter().chain(rotated)
+    }
+}
+
+impl fmt::Display for Rect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRectError(String);
+
+impl fmt::Display for ParseRectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rectangle {:?}, expected WxH", self.0)
+    }
+}
+
+impl std::error::Error for ParseRectError {}
+
+impl str::FromStr for Rect {
+    type Err = ParseRectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseRectError(s.to_string());
+        let (width, height) = s.split_once(['x', 'X']).ok_or_else(err)?;
+        let width = width.trim().parse().map_err(|_| err())?;
+        let height = height.trim().parse().map_err(|_| err())?;
+        Ok(Self::new(width, height))
+    }
+}
+
+/// An item placed on a sheet, `item` is the index into the packed slice.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub item: usize,
+    pub x: u32,
+    pub y: u32,
+    pub rect: Rect,
+    pub rotated: bool,
+}
+
+impl Placement {
+    pub fn right(&self) -> u32 {
+        self.x + self.rect.width
+    }
+
+    pub fn top(&self) -> u32 {
+        self.y + self.rect.height
+    }
+
+    pub fn overlaps(&self, other: &Placement) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.top()
+            && other.y < self.top()
+    }
+}
+
+impl fmt::Display for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{},{}", self.rect, self.x, self.y)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sheet {
+    pub size: Rect,
+    pub placements: Vec<Placement>,
+}
+
+impl Sheet {
+    pub fn new(size: Rect) -> Self {
+        Self {
+            size,
+            placements: Vec::new(),
+        }
+    }
+
+    pub fn used_area(&self) -> u64 {
+        self.placements.iter().map(|p| p.rect.area()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.placements.is_empty()
+    }
+
+    pub fn used_height(&self) -> u32 {
+        self.placements
+            .iter()
+            .map(Placement::top)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn accepts(&self, placement: &Placement) -> bool {
+        placement.right() <= self.size.width
+            && placement.top() <= self.size.height
+            && !self.placements.iter().any(|p| p.overlaps(placement))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Method {
+    Guillotine,
+    #[default]
+    MaxRects,
+    /// Exhaustive search, only practical for small item counts, keeping the max-rects packing
+    /// when stopped.
+    Exact,
+}
+
+impl str::FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "guillotine" => Ok(Self::Guillotine),
+            "max-rects" | "maxrects" => Ok(Self::MaxRects),
+            "exact" => Ok(Self::Exact),
+            _ => Err(format!(
+                "unknown method {s:?}, expected guillotine, max-rects or exact"
+            )),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Region {
+    x: u32,
+    y: u32,
+    size: Rect,
+}
+
+impl Region {
+    fn right(&self) -> u32 {
+        self.x + self.size.width
+    }
+
+    fn top(&self) -> u32 {
+        self.y + self.size.height
+    }
+
+    fn contains(&self, other: &Region) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right() <= self.right()
+            && other.top() <= self.top()
+    }
+
+    fn intersects(&self, p: &Placement) -> bool {
+        self.x < p.right() && p.x < self.right() && self.y < p.top() && p.y < self.top()
+    }
+}
+
+/// Free space bookkeeping of a sheet for the greedy heuristics.
+struct FreeSpace {
+    method: Method,
+    regions: Vec<Region>,
+}
+
+impl FreeSpace {
+    fn new(method: Method, size: Rect) -> Self {
+        Self {
+            method,
+            regions: vec![Region { x: 0, y: 0, size }],
+        }
+    }
+
+    /// Find the best region for `rect`, returns `(region index, orientation, rotated)`.
+    fn find(&self, rect: &Rect, allow_rotation: bool) -> Option<(usize, Rect, bool)> {
+        let mut best = None;
+        let mut best_score = (u64::MAX, u64::MAX);
+
+        for (idx, region) in self.regions.iter().enumerate() {
+            for (oriented, rotated) in rect.orientations(allow_rotation) {
+                if !oriented.fits_in(&region.size) {
+                    continue;
+                }
+
+                let dw = (region.size.width - oriented.width) as u64;
+                let dh = (region.size.height - oriented.height) as u64;
+                let score = match self.method {
+                    // best area fit
+                    Method::Guillotine => (region.size.area() - oriented.area(), dw.min(dh)),
+                    // best short side fit
+                    _ => (dw.min(dh), dw.max(dh)),
+                };
+
+                if score < best_score {
+                    best_score = score;
+                    best = Some((idx, oriented, rotated));
+                }
+            }
+        }
+
+        best
+    }
+
+    fn place(&mut self, region_idx: usize, placement: &Placement) {
+        match self.method {
+            Method::Guillotine => self.split_guillotine(region_idx, placement),
+            _ => self.split_max_rects(placement),
+        }
+    }
+
+    fn split_guillotine(&mut self, region_idx: usize, placement: &Placement) {
+        let region = self.regions.swap_remove(region_idx);
+        let rect = placement.rect;
+        let leftover_w = region.size.width - rect.width;
+        let leftover_h = region.size.height - rect.height;
+
+        // split along the shorter leftover axis
+        let (right, top) = if leftover_w <= leftover_h {
+            (
+                Rect::new(leftover_w, rect.height),
+                Rect::new(region.size.width, leftover_h),
+            )
+        } else {
+            (
+                Rect::new(leftover_w, region.size.height),
+                Rect::new(rect.width, leftover_h),
+            )
+        };
+
+        let right = Region {
+            x: placement.right(),
+            y: region.y,
+            size: right,
+        };
+        let top = Region {
+            x: region.x,
+            y: placement.top(),
+            size: top,
+        };
+
+        self.regions
+            .extend([right, top].into_iter().filter(|r| r.size.area() > 0));
+    }
+
+    fn split_max_rects(&mut self, p: &Placement) {
+        let mut new_regions = Vec::new();
+        self.regions.retain(|r| {
+            if !r.intersects(p) {
+                return true;
+            }
+
+            if p.x > r.x {
+                new_regions.push(Region {
+                    size: Rect::new(p.x - r.x, r.size.height),
+                    ..*r
+                });
+            }
+            if p.right() < r.right() {
+                new_regions.push(Region {
+                    x: p.right(),
+                    size: Rect::new(r.right() - p.right(), r.size.height),
+                    ..*r
+                });
+            }
+            if p.y > r.y {
+                new_regions.push(Region {
+                    size: Rect::new(r.size.width, p.y - r.y),
+                    ..*r
+                });
+            }
+            if p.top() < r.top() {
+                new_regions.push(Region {
+                    y: p.top(),
+                    size: Rect::new(r.size.width, r.top() - p.top()),
+                    ..*r
+                });
+            }
+
+            false
+        });
+
+        self.regions.extend(new_regions);
+
+        // prune regions contained in other regions
+        let mut idx = 0;
+        while idx < self.regions.len() {
+            let region = self.regions[idx];
+            let contained = self.regions.iter().enumerate().any(|(other_idx, other)| {
+                other_idx != idx && other.contains(&region) && (other != &region || other_idx < idx)
+            });
+
+            if contained {
+                self.regions.swap_remove(idx);
+            } else {
+                idx += 1;
+            }
+        }
+    }
+}
+
+/// Packs rectangles into identical sheets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Packer {
+    pub sheet: Rect,
+    pub method: Method,
+    pub allow_rotation: bool,
+}
+
+impl Packer {
+    pub fn new(sheet: Rect, method: Method) -> Self {
+        Self {
+            sheet,
+            method,
+            allow_rotation: false,
+        }
+    }
+
+    pub fn with_rotation(mut self, allow_rotation: bool) -> Self {
+        self.allow_rotation = allow_rotation;
+        self
+    }
+
+    /// Returns `None` if some item does not fit in an empty sheet.
+    pub fn pack(&self, items: &[Rect]) -> Option<Vec<Sheet>> {
+        self.pack_until(items, || true)
+    }
+
+    /// Like [`Packer::pack`], the exact method gives up when `should_continue` returns false,
+    /// keeping the packing of the heuristic.
+    pub fn pack_until(
+        &self,
+        items: &[Rect],
+        mut should_continue: impl FnMut() -> bool,
+    ) -> Option<Vec<Sheet>> {
+        if !items.iter().all(|item| {
+            item.orientations(self.allow_rotation)
+                .any(|(r, _)| r.fits_in(&self.sheet))
+        }) {
+            return None;
+        }
+
+        match self.method {
+            Method::Exact => Some(self.pack_exact(items, &mut should_continue)),
+            _ => Some(self.pack_greedy(items)),
+        }
+    }
+
+    /// Strip packing: packs every item into a single sheet of width `self.sheet.width`,
+    /// minimizing the height used. The sheet height is ignored.
+    ///
+    /// Returns `None` if some item is wider than the strip, or the items don't fit in a strip
+    /// of height `u32::MAX`.
+    pub fn pack_strip(&self, items: &[Rect]) -> Option<Sheet> {
+        self.pack_strip_until(items, || true)
+    }
+
+    /// Like [`Packer::pack_strip`], giving up on the exact method like [`Packer::pack_until`].
+    pub fn pack_strip_until(
+        &self,
+        items: &[Rect],
+        mut should_continue: impl FnMut() -> bool,
+    ) -> Option<Sheet> {
+        let width = self.sheet.width;
+
+        // the lowest orientation of each item that fits the strip
+        let mut heights = Vec::with_capacity(items.len());
+        for item in items {
+            let height = item
+                .orientations(self.allow_rotation)
+                .filter(|(r, _)| r.width <= width)
+                .map(|(r, _)| r.height)
+                .min()?;
+
+            heights.push(height);
+        }
+
+        // in u64, the strip can't be taller than the largest u32 but the items may add up to more
+        let total_area: u64 = items.iter().map(Rect::area).sum();
+        let area_bound = total_area.div_ceil(width.max(1) as u64);
+        let stacked: u64 = heights.iter().map(|&height| height as u64).sum();
+        let tallest = heights.iter().copied().max().unwrap_or(0);
+        let mut lower = u32::try_from(area_bound.max(tallest as u64)).ok()?;
+        let mut upper = u32::try_from(stacked).unwrap_or(u32::MAX);
+
+        let mut best = None;
+        while lower <= upper {
+            let height = lower + (upper - lower) / 2;
+            log::debug!("Trying to fit rectangles in a strip of height {height}");
+
+            let packer = Packer {
+                sheet: Rect::new(width, height),
+                ..*self
+            };
+
+            match packer.pack_until(items, &mut should_continue) {
+                Some(mut sheets) if sheets.len() <= 1 => {
+                    let mut sheet = sheets.pop().unwrap_or_else(|| Sheet::new(packer.sheet));
+                    sheet.size.height = sheet.used_height();
+                    upper = sheet.size.height.saturating_sub(1);
+                    best = Some(sheet);
+
+                    if height == 0 {
+                        break;
+                    }
+                }
+                _ => match height.checked_add(1) {
+                    Some(next) => lower = next,
+                    None => break,
+                },
+            }
+        }
+
+        // the heuristics are not monotone in the height, fall back to stacking the items
+        match best {
+            Some(best) => Some(best),
+            None if stacked <= u32::MAX as u64 => Some(self.stack(items, width)),
+            None => None,
+        }
+    }
+
+    fn stack(&self, items: &[Rect], width: u32) -> Sheet {
+        let mut sheet = Sheet::new(Rect::new(width, 0));
+        for (idx, item) in items.iter().enumerate() {
+            let (rect, rotated) = item
+                .orientations(self.allow_rotation)
+                .filter(|(r, _)| r.width <= width)
+                .min_by_key(|(r, _)| r.height)
+                .expect("item fits in the strip");
+
+            sheet.placements.push(Placement {
+                item: idx,
+                x: 0,
+                y: sheet.size.height,
+                rect,
+                rotated,
+            });
+            sheet.size.height += rect.height;
+        }
+
+        sheet
+    }
+
+    fn pack_greedy(&self, items: &[Rect]) -> Vec<Sheet> {
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&idx| {
+            let item = &items[idx];
+            std::cmp::Reverse((item.area(), item.width.max(item.height)))
+        });
+
+        let mut sheets: Vec<(Sheet, FreeSpace)> = Vec::new();
+        for idx in order {
+            let item = &items[idx];
+            let found = sheets
+                .iter()
+                .enumerate()
+                .find_map(|(sheet_idx, (_, free))| {
+                    free.find(item, self.allow_rotation)
+                        .map(|found| (sheet_idx, found))
+                });
+
+            let (sheet_idx, (region_idx, rect, rotated)) = match found {
+                Some(found) => found,
+                None => {
+                    let free = FreeSpace::new(self.method, self.sheet);
+                    let found = free
+                        .find(item, self.allow_rotation)
+                        .expect("item fits in an empty sheet");
+
+                    sheets.push((Sheet::new(self.sheet), free));
+                    (sheets.len() - 1, found)
+                }
+            };
+
+            let (sheet, free) = &mut sheets[sheet_idx];
+            let region = free.regions[region_idx];
+            let placement = Placement {
+                item: idx,
+                x: region.x,
+                y: region.y,
+                rect,
+                rotated,
+            };
+
+            free.place(region_idx, &placement);
+            sheet.placements.push(placement);
+        }
+
+        sheets.into_iter().map(|(sheet, _)| sheet).collect()
+    }
+
+    fn pack_exact(&self, items: &[Rect], should_continue: &mut dyn FnMut() -> bool) -> Vec<Sheet> {
+        let upper = Packer {
+            method: Method::MaxRects,
+            ..*self
+        }
+        .pack_greedy(items);
+
+        let total_area: u64 = items.iter().map(Rect::area).sum();
+        let lower = total_area.div_ceil(self.sheet.area().max(1)) as usize;
+
+        // largest first, identical items next to each other
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&idx| std::cmp::Reverse(self.shape(&items[idx])));
+
+        let min_side = |heights: bool| {
+            let sides = items
+                .iter()
+                .flat_map(|item| item.orientations(self.allow_rotation));
+            let sides = sides.map(|(rect, _)| if heights { rect.height } else { rect.width });
+            sides.min().unwrap_or(0)
+        };
+        let xs = self.normal_patterns(
+            items,
+            false,
+            self.sheet.width - min_side(false).min(self.sheet.width),
+        );
+        let ys = self.normal_patterns(
+            items,
+            true,
+            self.sheet.height - min_side(true).min(self.sheet.height),
+        );
+
+        for num_sheets in lower..upper.len() {
+            log::debug!("Trying to fit rectangles in {num_sheets} sheets");
+
+            let mut search = ExactSearch {
+                items,
+                packer: self,
+                order: &order,
+                xs: &xs,
+                ys: &ys,
+                sheets: vec![Sheet::new(self.sheet); num_sheets],
+                free_area: self.sheet.area() * num_sheets as u64,
+                remaining_area: total_area,
+                should_continue: &mut *should_continue,
+                stopped: false,
+            };
+
+            if search.search(0) {
+                return search.sheets;
+            }
+
+            if search.stopped {
+                log::info!("Gave up on fitting the rectangles in {num_sheets} sheets");
+                break;
+            }
+        }
+
+        upper
+    }
+
+    /// The rectangle up to rotation when it's allowed, area first so that sorting by it puts the
+    /// largest first.
+    fn shape(&self, item: &Rect) -> (u64, u32, u32) {
+        let (width, height) = match self.allow_rotation {
+            true => (item.width.min(item.height), item.width.max(item.height)),
+            false => (item.width, item.height),
+        };
+
+        (item.area(), width, height)
+    }
+
+    /// The sums of the widths, or heights, of any of the `items` up to `max`: pushed left and
+    /// down as far as they go, the items of a packing are at such positions.
+    fn normal_patterns(&self, items: &[Rect], heights: bool, max: u32) -> Vec<u32> {
+        let mut sums = std::collections::BTreeSet::from([0u32]);
+        for item in items {
+            let sides = item.orientations(self.allow_rotation).map(|(rect, _)| {
+                if heights {
+                    rect.height
+                } else {
+                    rect.width
+                }
+            });
+            let sides: Vec<u32> = sides.collect();
+            let grown: Vec<u32> = sums
+                .iter()
+                .flat_map(|&sum| sides.iter().filter_map(move |&side| sum.checked_add(side)))
+                .filter(|&sum| sum <= max)
+                .collect();
+            sums.extend(grown);
+        }
+
+        sums.into_iter().collect()
+    }
+}
+
+/// Exhaustive search placing the items one after another in a fixed order, each at every
+/// position of the normal patterns on every sheet used so far and the first empty one.
+///
+/// Pushing the items of a packing left and down as far as they go keeps it a packing, and then
+/// the left edge of each item touches the sheet border or the right edge of another item, so it
+/// is at a sum of the widths of other items, and likewise the bottom edge. Trying every such
+/// position for every item therefore finds a packing if there is one.
+struct ExactSearch<'a> {
+    items: &'a [Rect],
+    packer: &'a Packer,
+    order: &'a [usize],
+    xs: &'a [u32],
+    ys: &'a [u32],
+    sheets: Vec<Sheet>,
+    free_area: u64,
+    remaining_area: u64,
+    should_continue: &'a mut dyn FnMut() -> bool,
+    /// Whether `should_continue` ended the search before it was exhausted.
+    stopped: bool,
+}
+
+impl ExactSearch<'_> {
+    /// Places the items from `pos` in the order on.
+    fn search(&mut self, pos: usize) -> bool {
+        let Some(&idx) = self.order.get(pos) else {
+            return true;
+        };
+
+        if !(self.should_continue)() {
+            self.stopped = true;
+            return false;
+        }
+
+        if self.remaining_area > self.free_area {
+            return false;
+        }
+
+        let item = self.items[idx];
+        let shape = self.packer.shape(&item);
+
+        // swapping identical items gives the same packing, so they are placed in the order of
+        // their positions
+        let after = pos
+            .checked_sub(1)
+            .map(|prev| self.order[prev])
+            .filter(|&prev| self.packer.shape(&self.items[prev]) == shape)
+            .and_then(|prev| self.position(prev));
+
+        // the empty sheets are interchangeable
+        let open = self
+            .sheets
+            .iter()
+            .position(Sheet::is_empty)
+            .map_or(self.sheets.len(), |first| first + 1);
+
+        for sheet_idx in 0..open {
+            let size = self.sheets[sheet_idx].size;
+            for (rect, rotated) in item.orientations(self.packer.allow_rotation) {
+                for &y in self.ys {
+                    if y as u64 + rect.height as u64 > size.height as u64 {
+                        break;
+                    }
+
+                    for &x in self.xs {
+                        if x as u64 + rect.width as u64 > size.width as u64 {
+                            break;
+                        }
+
+                        if after.is_some_and(|after| (sheet_idx, y, x) < after) {
+                            continue;
+                        }
+
+                        let placement = Placement {
+                            item: idx,
+                            x,
+                            y,
+                            rect,
+                            rotated,
+                        };
+
+                        if !self.sheets[sheet_idx].accepts(&placement) {
+                            continue;
+                        }
+
+                        self.remaining_area -= rect.area();
+                        self.free_area -= rect.area();
+                        self.sheets[sheet_idx].placements.push(placement);
+
+                        if self.search(pos + 1) {
+                            return true;
+                        }
+
+                        self.sheets[sheet_idx].placements.pop();
+                        self.free_area += rect.area();
+                        self.remaining_area += rect.area();
+
+                        if self.stopped {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// The sheet and the bottom left corner of the placed `item`, bottom to top.
+    fn position(&self, item: usize) -> Option<(usize, u32, u32)> {
+        self.sheets
+            .iter()
+            .enumerate()
+            .find_map(|(sheet_idx, sheet)| {
+                let placement = sheet.placements.iter().find(|p| p.item == item)?;
+                Some((sheet_idx, placement.y, placement.x))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rects(sides: &[(u32, u32)]) -> Vec<Rect> {
+        sides
+            .iter()
+            .map(|&(width, height)| Rect::new(width, height))
+            .collect()
+    }
+
+    /// Packs exactly, checking every item is placed once inside its sheet without overlaps.
+    fn pack(sheet: Rect, allow_rotation: bool, items: &[Rect]) -> Vec<Sheet> {
+        let mut packer = Packer::new(sheet, Method::Exact);
+        packer.allow_rotation = allow_rotation;
+        let sheets = packer.pack(items).unwrap();
+
+        let mut placed: Vec<usize> = sheets
+            .iter()
+            .flat_map(|sheet| sheet.placements.iter().map(|p| p.item))
+            .collect();
+        placed.sort();
+        assert_eq!(placed, (0..items.len()).collect::<Vec<_>>());
+
+        for sheet in &sheets {
+            for (idx, placement) in sheet.placements.iter().enumerate() {
+                let item = items[placement.item];
+                let expected = if placement.rotated {
+                    item.rotated()
+                } else {
+                    item
+                };
+                assert_eq!(placement.rect, expected);
+                let mut others = Sheet::new(sheet.size);
+                others.placements = sheet.placements.clone();
+                others.placements.remove(idx);
+                assert!(others.accepts(placement), "{placement:?}");
+            }
+        }
+
+        sheets
+    }
+
+    #[test]
+    fn finds_the_pinwheel() {
+        let items = rects(&[(6, 4), (4, 6), (6, 4), (4, 6), (2, 2)]);
+        assert_eq!(pack(Rect::new(10, 10), false, &items).len(), 1);
+    }
+
+    #[test]
+    fn proves_the_area_bound_unreachable() {
+        let items = rects(&[(6, 6), (6, 6)]);
+        assert_eq!(pack(Rect::new(10, 10), false, &items).len(), 2);
+
+        let items = rects(&[(3, 3); 10]);
+        assert_eq!(pack(Rect::new(10, 10), false, &items).len(), 2);
+    }
+
+    #[test]
+    fn rotates_when_allowed() {
+        let items = rects(&[(12, 3), (12, 3), (3, 12)]);
+        assert_eq!(pack(Rect::new(12, 12), true, &items).len(), 1);
+        assert_eq!(pack(Rect::new(12, 12), false, &items).len(), 2);
+    }
+
+    #[test]
+    fn keeps_the_heuristic_when_stopped() {
+        let items = rects(&[(6, 4), (4, 6), (6, 4), (4, 6), (2, 2)]);
+        let packer = Packer::new(Rect::new(10, 10), Method::Exact);
+        let sheets = packer.pack_until(&items, || false).unwrap();
+        let heuristic = Packer::new(Rect::new(10, 10), Method::MaxRects).pack(&items);
+        assert_eq!(Some(sheets), heuristic);
+    }
+
+    #[test]
+    fn strips_taller_than_u32_are_not_packed() {
+        let packer = Packer::new(Rect::new(10, 0), Method::MaxRects);
+        let strip = packer
+            .pack_strip(&rects(&[(10, 3_000_000_000), (5, 1)]))
+            .unwrap();
+        assert_eq!(strip.size.height, 3_000_000_001);
+
+        let items = rects(&[(10, 3_000_000_000), (10, 3_000_000_000)]);
+        assert_eq!(packer.pack_strip(&items), None);
+    }
+}
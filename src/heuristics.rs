@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+use core::{cmp, ops};
+
+use crate::{
+    solver::{Budget, SolveOutcome, Solver},
+    Bin,
+};
+
+/// Where a greedy heuristic puts the next item.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FitRule {
+    /// The first bin it fits in.
+    #[default]
+    First,
+    /// The bin it leaves the least capacity in.
+    Best,
+    /// The bin it leaves the most capacity in.
+    Worst,
+}
+
+/// Places the items one by one in decreasing order and never backtracks: first-fit, best-fit
+/// or worst-fit decreasing. The items that don't fit anywhere are left in `items`.
+pub struct Greedy<T> {
+    pub items: Vec<T>,
+    pub bins: Vec<Bin<T>>,
+    pub rule: FitRule,
+}
+
+impl<T> Greedy<T>
+where
+    T: Ord + Clone + for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    pub fn new(items: Vec<T>, bin_capacities: impl IntoIterator<Item = T>, rule: FitRule) -> Self {
+        Self::with_bins(
+            items,
+            bin_capacities.into_iter().map(Bin::new).collect(),
+            rule,
+        )
+    }
+
+    /// Pack `items` around the items already in `bins`.
+    pub fn with_bins(mut items: Vec<T>, bins: Vec<Bin<T>>, rule: FitRule) -> Self {
+        // the largest items are popped first
+        items.sort();
+        Self { items, bins, rule }
+    }
+
+    fn choose_bin(&self, item: &T) -> Option<usize> {
+        let mut candidates = (0..self.bins.len()).filter(|&idx| self.bins[idx].fits(item));
+        match self.rule {
+            FitRule::First => candidates.next(),
+            FitRule::Best => candidates.min_by_key(|&idx| &self.bins[idx].capacity),
+            FitRule::Worst => candidates.min_by_key(|&idx| cmp::Reverse(&self.bins[idx].capacity)),
+        }
+    }
+}
+
+impl<T> Solver<T> for Greedy<T>
+where
+    T: Ord + Clone + for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    fn solve(&mut self, budget: Budget) -> SolveOutcome {
+        let mut rejected = Vec::new();
+        while let Some(item) = self.items.pop() {
+            if !budget.remains() {
+                self.items.push(item);
+                self.items.append(&mut rejected);
+                self.items.sort();
+                return SolveOutcome::Budget;
+            }
+
+            match self.choose_bin(&item) {
+                Some(bin_idx) => self.bins[bin_idx].push(item),
+                None => rejected.push(item),
+            }
+        }
+
+        rejected.reverse();
+        self.items = rejected;
+        if self.items.is_empty() {
+            SolveOutcome::Solved
+        } else {
+            SolveOutcome::Exhausted
+        }
+    }
+
+    fn bins(&self) -> &[Bin<T>] {
+        &self.bins
+    }
+}
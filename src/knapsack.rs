@@ -0,0 +1,263 @@
+use std::{cmp, fmt, str};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Item {
+    pub weight: u32,
+    pub value: u64,
+}
+
+impl Item {
+    pub fn new(weight: u32, value: u64) -> Self {
+        Self { weight, value }
+    }
+
+    /// Compares value per unit of weight, zero weight items are the densest.
+    fn cmp_density(&self, other: &Self) -> cmp::Ordering {
+        let lhs = self.value as u128 * other.weight as u128;
+        let rhs = other.value as u128 * self.weight as u128;
+        match (self.weight, other.weight) {
+            (0, 0) => self.value.cmp(&other.value),
+            (0, _) => cmp::Ordering::Greater,
+            (_, 0) => cmp::Ordering::Less,
+            _ => lhs.cmp(&rhs),
+        }
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.weight, self.value)
+    }
+}
+
+/// Parses `weight,value`, a bare `weight` is worth its weight.
+impl str::FromStr for Item {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(',') {
+            Some((weight, value)) => Ok(Self::new(weight.parse()?, value.parse()?)),
+            None => {
+                let weight = s.parse()?;
+                Ok(Self::new(weight, weight as u64))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    /// Indices of the selected items.
+    pub items: Vec<usize>,
+    /// Indices of the items assigned to each bin.
+    pub bins: Vec<Vec<usize>>,
+    /// The total value, in u128 so the values of any number of items add up.
+    pub value: u128,
+    pub weight: u64,
+    /// Whether the search finished, proving the selection is optimal.
+    pub optimal: bool,
+}
+
+/// The (multiple) knapsack problem: assign items to bins, maximizing the total value packed.
+pub struct Knapsack {
+    pub items: Vec<Item>,
+    pub capacities: Vec<u32>,
+}
+
+impl Knapsack {
+    pub fn new(items: Vec<Item>, capacity: u32) -> Self {
+        Self::with_capacities(items, vec![capacity])
+    }
+
+    pub fn with_capacities(items: Vec<Item>, capacities: Vec<u32>) -> Self {
+        Self { items, capacities }
+    }
+
+    /// Branch and bound over the items in order of decreasing density, bounded by the
+    /// fractional relaxation over the total remaining capacity. Stops early when `predicate`
+    /// returns false.
+    pub fn solve_until(&self, predicate: impl FnMut() -> bool) -> Selection {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by(|&a, &b| self.items[b].cmp_density(&self.items[a]));
+
+        let mut search = KnapsackSearch {
+            items: order.iter().map(|&idx| self.items[idx]).collect(),
+            remaining: self.capacities.iter().map(|&c| c as u64).collect(),
+            assignment: Vec::new(),
+            best: Vec::new(),
+            best_value: 0,
+            predicate,
+            aborted: false,
+        };
+
+        let capacity = search.remaining.iter().sum();
+        search.search(0, capacity, 0);
+
+        let mut bins = vec![Vec::new(); self.capacities.len()];
+        for &(idx, bin_idx) in &search.best {
+            bins[bin_idx].push(order[idx]);
+        }
+        bins.iter_mut().for_each(|bin| bin.sort_unstable());
+
+        let mut items: Vec<usize> = bins.concat();
+        items.sort_unstable();
+
+        Selection {
+            value: search.best_value,
+            weight: items.iter().map(|&idx| self.items[idx].weight as u64).sum(),
+            items,
+            bins,
+            optimal: !search.aborted,
+        }
+    }
+}
+
+struct KnapsackSearch<P> {
+    items: Vec<Item>,
+    remaining: Vec<u64>,
+    /// `(item, bin)` pairs on the current branch.
+    assignment: Vec<(usize, usize)>,
+    best: Vec<(usize, usize)>,
+    best_value: u128,
+    predicate: P,
+    aborted: bool,
+}
+
+impl<P: FnMut() -> bool> KnapsackSearch<P> {
+    /// Value of the fractional relaxation over the remaining items.
+    fn bound(&self, idx: usize, mut capacity: u64, mut value: u128) -> u128 {
+        for item in &self.items[idx..] {
+            let weight = item.weight as u64;
+            if weight <= capacity {
+                capacity -= weight;
+                value += item.value as u128;
+            } else {
+                return value + item.value as u128 * capacity as u128 / weight as u128;
+            }
+        }
+
+        value
+    }
+
+    fn search(&mut self, idx: usize, capacity: u64, value: u128) {
+        if value > self.best_value {
+            self.best_value = value;
+            self.best.clone_from(&self.assignment);
+        }
+
+        if idx == self.items.len() || self.aborted {
+            return;
+        }
+
+        if !(self.predicate)() {
+            self.aborted = true;
+            return;
+        }
+
+        if self.bound(idx, capacity, value) <= self.best_value {
+            return;
+        }
+
+        let item = self.items[idx];
+        let weight = item.weight as u64;
+        for bin_idx in 0..self.remaining.len() {
+            let remaining = self.remaining[bin_idx];
+            // bins with the same remaining capacity are interchangeable
+            if weight > remaining || self.remaining[..bin_idx].contains(&remaining) {
+                continue;
+            }
+
+            self.remaining[bin_idx] -= weight;
+            self.assignment.push((idx, bin_idx));
+            self.search(idx + 1, capacity - weight, value + item.value as u128);
+            self.assignment.pop();
+            self.remaining[bin_idx] += weight;
+        }
+
+        self.search(idx + 1, capacity, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(pairs: &[(u32, u64)]) -> Vec<Item> {
+        pairs
+            .iter()
+            .map(|&(weight, value)| Item::new(weight, value))
+            .collect()
+    }
+
+    #[test]
+    fn parses_items() {
+        assert_eq!("4,40".parse(), Ok(Item::new(4, 40)));
+        assert_eq!("7".parse(), Ok(Item::new(7, 7)));
+        assert!("4,".parse::<Item>().is_err());
+    }
+
+    #[test]
+    fn picks_the_most_valuable_items() {
+        let knapsack = Knapsack::new(items(&[(5, 10), (4, 40), (6, 30), (3, 50)]), 10);
+        let selection = knapsack.solve_until(|| true);
+        assert_eq!(selection.items, [1, 3]);
+        assert_eq!(selection.bins, [vec![1, 3]]);
+        assert_eq!((selection.value, selection.weight), (90, 7));
+        assert!(selection.optimal);
+
+        // the densest items first isn't optimal here
+        let knapsack = Knapsack::new(items(&[(6, 7), (5, 5), (5, 5)]), 10);
+        let selection = knapsack.solve_until(|| true);
+        assert_eq!((selection.items, selection.value), (vec![1, 2], 10));
+    }
+
+    #[test]
+    fn fills_several_bins() {
+        let weights = items(&[(6, 6), (5, 5), (5, 5), (4, 4)]);
+        let selection =
+            Knapsack::with_capacities(weights.clone(), vec![10, 10]).solve_until(|| true);
+        assert_eq!(selection.value, 20);
+        assert_eq!(selection.items, [0, 1, 2, 3]);
+
+        // one 5 is left out, the rest only fit as 5 and 4, then 6
+        let selection = Knapsack::with_capacities(weights.clone(), vec![9, 6]).solve_until(|| true);
+        assert_eq!((selection.value, selection.weight), (15, 15));
+        assert!(selection.optimal);
+        for (bin, capacity) in selection.bins.iter().zip([9, 6]) {
+            let load: u64 = bin.iter().map(|&idx| weights[idx].weight as u64).sum();
+            assert!(load <= capacity as u64, "{bin:?}");
+        }
+
+        let mut assigned = selection.bins.concat();
+        assigned.sort_unstable();
+        assert_eq!(assigned, selection.items);
+    }
+
+    #[test]
+    fn stops_when_asked() {
+        let knapsack = Knapsack::new(items(&[(5, 10), (4, 40), (6, 30), (3, 50)]), 10);
+        let selection = knapsack.solve_until(|| false);
+        assert_eq!(selection.value, 0);
+        assert!(selection.items.is_empty());
+        assert!(!selection.optimal);
+
+        let mut calls = 0;
+        let selection = knapsack.solve_until(|| {
+            calls += 1;
+            calls <= 2
+        });
+        assert!(!selection.optimal);
+        assert!(selection.weight <= 10);
+    }
+
+    #[test]
+    fn large_weights_and_values_do_not_overflow() {
+        let weights = items(&[(u32::MAX, u64::MAX), (u32::MAX, u64::MAX), (u32::MAX, 1)]);
+        let selection =
+            Knapsack::with_capacities(weights, vec![u32::MAX, u32::MAX]).solve_until(|| true);
+        assert_eq!(selection.items, [0, 1]);
+        assert_eq!(selection.value, 2 * u64::MAX as u128);
+        assert_eq!(selection.weight, 2 * u32::MAX as u64);
+        assert!(selection.optimal);
+    }
+}
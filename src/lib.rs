@@ -1,9 +1,176 @@
-use std::{cmp, hash, iter, ops, time};
+//! Without the default `std` feature only [`Bin`], [`Fitter`] and the [`solver`],
+//! [`heuristics`], [`completion`] and [`progress`] modules are built, on `core` and `alloc`, and
+//! the `bigint` module with its own feature.
 
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec, vec::Vec};
+use core::{cmp, error, fmt, hash, iter, mem, ops, str};
+
+use clock::Stopwatch;
+use progress::ProgressReporter;
+use prune::Pruner;
+use solver::{SolveLimits, SolveOutcome, SolveStats};
+#[cfg(feature = "std")]
+use watch::WatchHandle;
+
+#[cfg(feature = "std")]
+pub mod balance;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "bigint")]
+pub mod bigint;
+pub mod bounds;
+#[cfg(feature = "std")]
+pub mod capacity;
+#[cfg(feature = "std")]
+pub mod checkpoint;
+mod clock;
+pub mod completion;
+#[cfg(feature = "std")]
+pub mod constrained;
+#[cfg(feature = "std")]
+pub mod covering;
+#[cfg(feature = "std")]
+pub mod float;
+#[cfg(feature = "std")]
+pub mod generator;
+#[cfg(feature = "std")]
+pub mod geom2d;
+pub mod heuristics;
+#[cfg(feature = "std")]
+pub mod knapsack;
+#[cfg(feature = "std")]
+pub mod lns;
+#[cfg(feature = "std")]
+pub mod nested;
+#[cfg(feature = "std")]
+pub mod objective;
+#[cfg(feature = "std")]
+pub mod portfolio;
+#[cfg(feature = "std")]
+pub mod priority;
+pub mod progress;
+pub mod prune;
+#[cfg(feature = "std")]
+pub mod rational;
+#[cfg(feature = "std")]
+pub mod repack;
+#[cfg(feature = "std")]
+pub mod size;
+#[cfg(feature = "std")]
+pub mod soft;
+pub mod solver;
+#[cfg(feature = "std")]
+pub mod span;
+#[cfg(feature = "std")]
+pub mod splitting;
+#[cfg(feature = "std")]
+pub mod task;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(feature = "std")]
+pub mod watch;
+#[cfg(feature = "std")]
+pub mod weighted;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SolutionState<S> {
+    #[default]
+    Unknown,
+    Unsolvable,
+    Solved(S),
+}
+
+impl<S> SolutionState<S> {
+    pub fn insert(&mut self, status: Self) {
+        if let Self::Unknown = self {
+            *self = status
+        };
+    }
+}
+
+/// A packing in terms of the positions of the items in the input.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Solution {
+    /// The bin of each item, `None` for the items that weren't packed.
+    pub item_bins: Vec<Option<usize>>,
+    /// The positions of the items in each bin, in input order.
+    pub bins: Vec<Vec<usize>>,
+}
+
+impl Solution {
+    pub fn is_complete(&self) -> bool {
+        self.item_bins.iter().all(Option::is_some)
+    }
+
+    /// The items in `bin`, looked up in the input `items`.
+    pub fn bin_items<'a, T>(&self, bin: usize, items: &'a [T]) -> Vec<&'a T> {
+        self.bins[bin].iter().map(|&idx| &items[idx]).collect()
+    }
+
+    /// Puts the packing in a canonical order, so packings with the same weights in each bin
+    /// are equal however the search found them. The bins are ordered by their weights, largest
+    /// first like [`Fitter::packed_bins`], with the empty bins last, and the packed items of each
+    /// weight are handed out to the bins in input order.
+    pub fn normalize<T: Ord>(&mut self, items: &[T]) {
+        let mut weights: Vec<Vec<&T>> = self
+            .bins
+            .iter()
+            .map(|bin| {
+                let mut weights: Vec<&T> = bin.iter().map(|&idx| &items[idx]).collect();
+                weights.sort_by(|a, b| b.cmp(a));
+                weights
+            })
+            .collect();
+        weights.sort_by(|a, b| b.cmp(a));
+
+        let mut packed: BTreeMap<&T, Vec<usize>> = BTreeMap::new();
+        for &idx in self.bins.iter().flatten() {
+            packed.entry(&items[idx]).or_default().push(idx);
+        }
+
+        for positions in packed.values_mut() {
+            positions.sort_unstable_by(|a, b| b.cmp(a));
+        }
+
+        self.bins = weights
+            .into_iter()
+            .map(|bin| {
+                let mut indices: Vec<usize> = bin
+                    .into_iter()
+                    .map(|item| {
+                        packed
+                            .get_mut(item)
+                            .and_then(Vec::pop)
+                            .expect("the weights come from the packed items")
+                    })
+                    .collect();
+                indices.sort_unstable();
+                indices
+            })
+            .collect();
+
+        self.item_bins.fill(None);
+        for (bin_idx, bin) in self.bins.iter().enumerate() {
+            for &idx in bin {
+                self.item_bins[idx] = Some(bin_idx);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Bin<T> {
+    /// The capacity the bin started out with.
+    pub size: T,
+    /// What's left of `size` after the items.
     pub capacity: T,
     pub items: Vec<T>,
+    /// What the bin stands for, like a truck or a server. The search ignores it.
+    pub label: Option<String>,
 }
 
 impl<T> Bin<T>
@@ -12,27 +179,84 @@ where
 {
     pub fn new(capacity: T) -> Self {
         Self {
+            size: capacity.clone(),
             capacity,
             items: Vec::new(),
+            label: None,
         }
     }
 
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn remaining(&self) -> &T {
+        &self.capacity
+    }
+
+    /// The total weight of the items.
+    pub fn used(&self) -> T {
+        let mut used = self.size.clone();
+        used -= &self.capacity;
+        used
+    }
+
     pub fn fits(&self, item: &T) -> bool {
         &self.capacity >= item
     }
 
+    /// Doesn't check that `item` fits, see [`Bin::try_push`].
     pub fn push(&mut self, item: T) {
         self.capacity -= &item;
         self.items.push(item);
     }
 
+    /// Pushes `item` if it fits, hands it back otherwise.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if !self.fits(&item) {
+            return Err(item);
+        }
+
+        self.push(item);
+        Ok(())
+    }
+
     pub fn pop(&mut self) -> Option<T> {
-        self.items.pop().map(|item| {
-            self.capacity += &item;
-            item
+        self.items.pop().inspect(|item| {
+            self.capacity += item;
         })
     }
 
+    /// Takes out the item at `idx`, panics if it's out of bounds.
+    pub fn remove(&mut self, idx: usize) -> T {
+        let item = self.items.remove(idx);
+        self.capacity += &item;
+        item
+    }
+
+    /// Takes out all the items.
+    pub fn drain(&mut self) -> vec::Drain<'_, T> {
+        for item in &self.items {
+            self.capacity += item;
+        }
+
+        self.items.drain(..)
+    }
+
+    /// Keeps only the items `predicate` returns true for.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&T) -> bool) {
+        let capacity = &mut self.capacity;
+        self.items.retain(|item| {
+            let keep = predicate(item);
+            if !keep {
+                *capacity += item;
+            }
+
+            keep
+        });
+    }
+
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
@@ -42,15 +266,114 @@ where
     }
 }
 
+impl<T: Ord> Bin<T> {
+    /// Sorts the items largest first, the order the search puts them in, so bins with the same
+    /// items compare and hash equal.
+    pub fn canonicalize(&mut self) {
+        self.items.sort_by(|a, b| b.cmp(a));
+    }
+}
+
+impl<T> Bin<T>
+where
+    T: Clone + cmp::PartialOrd + for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+    T: Into<f64>,
+{
+    /// The used fraction of the bin, 0 for a bin of size 0.
+    pub fn utilization(&self) -> f64 {
+        let size: f64 = self.size.clone().into();
+        if size == 0. {
+            return 0.;
+        }
+
+        self.used().into() / size
+    }
+}
+
 // TODO: do we need both?
-impl<T: std::cmp::PartialOrd> PartialOrd for Bin<T> {
+/// Bins are ordered by their items, the rest of the fields only break ties so that the order
+/// agrees with `==`.
+impl<T: cmp::PartialOrd> PartialOrd for Bin<T> {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        self.items.partial_cmp(&other.items)
+        match self.items.partial_cmp(&other.items)? {
+            cmp::Ordering::Equal => (&self.size, &self.capacity, &self.label).partial_cmp(&(
+                &other.size,
+                &other.capacity,
+                &other.label,
+            )),
+            ordering => Some(ordering),
+        }
     }
 }
-impl<T: std::cmp::Ord + std::cmp::Eq> Ord for Bin<T> {
+impl<T: cmp::Ord + cmp::Eq> Ord for Bin<T> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.items.cmp(&other.items)
+        self.items.cmp(&other.items).then_with(|| {
+            (&self.size, &self.capacity, &self.label).cmp(&(
+                &other.size,
+                &other.capacity,
+                &other.label,
+            ))
+        })
+    }
+}
+
+/// The order the search tries the bins in for each item, see [`Fitter::set_bin_order`]. Ties
+/// keep the order the bins were given in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BinOrder {
+    /// The order the bins were given in.
+    #[default]
+    Insertion,
+    /// The most remaining capacity first.
+    Capacity,
+    /// By label, unlabeled bins last.
+    Label,
+}
+
+impl BinOrder {
+    fn sort<T: Ord>(self, bins: &mut [Bin<T>]) {
+        match self {
+            Self::Insertion => {}
+            Self::Capacity => bins.sort_by(|a, b| b.capacity.cmp(&a.capacity)),
+            Self::Label => bins.sort_by(|a, b| {
+                let key = |bin: &Bin<T>| (bin.label.is_none(), bin.label.clone());
+                key(a).cmp(&key(b))
+            }),
+        }
+    }
+}
+
+impl str::FromStr for BinOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "insertion" => Ok(Self::Insertion),
+            "capacity" => Ok(Self::Capacity),
+            "label" => Ok(Self::Label),
+            _ => Err(alloc::format!(
+                "unknown bin order {s:?}, expected insertion, capacity or label"
+            )),
+        }
+    }
+}
+
+/// Something that happened during a search, see [`Fitter::on_event`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SearchEvent<'a, T> {
+    /// `item` was put in bin `bin`.
+    Placed { item: &'a T, bin: usize },
+    /// `item` was taken back out of bin `bin`.
+    Backtracked { item: &'a T, bin: usize },
+    /// Fewer items are left unpacked than ever before in this search.
+    NewBest { unpacked: usize, bins: &'a [Bin<T>] },
+}
+
+type Hook<T> = Box<dyn FnMut(&SearchEvent<'_, T>) + Send>;
+
+fn emit<T>(hooks: &mut [Hook<T>], event: SearchEvent<'_, T>) {
+    for hook in hooks {
+        hook(&event);
     }
 }
 
@@ -60,27 +383,122 @@ enum Action {
     Backtrack,
 }
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct State<T> {
-    last_bin_capacity: Option<T>,
+struct State {
+    /// The last bin the item was put in, it has the same capacity as before by the time the
+    /// next bin is tried.
+    last_bin_idx: Option<usize>,
     next_bin_idx: usize,
     action: Action,
 }
 
-impl<T> Default for State<T> {
+impl Default for State {
     fn default() -> Self {
         Self {
-            last_bin_capacity: Default::default(),
+            last_bin_idx: None,
             next_bin_idx: 0,
             action: Action::Try,
         }
     }
 }
 
+/// Steps between progress reports when the search isn't improving.
+const PROGRESS_INTERVAL: u32 = 1024;
+
+/// Canonicalizes each bin, then orders the bins largest items first.
+fn canonical_bins<T: Ord>(mut bins: Vec<Bin<T>>) -> Vec<Bin<T>> {
+    bins.iter_mut().for_each(Bin::canonicalize);
+    bins.sort_by(|a, b| b.cmp(a));
+    bins
+}
+
+/// Whether each bin is identical to the previous one, labels aside.
+fn symmetric_bins<T: PartialEq>(bins: &[Bin<T>]) -> Vec<bool> {
+    let same = |a: &Bin<T>, b: &Bin<T>| a.capacity == b.capacity && a.items == b.items;
+    (0..bins.len())
+        .map(|idx| idx >= 1 && same(&bins[idx - 1], &bins[idx]))
+        .collect()
+}
+
+/// Whether `weights` add up to at most the `capacities`, taken from them piece by piece instead of
+/// summed, so weights near the largest value of `T` don't overflow.
+pub(crate) fn fits_in_total<'a, T>(
+    weights: impl IntoIterator<Item = &'a T>,
+    capacities: impl IntoIterator<Item = &'a T>,
+) -> bool
+where
+    T: 'a + Ord + Clone + for<'b> iter::Sum<&'b T> + for<'b> ops::SubAssign<&'b T>,
+{
+    let mut capacities = capacities.into_iter();
+    let mut room: T = iter::empty().sum();
+    for weight in weights {
+        let mut weight = weight.clone();
+        while weight > room {
+            weight -= &room;
+            let Some(capacity) = capacities.next() else {
+                return false;
+            };
+
+            room = capacity.clone();
+        }
+
+        room -= &weight;
+    }
+
+    true
+}
+
+/// Why an instance can't be packed, found before searching, see [`Fitter::try_with_bins`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InstanceError {
+    /// There are items but no bins.
+    EmptyInstance,
+    /// The item at this position in the input is larger than every bin.
+    ItemExceedsCapacity { item: usize },
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyInstance => f.write_str("there are items but no bins"),
+            Self::ItemExceedsCapacity { item } => {
+                write!(f, "item {item} is larger than every bin")
+            }
+        }
+    }
+}
+
+impl error::Error for InstanceError {}
+
+/// Backtracking search for a packing of `items` into `bins`.
+///
+/// Any weight with the bounds below works, including arbitrary-precision integers such as
+/// `bigint::BigUint`. Weights are only cloned when the search starts, never while it runs.
 pub struct Fitter<T> {
     pub items: Vec<T>,
     pub bins: Vec<Bin<T>>,
 
-    state_stack: Vec<State<T>>,
+    /// The items in the order they were given in.
+    input: Vec<T>,
+    /// The number of items each bin started out with.
+    fixed: Vec<usize>,
+    /// Whether each bin started out identical to the previous one, only those are ordered to
+    /// break symmetry.
+    symmetric: Vec<bool>,
+    /// Whether bins with the same remaining capacity are still told apart, which only matters
+    /// when enumerating packings.
+    exhaustive: bool,
+    bin_order: BinOrder,
+    /// Whether the items in the bins when the items last changed are kept in place until the
+    /// search around them is exhausted, see [`Fitter::add_item`].
+    settled: bool,
+    hooks: Vec<Hook<T>>,
+    pruners: Vec<Box<dyn Pruner<T> + Send>>,
+    progress: Box<dyn ProgressReporter + Send>,
+    #[cfg(feature = "std")]
+    watch: Option<WatchHandle<T>>,
+    stats: SolveStats,
+    state_stack: Vec<State>,
 }
 
 impl<T> Fitter<T>
@@ -88,35 +506,351 @@ where
     T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
     T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
 {
-    pub fn new(mut items: Vec<T>, bin_capacities: impl IntoIterator<Item = T>) -> Self {
+    pub fn new(items: Vec<T>, bin_capacities: impl IntoIterator<Item = T>) -> Self {
+        Self::with_bins(items, bin_capacities.into_iter().map(Bin::new).collect())
+    }
+
+    /// Like [`Fitter::new`], but fails on instances that can't be packed at all.
+    pub fn try_new(
+        items: Vec<T>,
+        bin_capacities: impl IntoIterator<Item = T>,
+    ) -> Result<Self, InstanceError> {
+        Self::try_with_bins(items, bin_capacities.into_iter().map(Bin::new).collect())
+    }
+
+    /// Like [`Fitter::with_bins`], but fails on instances that can't be packed at all.
+    pub fn try_with_bins(items: Vec<T>, bins: Vec<Bin<T>>) -> Result<Self, InstanceError> {
+        let largest_bin = bins.iter().map(|bin| &bin.capacity).max();
+        match largest_bin {
+            None if !items.is_empty() => return Err(InstanceError::EmptyInstance),
+            Some(largest_bin) => {
+                if let Some(item) = items.iter().position(|item| item > largest_bin) {
+                    return Err(InstanceError::ItemExceedsCapacity { item });
+                }
+            }
+            None => {}
+        }
+
+        Ok(Self::with_bins(items, bins))
+    }
+
+    /// Pack `items` around the items already in `bins`, which are never moved.
+    pub fn with_bins(mut items: Vec<T>, bins: Vec<Bin<T>>) -> Self {
+        let input = items.clone();
         items.sort();
 
         Self {
-            bins: bin_capacities.into_iter().map(Bin::new).collect(),
+            fixed: bins.iter().map(|bin| bin.items.len()).collect(),
+            symmetric: symmetric_bins(&bins),
+            stats: SolveStats {
+                fewest_unpacked: items.len(),
+                ..Default::default()
+            },
+            bins,
             items,
+            input,
+            exhaustive: false,
+            bin_order: BinOrder::default(),
+            settled: false,
+            hooks: Vec::new(),
+            pruners: Vec::new(),
+            progress: Box::new(progress::NoProgress),
+            #[cfg(feature = "std")]
+            watch: None,
             state_stack: vec![Default::default()],
         }
     }
 
+    /// Takes every item back out of the bins, except the ones the bins started out with, to
+    /// search again from the start.
+    pub fn reset(&mut self) {
+        for (bin, &fixed) in self.bins.iter_mut().zip(&self.fixed) {
+            while bin.items.len() > fixed {
+                self.items.extend(bin.pop());
+            }
+        }
+
+        self.items.sort();
+        self.settled = false;
+        self.state_stack = vec![Default::default()];
+        self.stats.fewest_unpacked = self.items.len();
+    }
+
+    /// Adds an item without throwing away the current packing: the search first packs the
+    /// unpacked items around the packed ones, and only starts over from scratch if that fails.
+    ///
+    /// A changed `Fitter` may yield a packing twice from [`Fitter::solutions`].
+    pub fn add_item(&mut self, item: T) {
+        self.input.push(item.clone());
+        let idx = self.items.partition_point(|other| other < &item);
+        self.items.insert(idx, item);
+        self.settle();
+    }
+
+    /// Removes one item equal to `item`, packed or not, keeping the rest of the packing, see
+    /// [`Fitter::add_item`]. Returns false if there is no such item, the items the bins started
+    /// out with can't be removed.
+    pub fn remove_item(&mut self, item: &T) -> bool {
+        let Some(input_idx) = self.input.iter().rposition(|other| other == item) else {
+            return false;
+        };
+
+        if let Some(idx) = self.items.iter().position(|other| other == item) {
+            self.items.remove(idx);
+        } else {
+            let (bin, idx) = self
+                .bins
+                .iter_mut()
+                .zip(&self.fixed)
+                .find_map(|(bin, &fixed)| {
+                    let idx = bin.items[fixed..].iter().position(|other| other == item)?;
+                    Some((bin, fixed + idx))
+                })
+                .expect("input items are either packed or unpacked");
+
+            bin.remove(idx);
+        }
+
+        self.input.remove(input_idx);
+        self.settle();
+        true
+    }
+
+    /// Restarts the search around the current packing.
+    fn settle(&mut self) {
+        self.settled = true;
+        self.state_stack = vec![Default::default()];
+        self.stats.fewest_unpacked = self.items.len();
+    }
+
+    /// Searches again with different `bins`, keeping the items.
+    pub fn set_bins(&mut self, bins: Vec<Bin<T>>) {
+        self.reset();
+        self.install_bins(bins);
+    }
+
+    /// Reorders the bins and searches again, the bins of a [`Solution`] are in the new order.
+    pub fn set_bin_order(&mut self, order: BinOrder) {
+        self.bin_order = order;
+        self.reset();
+        let bins = mem::take(&mut self.bins);
+        self.install_bins(bins);
+    }
+
+    fn install_bins(&mut self, mut bins: Vec<Bin<T>>) {
+        self.bin_order.sort(&mut bins);
+        self.fixed = bins.iter().map(|bin| bin.items.len()).collect();
+        self.symmetric = symmetric_bins(&bins);
+        self.bins = bins;
+    }
+
     pub fn is_solved(&self) -> bool {
         self.items.is_empty()
     }
 
+    /// The bins that hold items, largest items first, so equivalent packings compare equal up to
+    /// the order of the bins they came in.
+    pub fn packed_bins(&self) -> Vec<Bin<T>> {
+        let bins = self.bins.iter().filter(|bin| !bin.is_empty()).cloned();
+        canonical_bins(bins.collect())
+    }
+
+    /// Like [`Fitter::packed_bins`], without cloning the bins.
+    pub fn into_packed_bins(self) -> Vec<Bin<T>> {
+        let bins = self.bins.into_iter().filter(|bin| !bin.is_empty());
+        canonical_bins(bins.collect())
+    }
+
+    /// Like [`Fitter::solution`], consuming the `Fitter`.
+    pub fn into_solution(self) -> Solution {
+        self.solution()
+    }
+
+    /// Maps the packed items back to their positions in the input, items of the same weight are
+    /// handed out in input order. The items the bins started out with aren't part of it.
+    pub fn solution(&self) -> Solution {
+        let mut unassigned: BTreeMap<&T, Vec<usize>> = BTreeMap::new();
+        for (idx, item) in self.input.iter().enumerate().rev() {
+            unassigned.entry(item).or_default().push(idx);
+        }
+
+        let mut item_bins = vec![None; self.input.len()];
+        let mut bins = Vec::with_capacity(self.bins.len());
+        for (bin_idx, bin) in self.bins.iter().enumerate() {
+            let mut indices: Vec<usize> = bin.items[self.fixed[bin_idx]..]
+                .iter()
+                .map(|item| {
+                    unassigned
+                        .get_mut(item)
+                        .and_then(Vec::pop)
+                        .expect("packed items come from the input")
+                })
+                .collect();
+
+            indices.sort_unstable();
+            for &idx in &indices {
+                item_bins[idx] = Some(bin_idx);
+            }
+
+            bins.push(indices);
+        }
+
+        Solution { item_bins, bins }
+    }
+
+    /// Searches until the packing is found, the search is exhausted or one of the `limits` is
+    /// reached. Node and backtrack limits count from this call and stop the search at the same
+    /// point on every run.
+    pub fn solve_with_limits(&mut self, limits: SolveLimits) -> SolveOutcome {
+        let start = Stopwatch::start();
+        let (nodes, backtracks) = (self.stats.nodes, self.stats.backtracks);
+        let outcome = loop {
+            let exceeded = limits
+                .max_nodes
+                .is_some_and(|max| self.stats.nodes - nodes >= max)
+                || limits
+                    .max_backtracks
+                    .is_some_and(|max| self.stats.backtracks - backtracks >= max)
+                || limits.max_time.is_some_and(|max| start.elapsed() >= max);
+
+            if exceeded {
+                break Some(SolveOutcome::Budget);
+            }
+
+            if !self.step() {
+                break None;
+            }
+        };
+
+        self.stats.wall_time += start.elapsed();
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+
+        if self.is_solved() {
+            SolveOutcome::Solved
+        } else {
+            SolveOutcome::Exhausted
+        }
+    }
+
+    /// Searches for at most `duration`.
+    #[cfg(feature = "std")]
+    pub fn solve_for(&mut self, duration: std::time::Duration) -> SolveOutcome {
+        self.solve_with_deadline(std::time::Instant::now() + duration)
+    }
+
+    /// Searches until `deadline` at the latest.
+    #[cfg(feature = "std")]
+    pub fn solve_with_deadline(&mut self, deadline: std::time::Instant) -> SolveOutcome {
+        solver::Solver::solve(self, solver::Budget::until(deadline))
+    }
+
+    /// What the search did so far, across all solve calls.
+    pub fn stats(&self) -> &SolveStats {
+        &self.stats
+    }
+
+    /// Reports the progress of [`Fitter::solve_until`] to `reporter` instead of nowhere.
+    pub fn set_progress(&mut self, reporter: impl ProgressReporter + Send + 'static) {
+        self.progress = Box::new(reporter);
+    }
+
+    /// A handle other threads can read the best packing so far and the stats from while
+    /// [`Fitter::solve_until`] runs. The packing is published when it improves, the stats every
+    /// so often.
+    #[cfg(feature = "std")]
+    pub fn watch(&mut self) -> WatchHandle<T> {
+        self.watch.get_or_insert_with(WatchHandle::default).clone()
+    }
+
+    /// Calls `hook` on every [`SearchEvent`] from now on, so the search can be observed without
+    /// the library printing anything.
+    pub fn on_event(&mut self, hook: impl FnMut(&SearchEvent<'_, T>) + Send + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Asks `pruner` whether to go on after every item the search puts in a bin, from now on.
+    /// The search then tries every bin the item fits in, even ones that look interchangeable.
+    pub fn add_pruner(&mut self, pruner: impl Pruner<T> + Send + 'static) {
+        self.pruners.push(Box::new(pruner));
+    }
+
+    /// Every distinct packing, up to reordering identical bins and identical items. The search
+    /// continues from where it stopped after each packing, starting from a fresh `Fitter` gives
+    /// all of them.
+    pub fn solutions(&mut self) -> Solutions<'_, T> {
+        self.exhaustive = true;
+        Solutions { fitter: self }
+    }
+
+    /// Counts the packings [`Fitter::solutions`] yields, up to `cap`, without collecting them.
+    /// Stops early when `predicate` returns false.
+    ///
+    /// Returns the count and whether the search ran to the end.
+    pub fn count_solutions(
+        &mut self,
+        cap: Option<usize>,
+        mut predicate: impl FnMut() -> bool,
+    ) -> (usize, bool) {
+        self.exhaustive = true;
+
+        let start = Stopwatch::start();
+        let mut count = 0;
+        let mut complete = true;
+        while !self.state_stack.is_empty() {
+            if cap.is_some_and(|cap| count >= cap) || !predicate() {
+                complete = false;
+                break;
+            }
+
+            if !self.step() && self.is_solved() {
+                count += 1;
+            }
+        }
+
+        self.stats.wall_time += start.elapsed();
+        (count, complete)
+    }
+
     pub fn step(&mut self) -> bool {
-        self.step_inner().is_some()
+        let stepped = self.step_inner().is_some();
+
+        // the packing kept by `settle` doesn't extend to a full one, search from scratch
+        if self.settled && self.state_stack.is_empty() && !self.is_solved() {
+            self.reset();
+            self.stats.restarts += 1;
+            return true;
+        }
+
+        stepped
     }
 
     fn step_inner(&mut self) -> Option<()> {
         let mut current = self.state_stack.pop()?;
 
         let mut item = match current.action {
-            Action::Backtrack => self.bins[current.next_bin_idx - 1].pop().unwrap(),
+            Action::Backtrack => {
+                let bin = current.next_bin_idx - 1;
+                let item = self.bins[bin]
+                    .pop()
+                    .expect("a backtrack state has an item to undo");
+                self.stats.backtracks += 1;
+                emit(
+                    &mut self.hooks,
+                    SearchEvent::Backtracked { item: &item, bin },
+                );
+                item
+            }
             Action::Try => self.items.pop()?,
         };
 
         if let Some(prev_state) = self.state_stack.last() {
             let current_bin_idx = prev_state.next_bin_idx - 1;
-            let prev_item = self.bins[current_bin_idx].items.last().unwrap();
+            let prev_item = self.bins[current_bin_idx]
+                .items
+                .last()
+                .expect("the previous state put an item in this bin");
 
             if prev_item <= &item {
                 current.next_bin_idx = current.next_bin_idx.max(current_bin_idx)
@@ -136,21 +870,67 @@ where
                 continue;
             }
 
-            if current.last_bin_capacity.as_ref() == Some(&self.bins[bin_idx].capacity) {
-                continue;
-            };
+            // pruners may tell apart the bins these skip
+            let symmetry = self.pruners.is_empty();
+            if let (Some(last_bin_idx), false, true) =
+                (current.last_bin_idx, self.exhaustive, symmetry)
+            {
+                if self.bins[last_bin_idx].capacity == self.bins[bin_idx].capacity {
+                    continue;
+                }
+            }
 
-            let capacity = self.bins[bin_idx].capacity.clone();
-            self.bins[bin_idx].push(item);
-            if bin_idx >= 1 {
+            if cfg!(debug_assertions) {
+                let pushed = self.bins[bin_idx].try_push(item);
+                assert!(pushed.is_ok(), "only items that fit are pushed");
+            } else {
+                self.bins[bin_idx].push(item);
+            }
+
+            if self.symmetric[bin_idx] && symmetry {
                 // check that current and previous bins are in order
-                if self.bins[bin_idx - 1] < self.bins[bin_idx] {
-                    item = self.bins[bin_idx].pop().unwrap();
+                if self.bins[bin_idx - 1].items < self.bins[bin_idx].items {
+                    item = self.bins[bin_idx].pop().expect("the item was just pushed");
                     continue;
                 }
             }
 
-            current.last_bin_capacity = Some(capacity);
+            let (bins, items) = (&self.bins, &self.items);
+            if self
+                .pruners
+                .iter_mut()
+                .any(|pruner| pruner.prune(bins, items, bin_idx))
+            {
+                item = self.bins[bin_idx].pop().expect("the item was just pushed");
+                continue;
+            }
+
+            current.last_bin_idx = Some(bin_idx);
+            self.stats.nodes += 1;
+            let depth = self.input.len() - self.items.len();
+            self.stats.max_depth = self.stats.max_depth.max(depth);
+
+            let new_best = self.items.len() < self.stats.fewest_unpacked;
+            if new_best {
+                self.stats.fewest_unpacked = self.items.len();
+            }
+
+            if !self.hooks.is_empty() {
+                let item = self.bins[bin_idx]
+                    .items
+                    .last()
+                    .expect("the item was just pushed");
+                emit(&mut self.hooks, SearchEvent::Placed { item, bin: bin_idx });
+
+                if new_best {
+                    let event = SearchEvent::NewBest {
+                        unpacked: self.items.len(),
+                        bins: &self.bins,
+                    };
+
+                    emit(&mut self.hooks, event);
+                }
+            }
 
             // item was put in a bin
             current.action = Action::Backtrack;
@@ -164,15 +944,9 @@ where
 
     pub fn solve_until(&mut self, mut predicate: impl FnMut() -> bool) -> bool {
         let initial_len = self.items.len();
-        let print_interval = time::Duration::from_millis(200);
-
-        let mut solved_amount = 0.;
-        let mut prev_print_amount = solved_amount;
         let mut min_items = initial_len;
-        let mut next_print_time = time::Instant::now() + print_interval;
-
         let mut num_iters = 0;
-        let start = time::Instant::now();
+        let start = Stopwatch::start();
 
         let mut solving = predicate();
 
@@ -182,45 +956,291 @@ where
                 break;
             }
 
-            // print!("\x1b[2J\x1b[H"); // clear screen
-            // println!(
-            //     "c {}",
-            //     self
-            //         .items
-            //         .iter()
-            //         .map(ToString::to_string)
-            //         .collect::<Vec<_>>()
-            //         .join(" ")
-            // );
-
-            // print_solution(&self.bins);
-            // io::stdin().read_line(&mut String::new())?;
-
-            if self.items.len() < min_items {
-                min_items = self.items.len();
-                let m = min_items as f64;
-                let l = initial_len as f64;
-                solved_amount = 1. - (m * (l - 1.) / l + 1.).log(l);
-            };
-
-            if time::Instant::now() > next_print_time && prev_print_amount != solved_amount {
-                // print
-                next_print_time = time::Instant::now() + print_interval;
-                prev_print_amount = solved_amount;
-                log::info!("{:.2}% solved (items={min_items})", solved_amount * 100.);
+            // also every so often, so reporters can throttle without a clock of their own
+            let improved = self.items.len() < min_items;
+            if improved || num_iters % PROGRESS_INTERVAL == 0 {
+                min_items = min_items.min(self.items.len());
+                self.progress.stats(&self.stats);
+                self.progress.progress(min_items, initial_len);
+                self.publish(improved);
             }
 
             solving = predicate();
         }
 
         let dur = start.elapsed();
-        if num_iters > 0 {
-            let time_per_iteration = dur / num_iters;
-            log::debug!("{num_iters} iterations in {dur:?} ({time_per_iteration:?} per iteration)",);
-        } else {
-            log::warn!("No iterations");
-        }
+        self.stats.wall_time += dur;
+        self.progress.finish(num_iters, dur);
+        self.publish(self.items.len() <= min_items);
 
         solving
     }
+
+    /// Publishes the stats to the watch handle, with the current packing if `packing`.
+    fn publish(&self, packing: bool) {
+        #[cfg(feature = "std")]
+        if let Some(watch) = &self.watch {
+            let packing = packing.then_some((&self.bins[..], self.items.len()));
+            watch.publish(&self.stats, packing);
+        }
+
+        #[cfg(not(feature = "std"))]
+        let _ = packing;
+    }
+}
+
+/// Iterator over the packings of a [`Fitter`], see [`Fitter::solutions`].
+pub struct Solutions<'a, T> {
+    fitter: &'a mut Fitter<T>,
+}
+
+impl<T> Iterator for Solutions<'_, T>
+where
+    T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    type Item = Vec<Bin<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // an empty stack means the search is over
+        if self.fitter.state_stack.is_empty() {
+            return None;
+        }
+
+        while self.fitter.step() {}
+        self.fitter.is_solved().then(|| self.fitter.bins.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    fn solution(num_items: usize, bins: &[&[usize]]) -> Solution {
+        let mut item_bins = vec![None; num_items];
+        for (bin_idx, bin) in bins.iter().enumerate() {
+            bin.iter().for_each(|&idx| item_bins[idx] = Some(bin_idx));
+        }
+
+        let bins = bins.iter().map(|bin| bin.to_vec()).collect();
+        Solution { item_bins, bins }
+    }
+
+    #[test]
+    fn normalize_makes_equivalent_packings_equal() {
+        let items = [4, 6, 4, 3, 1];
+        let mut a = solution(5, &[&[0, 3], &[1, 2], &[]]);
+        let mut b = solution(5, &[&[], &[2, 3], &[1, 0]]);
+        a.normalize(&items);
+        b.normalize(&items);
+
+        // the 4s go to the bins in input order, the unpacked 1 stays unpacked
+        let expected = solution(5, &[&[0, 1], &[2, 3], &[]]);
+        assert_eq!(a, expected);
+        assert_eq!(b, expected);
+        assert!(!a.is_complete());
+
+        let mut c = solution(5, &[&[0, 1], &[2, 3, 4], &[]]);
+        c.normalize(&items);
+        assert_ne!(c, expected);
+        assert_eq!(c.bins[1], [2, 3, 4]);
+    }
+
+    #[test]
+    fn fits_in_total_without_summing() {
+        assert!(fits_in_total(&[4, 6, 3], &[5, 8]));
+        assert!(!fits_in_total(&[4, 6, 4], &[5, 8]));
+        assert!(fits_in_total::<u32>(&[], &[]));
+        assert!(fits_in_total::<u32>(&[0], &[]));
+        assert!(!fits_in_total(&[1], &[]));
+
+        let big = [3_000_000_000u32, 3_000_000_000];
+        assert!(fits_in_total(&big, &[4_000_000_000, 4_000_000_000]));
+        assert!(!fits_in_total(&big, &[4_000_000_000, 1_000_000_000]));
+        assert!(fits_in_total(&[u32::MAX; 3], &[u32::MAX; 3]));
+    }
+
+    #[test]
+    fn counts_packings_up_to_identical_bins() {
+        let count = |items: Vec<u32>, bins: &[u32]| {
+            Fitter::new(items, bins.iter().copied()).count_solutions(None, || true)
+        };
+
+        // {1 2 3}, {1 2} {3}, {1 3} {2} and {2 3} {1}
+        assert_eq!(count(vec![1, 2, 3], &[6, 6]), (4, true));
+        // the bin of 3 holds nothing, 1, 2, 3 or 1 2
+        assert_eq!(count(vec![1, 2, 3], &[6, 3]), (5, true));
+        assert_eq!(count(vec![1, 1, 2], &[2, 2]), (1, true));
+        assert_eq!(count(vec![3, 3], &[5, 5]), (1, true));
+        assert_eq!(count(vec![4, 3], &[5, 5, 5]), (1, true));
+        assert_eq!(count(vec![6], &[5, 5]), (0, true));
+
+        let mut fitter = Fitter::new(vec![1, 2, 3], [6, 6]);
+        assert_eq!(fitter.count_solutions(Some(2), || true), (2, false));
+        let mut fitter = Fitter::new(vec![1, 2, 3], [6, 6]);
+        assert_eq!(fitter.count_solutions(None, || false), (0, false));
+    }
+
+    #[test]
+    fn bin_edits_give_back_capacity() {
+        let mut bin = Bin::new(10);
+        [4, 3, 2, 1].into_iter().for_each(|item| bin.push(item));
+        assert_eq!(bin.capacity, 0);
+
+        assert_eq!(bin.remove(1), 3);
+        assert_eq!((bin.items(), bin.capacity), (&vec![4, 2, 1], 3));
+
+        bin.retain(|&item| item != 2);
+        assert_eq!((bin.items(), bin.capacity), (&vec![4, 1], 5));
+        assert_eq!(bin.used(), 5);
+
+        assert_eq!(bin.drain().collect::<Vec<_>>(), [4, 1]);
+        assert!(bin.is_empty());
+        assert_eq!(bin.capacity, bin.size);
+    }
+
+    #[test]
+    fn try_push_hands_back_what_doesnt_fit() {
+        let mut bin = Bin::new(10);
+        assert_eq!(bin.try_push(6), Ok(()));
+        assert_eq!(bin.try_push(5), Err(5));
+        assert_eq!((bin.items(), bin.capacity), (&vec![6], 4));
+
+        assert_eq!(bin.try_push(4), Ok(()));
+        assert_eq!(bin.try_push(0), Ok(()));
+        assert_eq!(bin.try_push(1), Err(1));
+        assert_eq!((bin.items(), bin.capacity), (&vec![6, 4, 0], 0));
+    }
+
+    #[test]
+    fn bin_order_agrees_with_eq() {
+        let bin = |size, items: &[u32], label: Option<&str>| {
+            let mut bin = Bin::new(size);
+            items.iter().for_each(|&item| bin.push(item));
+            bin.label = label.map(String::from);
+            bin
+        };
+
+        let a = bin(10, &[5, 3], None);
+        let b = bin(12, &[5, 3], None);
+        let c = bin(10, &[5, 3], Some("truck"));
+        assert!(a != b && a.cmp(&b) != cmp::Ordering::Equal);
+        assert!(a != c && a.cmp(&c) != cmp::Ordering::Equal);
+        assert_eq!(a.cmp(&a.clone()), cmp::Ordering::Equal);
+        assert!(bin(10, &[6], None) > b);
+
+        // canonical bins with the same items compare and hash alike
+        let mut d = bin(10, &[3, 5], None);
+        d.canonicalize();
+        assert_eq!(a, d);
+        assert_eq!(hash_of(&a), hash_of(&d));
+    }
+
+    fn hash_of(value: &impl hash::Hash) -> u64 {
+        use hash::Hasher;
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn add_and_remove_items_around_the_packing() {
+        let solve = |fitter: &mut Fitter<u32>| fitter.solve_with_limits(SolveLimits::default());
+        let mut fixed = Bin::new(10);
+        fixed.push(1);
+        let mut fitter = Fitter::with_bins(vec![5, 4], vec![Bin::new(10), fixed]);
+        assert_eq!(solve(&mut fitter), SolveOutcome::Solved);
+        let packed = fitter.bins.clone();
+
+        // the 1 fits next to the packed items, which stay where they are
+        fitter.add_item(1);
+        assert_eq!(fitter.items, [1]);
+        assert_eq!(solve(&mut fitter), SolveOutcome::Solved);
+        for (bin, before) in fitter.bins.iter().zip(&packed) {
+            assert!(bin.items.starts_with(&before.items));
+        }
+
+        assert_eq!(fitter.solution().item_bins.len(), 3);
+        assert!(fitter.remove_item(&5));
+        assert!(!fitter.remove_item(&5));
+        assert!(fitter.bins.iter().all(|bin| !bin.items.contains(&5)));
+        assert_eq!(fitter.solution().item_bins.len(), 2);
+
+        // the items the bins started out with stay
+        assert!(!fitter.remove_item(&7));
+        assert_eq!(fitter.bins[1].items[0], 1);
+        assert!(fitter.remove_item(&1));
+        assert_eq!(fitter.bins[1].items[0], 1);
+        assert!(!fitter.remove_item(&1));
+
+        fitter.add_item(20);
+        assert!(fitter.remove_item(&20));
+        assert!(fitter.items.is_empty());
+        assert_eq!(solve(&mut fitter), SolveOutcome::Solved);
+        assert!(fitter.solution().is_complete());
+    }
+
+    #[test]
+    fn restarts_count_only_settled_packings_given_up() {
+        let mut fitter = Fitter::new(vec![3, 3], [8, 7]);
+        let solve = |fitter: &mut Fitter<u32>| fitter.solve_with_limits(SolveLimits::default());
+        assert_eq!(solve(&mut fitter), SolveOutcome::Solved);
+
+        fitter.add_item(4);
+        assert_eq!(solve(&mut fitter), SolveOutcome::Solved);
+        assert_eq!(fitter.stats().restarts, 0);
+
+        // the second 4 doesn't fit around the two 3s in one bin
+        fitter.add_item(4);
+        assert_eq!(solve(&mut fitter), SolveOutcome::Solved);
+        assert_eq!(fitter.stats().restarts, 1);
+
+        fitter.set_bins(vec![Bin::new(8); 2]);
+        fitter.set_bin_order(BinOrder::Capacity);
+        assert_eq!(fitter.stats().restarts, 1);
+    }
+
+    fn assert_send(_: &impl Send) {}
+
+    #[test]
+    fn fitter_is_send() {
+        let mut fitter = Fitter::new(vec![1, 2], [3]);
+        fitter.on_event(|_| {});
+        fitter.add_pruner(|_: &[Bin<u32>], _: &[u32], _| false);
+        fitter.set_progress(progress::NoProgress);
+        assert_send(&fitter);
+    }
+
+    #[test]
+    fn pruner_sees_interchangeable_bins() {
+        // the fixed item makes both bins start with the same remaining capacity
+        let bins = vec![Bin::new(12), Bin::new(10)];
+        let mut fitter = Fitter::with_bins(vec![6, 1], bins);
+        fitter.bins[0].push(2);
+        fitter.fixed[0] = 1;
+        fitter.add_pruner(|bins: &[Bin<u32>], items: &[u32], _| {
+            items.is_empty() && bins[0].items.contains(&6)
+        });
+
+        assert_eq!(
+            fitter.solve_with_limits(SolveLimits::default()),
+            SolveOutcome::Solved
+        );
+        assert_eq!(fitter.bins[1].items, [6]);
+    }
+
+    #[test]
+    fn pruner_sees_symmetric_bins() {
+        let mut fitter = Fitter::new(vec![5, 2], [10, 10]);
+        fitter.add_pruner(|bins: &[Bin<u32>], _: &[u32], _| bins[0].items.contains(&5));
+
+        assert_eq!(
+            fitter.solve_with_limits(SolveLimits::default()),
+            SolveOutcome::Solved
+        );
+        assert_eq!(fitter.bins[1].items, [5]);
+    }
 }
@@ -0,0 +1,137 @@
+use std::{hash, iter, mem, ops};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    fits_in_total,
+    heuristics::{FitRule, Greedy},
+    solver::{Budget, SolveLimits, SolveOutcome, Solver},
+    Bin, Fitter,
+};
+
+/// Large neighborhood search: starts from first-fit decreasing, then again and again empties a
+/// few random bins and repacks their items together with the unpacked ones, keeping the result
+/// unless it leaves more weight unpacked. Finds packings fast, but never proves there is none:
+/// it gives up after `max_iterations`.
+pub struct Lns<T> {
+    pub items: Vec<T>,
+    pub bins: Vec<Bin<T>>,
+    /// Bins emptied in each iteration.
+    pub neighborhood: usize,
+    pub max_iterations: u64,
+    /// Items the exact repair of a neighborhood may place before falling back to a random
+    /// first-fit.
+    pub repair_nodes: u64,
+    pub seed: u64,
+}
+
+impl<T> Lns<T>
+where
+    T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    pub fn new(items: Vec<T>, bin_capacities: impl IntoIterator<Item = T>) -> Self {
+        Self::with_bins(items, bin_capacities.into_iter().map(Bin::new).collect())
+    }
+
+    /// Pack `items` around the items already in `bins`, which are never moved.
+    pub fn with_bins(items: Vec<T>, bins: Vec<Bin<T>>) -> Self {
+        Self {
+            items,
+            bins,
+            neighborhood: 3,
+            max_iterations: 10_000,
+            repair_nodes: 1_000,
+            seed: 0,
+        }
+    }
+
+    /// Empties `chosen` down to their `fixed` items and repacks them with the unpacked items,
+    /// keeping the repair unless it leaves more weight unpacked.
+    fn repair(&mut self, chosen: &[usize], fixed: &[usize], rng: &mut StdRng) {
+        let mut pool = self.items.clone();
+        let mut bins: Vec<Bin<T>> = chosen.iter().map(|&idx| self.bins[idx].clone()).collect();
+        for (bin, &idx) in bins.iter_mut().zip(chosen) {
+            while bin.items.len() > fixed[idx] {
+                pool.extend(bin.pop());
+            }
+        }
+
+        let mut exact = Fitter::with_bins(pool.clone(), bins.clone());
+        let limits = SolveLimits {
+            max_nodes: Some(self.repair_nodes),
+            ..SolveLimits::default()
+        };
+        if exact.solve_with_limits(limits) == SolveOutcome::Solved {
+            for (bin, &idx) in exact.bins.into_iter().zip(chosen) {
+                self.bins[idx] = bin;
+            }
+
+            self.items.clear();
+            return;
+        }
+
+        pool.shuffle(rng);
+        let mut unpacked = Vec::new();
+        for item in pool {
+            match bins.iter_mut().find(|bin| bin.fits(&item)) {
+                Some(bin) => bin.push(item),
+                None => unpacked.push(item),
+            }
+        }
+
+        // keep the repair only if it leaves at most as much weight unpacked
+        if !fits_in_total(&unpacked, &self.items) {
+            return;
+        }
+
+        for (bin, &idx) in bins.into_iter().zip(chosen) {
+            self.bins[idx] = bin;
+        }
+
+        self.items = unpacked;
+    }
+}
+
+impl<T> Solver<T> for Lns<T>
+where
+    T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    fn solve(&mut self, budget: Budget) -> SolveOutcome {
+        let fixed: Vec<usize> = self.bins.iter().map(|bin| bin.items.len()).collect();
+        let mut greedy = Greedy::with_bins(
+            mem::take(&mut self.items),
+            mem::take(&mut self.bins),
+            FitRule::First,
+        );
+
+        let outcome = greedy.solve(budget.clone());
+        self.items = greedy.items;
+        self.bins = greedy.bins;
+        if outcome != SolveOutcome::Exhausted {
+            return outcome;
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut indices: Vec<usize> = (0..self.bins.len()).collect();
+        for _ in 0..self.max_iterations {
+            if !budget.remains() {
+                return SolveOutcome::Budget;
+            }
+
+            let (chosen, _) = indices.partial_shuffle(&mut rng, self.neighborhood);
+            let chosen = chosen.to_vec();
+            self.repair(&chosen, &fixed, &mut rng);
+            if self.items.is_empty() {
+                return SolveOutcome::Solved;
+            }
+        }
+
+        SolveOutcome::Exhausted
+    }
+
+    fn bins(&self) -> &[Bin<T>] {
+        &self.bins
+    }
+}
@@ -1,27 +1,94 @@
-#![feature(deadline_api)]
-#![feature(is_sorted)]
-#![feature(buf_read_has_data_left)]
-
 use clap::Parser;
+use cli::{
+    bpplib,
+    completions::{self, Shell},
+    config, csv,
+    json::{self, Json},
+    plain,
+};
 use core::fmt;
+use fitter::{
+    batch,
+    bounds::{self, LowerBound},
+    capacity,
+    completion::BinCompletion,
+    float::Float,
+    generator,
+    geom2d::Rect,
+    heuristics::{FitRule, Greedy},
+    knapsack,
+    lns::Lns,
+    portfolio::Portfolio,
+    progress::{BarProgress, LogProgress, ProgressReporter},
+    rational::Rational,
+    size::Size,
+    solver::{Budget, CancellationToken, SolveOutcome, SolveStats, Solver},
+    span::Span,
+    SolutionState,
+};
 use std::{
-    cmp, error, fs,
-    io::{self, BufRead},
-    path, time,
+    cell::{Cell, RefCell},
+    cmp,
+    collections::BTreeMap,
+    env, error, ffi, fs,
+    io::{self, BufRead, IsTerminal, Write},
+    iter, path, process, str,
+    sync::{Mutex, OnceLock},
+    thread, time,
 };
 
+// The std macros, writing through `write_output` so `--jobs` can keep the output of each
+// instance together.
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::write_output(format_args!($($arg)*))
+    };
+}
+
+macro_rules! println {
+    () => {
+        $crate::write_output(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::write_output(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+// after the macros, so the modules print through them too
+mod cli;
+
 /// A backtracking solution to bin packing problem
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+// the options given override the ones of the config file, which go before them
+#[command(author, version, about, long_about = None, args_override_self = true)]
 struct Args {
+    /// The config file with the defaults of the options, `$XDG_CONFIG_HOME/fitter.toml` or
+    /// `~/.config/fitter.toml` if it exists. Each `key = value` line sets `--key=value`, a
+    /// `true` sets the flag. Only these lines and comments of TOML are read, no tables or
+    /// arrays; a value is a string, a number, a boolean or a bare word like `max-rects`
+    #[arg(long, value_name = "FILE")]
+    config: Option<path::PathBuf>,
+
     /// Input file to parse (uses stdin by default)
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "files")]
     input_file: Option<path::PathBuf>,
 
+    /// Input files solved one after the other, each under a `c file` line with its path
+    #[arg(value_name = "FILE")]
+    files: Vec<path::PathBuf>,
+
+    #[command(flatten)]
+    input: InputArgs,
+
     /// Timeout for the solve
     #[arg(short, long)]
     timeout: Option<humantime::Duration>,
 
+    /// Give each instance at most this long in all, every search of it included, before moving
+    /// on to the next one with what was found so far
+    #[arg(long, value_name = "DURATION")]
+    instance_timeout: Option<humantime::Duration>,
+
     /// Show the values
     #[arg(long)]
     values: bool,
@@ -30,27 +97,831 @@ struct Args {
     #[arg(long)]
     minimize: bool,
 
+    /// Pack into at most this many bins instead of one per item, fixed bins included
+    #[arg(long, visible_alias = "max-bins", value_name = "N")]
+    bins: Option<usize>,
+
+    /// Write an `o` line with the number of bins each time the minimize search finds a better
+    /// packing, followed by the packing with `--values`
+    #[arg(long, conflicts_with = "format")]
+    anytime: bool,
+
+    /// Write the bins fullest first, largest first, or by label instead of largest items first
+    #[arg(long, value_enum, value_name = "ORDER")]
+    sort_bins: Option<BinSort>,
+
+    /// Write the items of each bin in ascending or descending order of weight
+    #[arg(long, value_enum, value_name = "ORDER")]
+    sort_items: Option<ItemSort>,
+
+    /// Write the empty bins in the default mode too, up to `--bins`, and start each `v` line
+    /// with the position of its bin before sorting, like `v 2: 5 4`
+    #[arg(long)]
+    empty_bins: bool,
+
+    /// Stop minimizing once the items fit in this many bins, fixed bins included
+    #[arg(long, value_name = "N", requires = "minimize")]
+    min_bins: Option<usize>,
+
+    /// The engine packing the items: exact backtracking or bin completion, the first-fit or
+    /// best-fit decreasing heuristics, large neighborhood search, or all of them at once
+    #[arg(long, value_enum, default_value_t = Algorithm::Backtracking)]
+    algorithm: Algorithm,
+
+    /// How the default mode writes its result, the other modes only write text, but for csv with
+    /// `--labels` and json with `--bound-only`
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Format::Text,
+        conflicts_with_all = [
+            "partial", "check", "cover", "makespan", "min_capacity", "overflow", "knapsack",
+            "priorities", "count", "split", "max_per_color", "alternate_colors", "max_classes",
+            "min_fill", "fragile", "temporal", "groups", "nested", "repack", "geom2d",
+        ]
+    )]
+    format: Format,
+
+    /// Write a `c stats` line after each solve of the default mode with the search
+    /// statistics, the time taken, the bins used and the lower bound on them
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        conflicts_with = "format"
+    )]
+    stats: Option<StatsFormat>,
+
+    /// Even out the loads of the bins once the number of bins is settled
+    #[arg(long, conflicts_with = "warm_start")]
+    balance: bool,
+
+    /// When the items don't fit, pack as many of them as possible (`count`), or the most
+    /// valuable `weight,value` items (`value`), and list the rest on a `u` line
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "count")]
+    partial: Option<PartialObjective>,
+
+    /// Keep the items of the `v` lines in this file fixed in their bins and pack the rest of
+    /// the items around them
+    #[arg(long, value_name = "FILE")]
+    warm_start: Option<path::PathBuf>,
+
+    /// Repack the items starting from the `v` lines in this file, moving as few items as
+    /// possible out of their bin
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["warm_start", "balance"])]
+    repack: Option<path::PathBuf>,
+
     #[command(flatten)]
     verbose: clap_verbosity_flag::Verbosity<clap_verbosity_flag::WarnLevel>,
 
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Read multiple inputs and parse one by one
     #[arg(long)]
     multi_mode: bool,
+
+    /// Exit with 10 when every instance is satisfiable, 20 when one isn't, and 0 otherwise
+    #[arg(long)]
+    exit_codes: bool,
+
+    /// Seed of the randomized engines and of the instance generator, the same seed gives the
+    /// same results
+    #[arg(long, global = true, default_value_t = 0)]
+    seed: u64,
+
+    /// Stop and print the best packing found so far once the process holds this much memory,
+    /// like `512MiB` or `2G`
+    #[arg(long, value_name = "SIZE")]
+    memory_limit: Option<Size>,
+
+    /// Instances of `--multi-mode` solved at the same time, the output stays in input order
+    #[arg(short, long, default_value_t = 1, requires = "multi_mode")]
+    jobs: usize,
+
+    /// Draw a progress bar on stderr instead of logging the progress, if stderr is a terminal
+    #[arg(long)]
+    progress: bool,
+
+    /// Step through the backtracking search, showing the bins on stderr and waiting for a
+    /// command on the terminal: enter for the next step, `b` to run to the next backtrack, `c`
+    /// to run to the end and `q` to stop
+    #[arg(long, conflicts_with = "jobs")]
+    interactive: bool,
+
+    /// Print the L1 and L2 lower bounds and the first-fit and best-fit decreasing upper bounds
+    /// on the number of bins on `b` lines, without searching
+    #[arg(long, conflicts_with = "check")]
+    bound_only: bool,
+
+    /// Check the packing in this file, `v` lines or the output of `--format json`, against
+    /// the instance instead of solving it
+    #[arg(long, value_name = "FILE")]
+    check: Option<path::PathBuf>,
+
+    /// Bin covering: maximize the number of bins filled to at least the capacity
+    #[arg(long, conflicts_with = "geom2d")]
+    cover: bool,
+
+    /// Treat the first number of the input as the number of bins and minimize the maximum load
+    #[arg(long, conflicts_with_all = ["cover", "geom2d"])]
+    makespan: bool,
+
+    /// Find the smallest capacity, up to the one in the input, that fits the items in this many
+    /// bins
+    #[arg(long, value_name = "BINS", conflicts_with_all = ["cover", "makespan", "geom2d"])]
+    min_capacity: Option<usize>,
+
+    /// Allow loading bins up to this many units over their capacity, minimizing the overflow
+    #[arg(long, value_name = "UNITS", conflicts_with_all = ["cover", "makespan", "geom2d"])]
+    overflow: Option<u32>,
+
+    /// Penalty of every unit over the capacity
+    #[arg(long, default_value_t = 1, requires = "overflow")]
+    overflow_penalty: u64,
+
+    /// Select the most valuable `weight,value` items that fit in the bins, the first line lists
+    /// the bin capacities
+    #[arg(long, conflicts_with_all = ["cover", "makespan", "geom2d"])]
+    knapsack: bool,
+
+    /// Items are `weight^priority`, all items of priority 1 (the default) have to be packed in
+    /// the bins of the first line, then as many as possible of priority 2, 3 and so on
+    #[arg(long, conflicts_with_all = ["cover", "makespan", "knapsack", "geom2d"])]
+    priorities: bool,
+
+    /// Weights and the capacity are decimal numbers
+    #[arg(long, conflicts_with_all = ["cover", "makespan", "knapsack", "geom2d", "warm_start", "balance", "partial", "format"])]
+    float: bool,
+
+    /// Weights and the capacity are exact fractions like `2/3`
+    #[arg(long, conflicts_with_all = ["float", "cover", "makespan", "knapsack", "geom2d", "warm_start", "balance", "partial", "format"])]
+    rational: bool,
+
+    /// Weights and the capacity are durations like `90m` or `1h30m`, e.g. tasks in shifts
+    #[arg(long, conflicts_with_all = ["float", "rational", "cover", "makespan", "knapsack", "geom2d", "warm_start", "balance", "partial", "format"])]
+    durations: bool,
+
+    /// Weights and the capacity are exact decimal numbers like `2.5`, all scaled by the power of
+    /// ten that makes them whole, reported on a `c scale` line
+    #[arg(long, conflicts_with_all = ["float", "rational", "durations", "sizes", "cover", "makespan", "knapsack", "geom2d", "warm_start", "partial"])]
+    decimal: bool,
+
+    /// Weights and the capacity are whole numbers of any size, e.g. byte counts past `u64`
+    #[cfg(feature = "bigint")]
+    #[arg(long, conflicts_with_all = ["float", "rational", "durations", "decimal", "sizes", "cover", "makespan", "knapsack", "geom2d", "warm_start", "balance", "partial", "format"])]
+    bigint: bool,
+
+    /// Weights and the capacity are sizes like `700MiB` or `4.5GB`, e.g. files onto media
+    #[arg(long, conflicts_with_all = ["float", "rational", "durations", "cover", "makespan", "knapsack", "geom2d", "warm_start", "balance", "partial", "format"])]
+    sizes: bool,
+
+    /// Count the distinct packings into the bins of the first line, up to CAP
+    #[arg(long, value_name = "CAP", num_args = 0..=1, conflicts_with_all = ["cover", "makespan", "knapsack", "geom2d"])]
+    count: Option<Option<usize>>,
+
+    /// Allow splitting each item in up to this many fragments, minimizing the split penalty
+    #[arg(long, value_name = "FRAGMENTS", conflicts_with_all = ["cover", "makespan", "knapsack", "geom2d"])]
+    split: Option<usize>,
+
+    /// Penalty of every split
+    #[arg(long, default_value_t = 1, requires = "split")]
+    split_penalty: u64,
+
+    /// Allow at most this many items of the same color (`weight#color`) per bin
+    #[arg(long, value_name = "K")]
+    max_per_color: Option<usize>,
+
+    /// Items in a bin must be arrangeable so no two consecutive items share a color
+    #[arg(long)]
+    alternate_colors: bool,
+
+    /// Allow items of at most this many distinct classes (`weight#class`) per bin
+    #[arg(long, value_name = "C")]
+    max_classes: Option<usize>,
+
+    /// Bins that aren't empty have to be filled to at least this load, or percentage of the
+    /// capacity (e.g. `80%`)
+    #[arg(long, value_name = "FILL")]
+    min_fill: Option<MinFill>,
+
+    /// Items may be `weight!threshold`, the load of a bin holding them may not exceed the
+    /// threshold
+    #[arg(long)]
+    fragile: bool,
+
+    /// Items are `weight@start-end` and only occupy their bin during `[start, end)`
+    #[arg(long)]
+    temporal: bool,
+
+    /// The first line lists the bins as `capacity#group`, the `v` lines follow the order of the
+    /// bins
+    #[arg(long, conflicts_with_all = ["cover", "makespan", "geom2d", "knapsack"])]
+    groups: bool,
+
+    /// The first line lists the bins as `capacity#label`, the items are packed into exactly
+    /// these bins and each `v` line starts with the `#label` of its bin
+    #[arg(long, conflicts_with_all = ["cover", "makespan", "geom2d", "knapsack", "groups"])]
+    labels: bool,
+
+    /// The order the bins of `--labels` are tried in: insertion, capacity or label
+    #[arg(
+        long,
+        value_name = "ORDER",
+        default_value = "insertion",
+        requires = "labels"
+    )]
+    bin_order: fitter::BinOrder,
+
+    /// Allow a group to open at most this many of its bins
+    #[arg(long, value_name = "GROUP=BINS", requires = "groups")]
+    group_bins: Vec<GroupLimit>,
+
+    /// Allow a group to hold at most this much weight
+    #[arg(long, value_name = "GROUP=WEIGHT", requires = "groups")]
+    group_weight: Vec<GroupLimit>,
+
+    /// Pack the items in boxes of the input capacity and the boxes on pallets that carry this
+    /// much, the `v` lines list the boxes of each pallet in brackets
+    #[arg(long, value_name = "CAPACITY", conflicts_with_all = ["cover", "makespan", "geom2d"])]
+    nested: Option<u32>,
+
+    /// Allow at most this many boxes per pallet
+    #[arg(long, value_name = "BOXES", requires = "nested")]
+    boxes_per_pallet: Option<usize>,
+
+    /// Minimize the pallets with the boxing of their items, rather than the boxes first
+    #[arg(long, requires = "nested")]
+    joint: bool,
+
+    /// Pack `WxH` rectangles into `WxH` sheets (guillotine, max-rects or exact)
+    #[arg(long, value_name = "METHOD")]
+    geom2d: Option<fitter::geom2d::Method>,
+
+    /// Allow rotating rectangles by 90 degrees
+    #[arg(long)]
+    rotate: bool,
+
+    /// Pack all rectangles in a single strip of the sheet width, minimizing its height
+    #[arg(long, requires = "geom2d")]
+    strip: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum MinFill {
+    Absolute(u32),
+    Percent(f64),
+}
+
+impl MinFill {
+    fn of(&self, capacity: u32) -> u64 {
+        match *self {
+            MinFill::Absolute(fill) => fill as u64,
+            MinFill::Percent(percent) => (capacity as f64 * percent / 100.).ceil() as u64,
+        }
+    }
+}
+
+impl str::FromStr for MinFill {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix('%') {
+            Some(percent) => Ok(Self::Percent(percent.parse()?)),
+            None => Ok(Self::Absolute(s.parse()?)),
+        }
+    }
+}
+
+/// A limit of the form `group=limit`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct GroupLimit {
+    group: String,
+    limit: u64,
+}
+
+impl str::FromStr for GroupLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (group, limit) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("invalid limit {s:?}, expected group=limit"))?;
+
+        Ok(Self {
+            group: group.to_string(),
+            limit: limit.parse()?,
+        })
+    }
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Write a random instance in the input format
+    Generate {
+        /// The family to draw from: falkenauer-u, falkenauer-t or weibull
+        #[arg(long, default_value = "falkenauer-u")]
+        class: generator::Family,
+
+        /// The number of items, rounded down to a multiple of 3 for falkenauer-t
+        #[arg(long, default_value_t = 120)]
+        n: usize,
+    },
+
+    /// Describe the instances in a file without solving them: the weights, their histogram and
+    /// duplicates, and the lower bounds on the bins
+    Stats {
+        /// The file to read the instances from, stdin by default
+        file: Option<path::PathBuf>,
+    },
+
+    /// Check the instances in a file without solving them, listing each problem with its line:
+    /// items that don't parse or fit in any bin, zero or negative weights, and stray 0s
+    Validate {
+        /// The file to check, stdin by default
+        file: Option<path::PathBuf>,
+    },
+
+    /// Remove and lighten the items of an instance for as long as it stays interesting, then
+    /// write the smallest instance found, to report a bug or a slow instance with
+    Shrink {
+        /// The file to read the instance from, stdin by default
+        file: Option<path::PathBuf>,
+
+        #[command(flatten)]
+        interesting: Interesting,
+    },
+
+    /// Minimize the bins of each instance in a file with every engine within `--timeout`, a `r`
+    /// line for each, and check that they agree: every packing verifies, and none has fewer bins
+    /// than one proven optimal or than the lower bound
+    Crosscheck {
+        /// The file to read the instances from, stdin by default
+        file: Option<path::PathBuf>,
+    },
+
+    /// Write the completions of the options, their values and the subcommands for a shell, to
+    /// be sourced from its startup file
+    Completions { shell: Shell },
+
+    /// Minimize the bins of every instance in a directory and print a table of the results
+    Bench {
+        /// The directory to read the instances from, files can hold several of them
+        dir: path::PathBuf,
+
+        /// Time limit of each instance
+        #[arg(short, long, default_value = "10s")]
+        timeout: humantime::Duration,
+
+        /// Instances solved at the same time
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+}
+
+/// The order of the bins in the output, see [`arrange_bins`].
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum BinSort {
+    /// The fullest bins first
+    Fill,
+    /// The largest bins first
+    Capacity,
+    /// By label, the bins without one last
+    Label,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum ItemSort {
+    Ascending,
+    Descending,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum Algorithm {
+    /// Place one item at a time, backtracking on failure
+    Backtracking,
+    /// Fill one bin at a time with its maximal completions
+    BinCompletion,
+    /// First-fit decreasing, without backtracking
+    Ffd,
+    /// Best-fit decreasing, without backtracking
+    Bfd,
+    /// Large neighborhood search, repacking a few bins at a time
+    Lns,
+    /// Run all engines at once and keep the first answer
+    Portfolio,
+}
+
+impl Algorithm {
+    /// Whether the engine only gives up when there is no packing.
+    fn is_exact(self) -> bool {
+        matches!(
+            self,
+            Algorithm::Backtracking | Algorithm::BinCompletion | Algorithm::Portfolio
+        )
+    }
+}
+
+thread_local! {
+    /// The output of the instance solved on this thread with `--jobs`, written out in order
+    /// once it's done.
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// When the instance solved on this thread has to be done with `--instance-timeout`.
+    static INSTANCE_DEADLINE: Cell<Option<time::Instant>> = const { Cell::new(None) };
+}
+
+/// When a solve starting at `start` has to stop, with `--timeout` and `--instance-timeout`.
+fn solve_deadline(args: &Args, start: time::Instant) -> Option<time::Instant> {
+    let deadline = args.timeout.map(|timeout| start + timeout.into());
+    match (deadline, INSTANCE_DEADLINE.get()) {
+        (Some(deadline), Some(instance)) => Some(deadline.min(instance)),
+        (deadline, instance) => deadline.or(instance),
+    }
+}
+
+/// Writes the output of `print!` and `println!`, to stdout or to the output captured on this
+/// thread.
+fn write_output(args: fmt::Arguments<'_>) {
+    CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(captured) => fmt::Write::write_fmt(captured, args).unwrap(),
+        None => {
+            if let Err(err) = io::stdout().lock().write_fmt(args) {
+                panic!("failed printing to stdout: {err}");
+            }
+        }
+    });
+}
+
+/// Cancelled by the first Ctrl-C, the solves stop and print the best packing they found.
+static INTERRUPT: OnceLock<CancellationToken> = OnceLock::new();
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_: libc::c_int) {
+    if let Some(token) = INTERRUPT.get() {
+        token.cancel();
+    }
+
+    // a second Ctrl-C kills the run as usual
+    unsafe { libc::signal(libc::SIGINT, libc::SIG_DFL) };
+}
+
+/// Makes the first Ctrl-C cancel [`INTERRUPT`] instead of killing the run.
+fn handle_interrupts() {
+    INTERRUPT.get_or_init(CancellationToken::new);
+
+    #[cfg(unix)]
+    unsafe {
+        let handler: extern "C" fn(libc::c_int) = on_interrupt;
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+/// Stops the solves like Ctrl-C does once the process holds more than `limit` of memory.
+fn limit_memory(limit: Size) {
+    thread::spawn(move || loop {
+        let Some(used) = resident_memory() else {
+            log::warn!("Can't tell the memory in use here, ignoring --memory-limit");
+            break;
+        };
+
+        if used > limit.0 {
+            log::warn!(
+                "Using {used} bytes of memory, over the limit of {}",
+                limit.0
+            );
+            if let Some(token) = INTERRUPT.get() {
+                token.cancel();
+            }
+
+            break;
+        }
+
+        thread::sleep(MEMORY_POLL_INTERVAL);
+    });
+}
+
+/// How often [`limit_memory`] looks at the memory in use.
+const MEMORY_POLL_INTERVAL: time::Duration = time::Duration::from_millis(50);
+
+/// The memory the process holds in bytes, where `/proc` tells.
+fn resident_memory() -> Option<u64> {
+    let statm = fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+
+    #[cfg(unix)]
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+    #[cfg(not(unix))]
+    let page_size = 4096;
+
+    Some(pages * page_size)
+}
+
+/// Whether the run was interrupted with Ctrl-C, or by [`limit_memory`].
+fn interrupted() -> bool {
+    INTERRUPT.get().is_some_and(CancellationToken::is_cancelled)
+}
+
+/// Runs until `deadline`, if any, or until the run is interrupted.
+fn budget(deadline: Option<time::Instant>) -> Budget {
+    let budget = deadline.map_or_else(Budget::unlimited, Budget::until);
+    match INTERRUPT.get() {
+        Some(token) => budget.with_cancellation(token.clone()),
+        None => budget,
+    }
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub enum SolutionState<S> {
+/// A progress bar with `--progress` on a terminal, progress logs otherwise.
+fn progress_reporter(args: &Args) -> Box<dyn ProgressReporter + Send> {
+    match args.progress && io::stderr().is_terminal() {
+        true => Box::new(BarProgress::default()),
+        false => Box::new(LogProgress::default()),
+    }
+}
+
+/// Builds the engine for `algorithm`, the items already in `bins` stay where they are.
+fn engine(
+    algorithm: Algorithm,
+    items: Vec<u32>,
+    bins: Vec<fitter::Bin<u32>>,
+    seed: u64,
+) -> Box<dyn Solver<u32>> {
+    match algorithm {
+        Algorithm::Backtracking => Box::new(fitter::Fitter::with_bins(items, bins)),
+        Algorithm::BinCompletion => Box::new(BinCompletion::with_bins(items, bins)),
+        Algorithm::Ffd => Box::new(Greedy::with_bins(items, bins, FitRule::First)),
+        Algorithm::Bfd => Box::new(Greedy::with_bins(items, bins, FitRule::Best)),
+        Algorithm::Lns => {
+            let mut lns = Lns::with_bins(items, bins);
+            lns.seed = seed;
+            Box::new(lns)
+        }
+        Algorithm::Portfolio => {
+            let mut portfolio = Portfolio::new(items, bins);
+            for (algorithm, exact) in [
+                (Algorithm::Ffd, false),
+                (Algorithm::Backtracking, true),
+                (Algorithm::BinCompletion, true),
+                (Algorithm::Lns, false),
+            ] {
+                portfolio.add(
+                    move |items, bins| engine(algorithm, items, bins, seed),
+                    exact,
+                );
+            }
+
+            Box::new(portfolio)
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum Format {
+    /// `s` and `v` lines
+    Text,
+    /// A JSON object with the status, the bins, the search statistics and the time taken
+    Json,
+    /// A row for each item with its position in the input, its weight, and its bin
+    Csv,
+    /// The JSON object on a single line, one line per instance with `--multi-mode`
+    Jsonl,
+    /// An SVG diagram of the bins as columns of their items, with the fill of each bin
+    Svg,
+    /// A Graphviz graph with a cluster for each bin around its items
+    Dot,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq)]
+enum InputFormat {
+    /// JSON when the input looks like it, plain otherwise
     #[default]
-    Unknown,
-    Unsolvable,
-    Solved(S),
+    Auto,
+    /// The bin capacities on the first line, then the items up to a 0, `42 x 500` for 500 copies
+    Plain,
+    /// The BPPLIB and OR-Library layout: the number of items, the capacity, then the weights.
+    /// Never guessed, as a plain instance with a weight on each line looks the same
+    Bpplib,
+    /// A row for each item with the columns `id,weight,value,group`, the last two optional, or
+    /// the ones named by a header; the ids name the items and the capacity comes from
+    /// `--capacity`
+    Csv,
+    /// An object with a `capacity` or a list of `bins`, and a list of `items` that are weights
+    /// or objects with a `weight` and optional fields, see `instance.schema.json`
+    Json,
+}
+
+/// What keeps an instance interesting to the `shrink` subcommand, exactly one of them.
+#[derive(clap::Args, Debug)]
+#[group(required = true, multiple = false)]
+struct Interesting {
+    /// The exact search doesn't prove the fewest bins within this time
+    #[arg(long, value_name = "DURATION")]
+    slower_than: Option<humantime::Duration>,
+
+    /// The exact search proves more bins are needed than first-fit decreasing uses, or finds a
+    /// packing that doesn't verify, within `--timeout`
+    #[arg(long)]
+    disagrees_with_ffd: bool,
+
+    /// The command, run with `sh -c` and the instance on stdin, fails
+    #[arg(long, value_name = "CMD")]
+    command: Option<String>,
+}
+
+/// How the instances are read.
+#[derive(clap::Args, Clone, Debug, Default)]
+struct InputArgs {
+    /// The layout of the instances
+    #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+    input_format: InputFormat,
+
+    /// The bin capacity of CSV input
+    #[arg(long, value_name = "C")]
+    capacity: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum StatsFormat {
+    /// `key=value` pairs
+    Text,
+    /// A JSON object
+    Json,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum PartialObjective {
+    /// Maximize the number of packed items
+    Count,
+    /// Maximize the total value of the packed items
+    Value,
 }
 
-impl<S> SolutionState<S> {
-    pub fn insert(&mut self, status: Self) {
-        if let Self::Unknown = self {
-            *self = status
+/// An item with an optional name, as `name:item`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Named<I> {
+    name: Option<String>,
+    item: I,
+}
+
+impl<I: str::FromStr> str::FromStr for Named<I> {
+    type Err = I::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, item) = match s.rsplit_once(':') {
+            Some((name, item)) => (Some(name.to_string()), item),
+            None => (None, s),
         };
+
+        Ok(Self {
+            name,
+            item: item.parse()?,
+        })
+    }
+}
+
+/// An item of the form `weight#label`, the label is optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct LabeledItem {
+    weight: u32,
+    label: Option<String>,
+}
+
+impl str::FromStr for LabeledItem {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (weight, label) = match s.split_once('#') {
+            Some((weight, label)) => (weight, Some(label.to_string())),
+            None => (s, None),
+        };
+
+        Ok(Self {
+            weight: weight.parse()?,
+            label,
+        })
+    }
+}
+
+impl fmt::Display for LabeledItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{}#{label}", self.weight),
+            None => write!(f, "{}", self.weight),
+        }
+    }
+}
+
+/// Maps the labels of the items to dense ids.
+fn label_ids(items: &[LabeledItem]) -> Vec<Option<usize>> {
+    let mut ids = std::collections::HashMap::new();
+    items
+        .iter()
+        .map(|item| {
+            let next_id = ids.len();
+            item.label
+                .as_ref()
+                .map(|label| *ids.entry(label.clone()).or_insert(next_id))
+        })
+        .collect()
+}
+
+/// An item of the form `weight@start-end`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct TemporalItem {
+    weight: u32,
+    start: u32,
+    end: u32,
+}
+
+impl str::FromStr for TemporalItem {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (weight, interval) = s.split_once('@')?;
+            let (start, end) = interval.split_once('-')?;
+            Some(Self {
+                weight: weight.parse().ok()?,
+                start: start.parse().ok()?,
+                end: end.parse().ok()?,
+            })
+        };
+
+        parse().ok_or_else(|| anyhow::anyhow!("invalid item {s:?}, expected weight@start-end"))
+    }
+}
+
+impl fmt::Display for TemporalItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}-{}", self.weight, self.start, self.end)
+    }
+}
+
+/// An item of the form `weight!threshold`, the threshold is optional.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct FragileItem {
+    weight: u32,
+    threshold: Option<u32>,
+}
+
+impl str::FromStr for FragileItem {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (weight, threshold) = match s.split_once('!') {
+            Some((weight, threshold)) => (weight, Some(threshold.parse()?)),
+            None => (s, None),
+        };
+
+        Ok(Self {
+            weight: weight.parse()?,
+            threshold,
+        })
+    }
+}
+
+impl fmt::Display for FragileItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.threshold {
+            Some(threshold) => write!(f, "{}!{threshold}", self.weight),
+            None => write!(f, "{}", self.weight),
+        }
+    }
+}
+
+/// An item of the form `weight^priority`, the priority defaults to 1.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct PrioritizedItem {
+    weight: u32,
+    priority: u32,
+}
+
+impl str::FromStr for PrioritizedItem {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (weight, priority) = match s.split_once('^') {
+            Some((weight, priority)) => (weight, priority.parse()?),
+            None => (s, 1),
+        };
+
+        if priority == 0 {
+            anyhow::bail!("invalid item {s:?}, priorities start at 1");
+        }
+
+        Ok(Self {
+            weight: weight.parse()?,
+            priority,
+        })
+    }
+}
+
+impl fmt::Display for PrioritizedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}^{}", self.weight, self.priority)
     }
 }
 
@@ -64,126 +935,924 @@ impl fmt::Display for EOFError {
 
 impl error::Error for EOFError {}
 
-fn parse_input(reader: &mut impl BufRead) -> anyhow::Result<(u32, Vec<u32>)> {
+/// Whether `reader` has anything left to read, like the unstable `BufRead::has_data_left`.
+fn has_data_left(reader: &mut (impl BufRead + ?Sized)) -> io::Result<bool> {
+    reader.fill_buf().map(|buf| !buf.is_empty())
+}
+
+/// Whether `reader` has another instance, past any blank and comment lines, which are skipped.
+fn has_instance_left(reader: &mut (impl BufRead + ?Sized)) -> io::Result<bool> {
+    skip_comments(reader)?;
+    has_data_left(reader)
+}
+
+/// Whether `line` is a comment of the plain and BPPLIB formats: it starts with `#`, or with a
+/// `c` token like the comments of the output.
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('#') || line.split_whitespace().next() == Some("c")
+}
+
+/// Consumes the blank and comment lines at the start of `reader`, and the whitespace the next
+/// line starts with. A line is only consumed once its start shows it's a comment, however
+/// little of it is buffered, so a line split across the buffer is never swallowed. A `c` at
+/// the very end of the buffer is left for the parser, which skips it too if the whole line
+/// turns out to be a comment.
+fn skip_comments(reader: &mut (impl BufRead + ?Sized)) -> io::Result<()> {
+    loop {
+        let buf = reader.fill_buf()?;
+        let blank = buf
+            .iter()
+            .take_while(|byte| byte.is_ascii_whitespace())
+            .count();
+        if blank > 0 {
+            reader.consume(blank);
+            continue;
+        }
+
+        let comment = match buf {
+            [b'#', ..] => true,
+            [b'c', next, ..] => next.is_ascii_whitespace(),
+            _ => false,
+        };
+
+        if !comment {
+            return Ok(());
+        }
+
+        reader.read_until(b'\n', &mut Vec::new())?;
+    }
+}
+
+fn parse_input(reader: &mut impl BufRead, input: &InputArgs) -> anyhow::Result<(u32, Vec<u32>)> {
+    parse_instance(reader, input)
+}
+
+fn parse_instance<I>(reader: &mut impl BufRead, input: &InputArgs) -> anyhow::Result<(u32, Vec<I>)>
+where
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    let (capacities, items) = parse_multi_instance(reader, input)?;
+    match capacities[..] {
+        [bin_capacity] => Ok((bin_capacity, items)),
+        _ => anyhow::bail!("expected a single bin capacity, found {}", capacities.len()),
+    }
+}
+
+fn parse_multi_instance<I>(
+    reader: &mut impl BufRead,
+    input: &InputArgs,
+) -> anyhow::Result<(Vec<u32>, Vec<I>)>
+where
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    parse_bins_instance(reader, input)
+}
+
+/// Like [`parse_multi_instance`], with the bins parsed as `B`.
+fn parse_bins_instance<B, I>(
+    reader: &mut impl BufRead,
+    input: &InputArgs,
+) -> anyhow::Result<(Vec<B>, Vec<I>)>
+where
+    B: str::FromStr + fmt::Debug,
+    B::Err: error::Error + Send + Sync + 'static,
+    I: str::FromStr,
+    anyhow::Error: From<I::Err>,
+{
+    match input.input_format {
+        InputFormat::Bpplib => bpplib::parse_instance(reader),
+        InputFormat::Auto if json::looks_like(reader)? => json::parse_instance(reader),
+        InputFormat::Json => json::parse_instance(reader),
+        InputFormat::Csv => csv::parse_instance(reader, input.capacity.as_deref()),
+        InputFormat::Auto | InputFormat::Plain => plain::parse_instance(reader),
+    }
+}
+
+/// An item written the way the modes read their items, from its `weight` and `extras` like
+/// `,value` or `#group`: with all the extras, or the one the mode reads, or none of them, and
+/// as `name:weight` in the modes that keep names. What the items have no use for is dropped.
+fn parse_item<I: str::FromStr>(
+    name: Option<&str>,
+    weight: &str,
+    extras: &[String],
+) -> Result<I, I::Err> {
+    let named = name.map(|name| format!("{name}:{weight}"));
+    let extras: Vec<String> = iter::once(extras.concat())
+        .chain(extras.iter().cloned())
+        .chain([String::new()])
+        .collect();
+
+    let parsed = named
+        .iter()
+        .map(String::as_str)
+        .chain([weight])
+        .flat_map(|weight| extras.iter().map(move |extra| format!("{weight}{extra}")))
+        .find_map(|item| item.parse().ok());
+
+    parsed.map_or_else(|| weight.parse(), Ok)
+}
+
+fn parse_input_2d(reader: &mut impl BufRead) -> anyhow::Result<(Rect, Vec<Rect>)> {
     let mut line = String::new();
-    let bin_capacity = loop {
-        if !reader.has_data_left()? {
+    let sheet = loop {
+        if !has_instance_left(reader)? {
             Err(EOFError)?;
         }
 
+        line.clear();
         reader.read_line(&mut line)?;
         let trimmed_line = line.trim();
-        log::trace!("trimmed_line={trimmed_line:?}");
-        if !trimmed_line.is_empty() {
-            let count = trimmed_line.parse::<u32>()?;
+        if !trimmed_line.is_empty() && !is_comment(&line) {
+            // a strip only needs a width
+            if let Ok(width) = trimmed_line.parse::<u32>() {
+                break Rect::new(width, u32::MAX);
+            }
 
-            log::trace!("count={count}");
-            break count;
+            break trimmed_line.parse::<Rect>()?;
         }
     };
 
-    let mut weights = Vec::new();
+    let mut rects = Vec::new();
     'outer: loop {
-        let mut line = String::new();
+        if !has_data_left(reader)? {
+            Err(EOFError)?;
+        }
+
+        line.clear();
         reader.read_line(&mut line)?;
+        if is_comment(&line) {
+            continue;
+        }
 
-        for num in line.split_whitespace() {
-            log::trace!("num={num:?}");
-            let num = num.parse::<u32>()?;
-            if num == 0 {
+        for token in line.split_whitespace() {
+            log::trace!("token={token:?}");
+            if token == "0" {
                 break 'outer;
             }
 
-            weights.push(num)
+            rects.push(token.parse::<Rect>()?);
         }
     }
 
-    Ok((bin_capacity, weights))
+    Ok((sheet, rects))
+}
+
+/// Reads the `v` lines of a previous solution, each line is one bin.
+fn parse_fixed_bins(path: &path::Path) -> anyhow::Result<Vec<Vec<u32>>> {
+    parse_v_lines(&fs::read_to_string(path)?)
+}
+
+/// The items of each `v` line in `text`, the other lines are skipped.
+fn parse_v_lines(text: &str) -> anyhow::Result<Vec<Vec<u32>>> {
+    let mut bins = Vec::new();
+    for line in text.lines() {
+        let Some(items) = line.trim().strip_prefix('v') else {
+            continue;
+        };
+
+        // skip the label of the bin
+        let items = items
+            .split_whitespace()
+            .filter(|token| !token.starts_with('#'))
+            .map(str::parse::<u32>)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        bins.push(items);
+    }
+
+    Ok(bins)
+}
+
+/// The bins of a packing written as `v` lines, or as the output of `--format json`.
+fn parse_solution_file(path: &path::Path) -> anyhow::Result<Vec<Vec<u32>>> {
+    let text = fs::read_to_string(path)?;
+    if !text.trim_start().starts_with('{') {
+        return parse_v_lines(&text);
+    }
+
+    let bins = match text.parse::<Json>()?.get("bins") {
+        Some(Json::Array(bins)) => bins.clone(),
+        Some(Json::Null) => anyhow::bail!("the solution has no packing"),
+        _ => anyhow::bail!("expected a \"bins\" list"),
+    };
+
+    bins.iter()
+        .map(|bin| match bin.get("items") {
+            Some(Json::Array(items)) => items.iter().map(Json::to_u32).collect(),
+            _ => anyhow::bail!("expected an \"items\" list in every bin"),
+        })
+        .collect()
+}
+
+/// The bins that hold items, largest items first, like [`fitter::Fitter::packed_bins`].
+fn packed_bins(bins: &[fitter::Bin<u32>]) -> Vec<fitter::Bin<u32>> {
+    let mut packed: Vec<_> = bins.iter().filter(|bin| !bin.is_empty()).cloned().collect();
+    packed.iter_mut().for_each(fitter::Bin::canonicalize);
+    packed.sort_by(|a, b| b.cmp(a));
+    packed
+}
+
+/// The instances that ended with each status, for `--exit-codes`.
+static STATUSES: Mutex<BTreeMap<&str, usize>> = Mutex::new(BTreeMap::new());
+
+/// Writes the `s` line of an instance.
+fn print_status(status: &'static str) {
+    println!("s {status}");
+    record_status(status);
+}
+
+fn record_status(status: &'static str) {
+    *STATUSES.lock().unwrap().entry(status).or_default() += 1;
+}
+
+/// The exit code of `--exit-codes`: 10 when every instance is satisfiable, 20 when one isn't,
+/// and 0 otherwise, like SAT solvers do.
+fn exit_code() -> i32 {
+    let statuses = STATUSES.lock().unwrap();
+    if statuses.contains_key("UNSAT") {
+        20
+    } else if statuses.contains_key("UNKNOWN") || statuses.is_empty() {
+        0
+    } else {
+        10
+    }
+}
+
+/// Writes the `c stats` line of `--stats`, leaving out what the solve doesn't know.
+fn print_stats<T>(
+    format: StatsFormat,
+    solution: &SolutionState<Vec<fitter::Bin<T>>>,
+    stats: Option<&SolveStats>,
+    elapsed: time::Duration,
+    lower_bound: Option<usize>,
+) {
+    let mut fields: Vec<(&str, Json)> = Vec::new();
+    if let Some(stats) = stats {
+        fields.push(("nodes", stats.nodes.into()));
+        fields.push(("backtracks", stats.backtracks.into()));
+        fields.push(("restarts", stats.restarts.into()));
+    }
+
+    fields.push(("time", elapsed.as_secs_f64().into()));
+    if let SolutionState::Solved(bins) = solution {
+        let used = bins.iter().filter(|bin| !bin.items.is_empty()).count();
+        fields.push(("bins", used.into()));
+    }
+
+    if let Some(lower_bound) = lower_bound {
+        fields.push(("lower_bound", lower_bound.into()));
+    }
+
+    let line = match format {
+        StatsFormat::Text => fields
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+        StatsFormat::Json => Json::object(fields).to_string(),
+    };
+
+    println!("c stats {line}");
 }
 
-fn print_solution(best_fit: &[fitter::Bin<u32>]) {
+/// Fails unless `--format` is one of `formats`, for the modes that only write some of them.
+fn require_format(args: &Args, mode: &str, formats: &[Format]) -> anyhow::Result<()> {
+    if formats.contains(&args.format) {
+        return Ok(());
+    }
+
+    let format = clap::ValueEnum::to_possible_value(&args.format).unwrap();
+    anyhow::bail!("{mode} doesn't write --format {}", format.get_name())
+}
+
+/// Writes the result in the `--format` other than text, returning false for text, which every
+/// mode writes its own way.
+fn print_structured(
+    args: &Args,
+    input: &[u32],
+    names: &[Option<String>],
+    solution: &SolutionState<Vec<fitter::Bin<u32>>>,
+    stats: Option<&SolveStats>,
+    elapsed: time::Duration,
+) -> bool {
+    if args.format != Format::Text {
+        record_status(match solution {
+            SolutionState::Unknown => "UNKNOWN",
+            SolutionState::Unsolvable => "UNSAT",
+            SolutionState::Solved(_) => "SAT",
+        });
+    }
+
+    match (args.format, solution) {
+        (Format::Text, _) => return false,
+        (Format::Json, _) => println!("{:#}", json::result(solution, stats, elapsed)),
+        (Format::Jsonl, _) => println!("{}", json::result(solution, stats, elapsed)),
+        (Format::Csv, SolutionState::Solved(bins)) => {
+            csv::print_assignment(input, &item_bins(input, bins), bins)
+        }
+        (Format::Csv, _) => csv::print_assignment(input, &vec![None; input.len()], &[]),
+        (Format::Svg, SolutionState::Solved(bins)) => cli::svg::print(input, names, bins),
+        (Format::Svg, SolutionState::Unsolvable) => cli::svg::print_message("UNSAT"),
+        (Format::Svg, SolutionState::Unknown) => cli::svg::print_message("UNKNOWN"),
+        (Format::Dot, SolutionState::Solved(bins)) => cli::dot::print(input, names, bins),
+        (Format::Dot, SolutionState::Unsolvable) => println!("graph packing {{ label=UNSAT }}"),
+        (Format::Dot, SolutionState::Unknown) => println!("graph packing {{ label=UNKNOWN }}"),
+    }
+
+    true
+}
+
+/// The bin of each of the `input` items in `bins`, items of the same weight are handed out in
+/// input order.
+fn item_bins(input: &[u32], bins: &[fitter::Bin<u32>]) -> Vec<Option<usize>> {
+    let mut item_bins = vec![None; input.len()];
+    for (bin, positions) in bin_positions(input, bins).into_iter().enumerate() {
+        for idx in positions {
+            item_bins[idx] = Some(bin);
+        }
+    }
+
+    item_bins
+}
+
+/// The positions in `input` of the items in each of `bins`, in the order of the bin, handed
+/// out like [`item_bins`] does.
+fn bin_positions(input: &[u32], bins: &[fitter::Bin<u32>]) -> Vec<Vec<usize>> {
+    let mut positions: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+    for (idx, &weight) in input.iter().enumerate().rev() {
+        positions.entry(weight).or_default().push(idx);
+    }
+
+    bins.iter()
+        .map(|bin| {
+            bin.items
+                .iter()
+                .filter_map(|weight| positions.get_mut(weight).and_then(Vec::pop))
+                .collect()
+        })
+        .collect()
+}
+
+fn print_solution<T: Ord + fmt::Display>(best_fit: &[fitter::Bin<T>]) {
     best_fit
         .iter()
         // .filter(|bin| !bin.is_empty())
         .for_each(|bin| {
             let line = bin
-                .items()
+                .items
                 .iter()
                 .map(ToString::to_string)
                 .collect::<Vec<_>>()
                 .join(" ");
 
-            println!("v {}", line);
+            match &bin.label {
+                Some(label) => println!("v #{label} {}", line),
+                None => println!("v {}", line),
+            }
         });
 
     let is_sorted = best_fit.is_sorted_by_key(cmp::Reverse);
     log::debug!("Is sorted: {}", is_sorted);
 }
 
+/// Sorts `bins` for the output with `--sort-bins` and `--sort-items`, returning the position
+/// each of them had before.
+fn arrange_bins(args: &Args, bins: &mut Vec<fitter::Bin<u32>>) -> Vec<usize> {
+    let mut arranged: Vec<_> = bins.drain(..).enumerate().collect();
+    match args.sort_bins {
+        Some(BinSort::Fill) => {
+            arranged.sort_by(|(_, a), (_, b)| b.utilization().total_cmp(&a.utilization()))
+        }
+        Some(BinSort::Capacity) => arranged.sort_by_key(|(_, bin)| cmp::Reverse(bin.size)),
+        Some(BinSort::Label) => arranged.sort_by(|(_, a), (_, b)| {
+            (a.label.is_none(), &a.label).cmp(&(b.label.is_none(), &b.label))
+        }),
+        None => {}
+    }
+
+    for (_, bin) in &mut arranged {
+        match args.sort_items {
+            Some(ItemSort::Ascending) => bin.items.sort(),
+            Some(ItemSort::Descending) => bin.items.sort_by(|a, b| b.cmp(a)),
+            None => {}
+        }
+    }
+
+    let (positions, arranged) = arranged.into_iter().unzip();
+    *bins = arranged;
+    positions
+}
+
+/// Writes the `o` line of `--anytime` for a packing into fewer bins than before, with the `v`
+/// lines of the packing too with `--values`.
+fn print_incumbent(
+    args: &Args,
+    input: &[u32],
+    names: &[Option<String>],
+    bins: &[fitter::Bin<u32>],
+) {
+    println!("o {}", bins.iter().filter(|bin| !bin.is_empty()).count());
+    if args.values {
+        let mut bins = bins.to_vec();
+        let positions = arrange_bins(args, &mut bins);
+        let positions = args.empty_bins.then_some(&positions[..]);
+        print_named_solution(input, names, &bins, positions);
+    }
+}
+
+/// Like [`print_solution`], with the items that have a name in `names` written as it, and each
+/// line starting with the position of its bin in `bin_indices` if any.
+fn print_named_solution(
+    input: &[u32],
+    names: &[Option<String>],
+    bins: &[fitter::Bin<u32>],
+    bin_indices: Option<&[usize]>,
+) {
+    if names.iter().all(Option::is_none) && bin_indices.is_none() {
+        return print_solution(bins);
+    }
+
+    for (bin_idx, (bin, positions)) in bins.iter().zip(bin_positions(input, bins)).enumerate() {
+        let line = positions
+            .into_iter()
+            .map(|idx| match &names[idx] {
+                Some(name) => name.clone(),
+                None => input[idx].to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let prefix = match bin_indices {
+            Some(indices) => format!("v {}:", indices[bin_idx]),
+            None => "v".to_string(),
+        };
+
+        match &bin.label {
+            Some(label) => println!("{prefix} #{label} {line}"),
+            None => println!("{prefix} {line}"),
+        }
+    }
+}
+
 fn solve_single_input(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
-    let (bin_capacity, weights) = parse_input(stream)?;
+    let (capacities, items) = parse_multi_instance::<Named<knapsack::Item>>(stream, &args.input)?;
+    solve_items(capacities, items, args)
+}
+
+/// Solves the instance of `items` in bins of `capacities`, in the default mode.
+fn solve_items(
+    capacities: Vec<u32>,
+    items: Vec<Named<knapsack::Item>>,
+    args: &Args,
+) -> anyhow::Result<()> {
+    let (names, mut items): (Vec<_>, Vec<_>) =
+        items.into_iter().map(|item| (item.name, item.item)).unzip();
+    if args.interactive && (args.algorithm != Algorithm::Backtracking || capacities.len() != 1) {
+        anyhow::bail!("--interactive steps through the backtracking search with one bin capacity");
+    }
+
+    let [bin_capacity] = capacities[..] else {
+        return solve_heterogeneous(capacities, items, &names, args);
+    };
+
+    let mut weights: Vec<u32> = items.iter().map(|item| item.weight).collect();
+    let input = weights.clone();
     let solve_start = time::Instant::now();
-    let deadline = args.timeout.map(|timeout| solve_start + timeout.into());
+    let deadline = solve_deadline(args, solve_start);
+
+    let mut fixed_bins = Vec::new();
+    if let Some(path) = &args.warm_start {
+        for fixed_items in parse_fixed_bins(path)? {
+            let mut bin = fitter::Bin::new(bin_capacity);
+            for item in fixed_items {
+                let Some(idx) = weights.iter().position(|&weight| weight == item) else {
+                    anyhow::bail!("fixed item {item} is not part of the instance");
+                };
+
+                weights.swap_remove(idx);
+                items.swap_remove(idx);
+                if !bin.fits(&item) {
+                    anyhow::bail!("fixed items {:?} exceed the bin capacity", bin.items);
+                }
+
+                bin.push(item);
+            }
+
+            fixed_bins.push(bin);
+        }
+    }
+
+    let fixed_weight: u64 = fixed_bins
+        .iter()
+        .flat_map(|bin| &bin.items)
+        .map(|&item| item as u64)
+        .sum();
     let mut solution = SolutionState::Unknown;
-    let mut max_bins = weights.len();
+    // more bins than items would only stay empty
+    let mut max_bins = weights.len() + fixed_bins.len();
+    max_bins = args.bins.map_or(max_bins, |bins| bins.min(max_bins));
+    let mut solver = fitter::Fitter::with_bins(weights.clone(), Vec::new());
+    solver.set_progress(progress_reporter(args));
     'optimize: loop {
         log::info!("Trying to fit in {max_bins} bins");
 
-        let total_weight: u32 = weights.iter().sum();
-        let total_size = bin_capacity * max_bins as u32;
-        if total_weight > total_size {
+        let total_weight = weights.iter().map(|&weight| weight as u64).sum::<u64>() + fixed_weight;
+        let total_size = bin_capacity as u64 * max_bins as u64;
+        if total_weight > total_size || max_bins < fixed_bins.len() {
             solution.insert(SolutionState::Unsolvable);
             break 'optimize;
         }
 
-        let bin_capacities = vec![bin_capacity; max_bins];
-        let mut solver = fitter::Fitter::new(weights.clone(), bin_capacities);
+        let mut bins = fixed_bins.clone();
+        bins.resize(max_bins, fitter::Bin::new(bin_capacity));
 
-        let time_out = if let Some(deadline) = deadline {
-            !solver.solve_until(|| time::Instant::now() < deadline)
+        let (outcome, packed) = if args.algorithm == Algorithm::Backtracking {
+            solver.set_bins(bins);
+            let outcome = match args.interactive {
+                true => cli::interactive::solve(&mut solver, deadline)?,
+                false => solver.solve(budget(deadline)),
+            };
+
+            log::debug!("{:?}", solver.stats());
+            (outcome, solver.packed_bins())
         } else {
-            !solver.solve_until(|| true)
+            let mut engine = engine(args.algorithm, weights.clone(), bins, args.seed);
+            let outcome = engine.solve(budget(deadline));
+            (outcome, packed_bins(engine.bins()))
         };
 
-        if time_out {
+        if outcome == SolveOutcome::Budget {
+            if interrupted() {
+                log::warn!("Interrupted, printing the best packing found so far");
+            }
+
             break 'optimize;
         }
 
-        if solver.is_solved() {
-            let bins = solver
-                .bins
-                .into_iter()
-                .filter(|bin| !bin.is_empty())
-                .collect::<Vec<_>>();
+        if outcome == SolveOutcome::Solved {
+            let bins = packed;
+            let enough = args.min_bins.is_some_and(|min_bins| bins.len() <= min_bins);
+            if args.anytime {
+                print_incumbent(args, &input, &names, &bins);
+            }
 
             max_bins = bins.len().saturating_sub(1);
             solution = SolutionState::Solved(bins);
-            if max_bins > 0 && args.minimize {
+            if max_bins > 0 && args.minimize && !enough {
                 continue 'optimize;
             }
         }
 
-        solution.insert(SolutionState::Unsolvable);
+        // the heuristics giving up doesn't mean there is no packing
+        if args.algorithm.is_exact() {
+            solution.insert(SolutionState::Unsolvable);
+        }
+
         break;
     }
 
-    match solution {
-        SolutionState::Unknown => println!("s UNKNOWN"),
-        SolutionState::Unsolvable => println!("s UNSAT"),
+    if let (SolutionState::Solved(bins), true) = (&mut solution, args.balance) {
+        log::info!("Balancing the loads of {} bins", bins.len());
+        let budget = budget(deadline);
+        let (balanced, _) =
+            capacity::minimize_capacity(&weights, bins.clone(), || budget.remains());
+
+        // restore the original capacities before evening out the rest of the loads
+        *bins = balanced
+            .into_iter()
+            .map(|bin| {
+                let mut restored = fitter::Bin::new(bin_capacity);
+                bin.items.into_iter().for_each(|item| restored.push(item));
+                restored
+            })
+            .collect();
+
+        let moves = fitter::balance::balance(bins);
+        log::debug!("{moves} balancing moves");
+    }
+
+    let mut bin_indices = Vec::new();
+    if let SolutionState::Solved(bins) = &mut solution {
+        if args.empty_bins {
+            let all_bins = args.bins.unwrap_or(0).max(bins.len());
+            bins.resize(all_bins, fitter::Bin::new(bin_capacity));
+        }
+
+        bin_indices = arrange_bins(args, bins);
+    }
+
+    let stats = (args.algorithm == Algorithm::Backtracking).then(|| solver.stats());
+    if print_structured(
+        args,
+        &input,
+        &names,
+        &solution,
+        stats,
+        solve_start.elapsed(),
+    ) {
+        return Ok(());
+    }
+
+    match &solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable if args.partial.is_some() => {
+            print_status("UNSAT");
+
+            // exactly the bins of --bins, the fixed ones among them
+            let mut bins = fixed_bins;
+            let num_bins = args.bins.map_or(weights.len() + bins.len(), |num_bins| {
+                num_bins.max(bins.len())
+            });
+            bins.resize(num_bins, fitter::Bin::new(bin_capacity));
+            pack_partially(&items, &mut bins, deadline, args);
+        }
+        SolutionState::Unsolvable => print_status("UNSAT"),
         SolutionState::Solved(solution) => {
-            println!("s SAT");
+            print_status("SAT");
 
             if args.values {
-                print_solution(&solution);
+                let bin_indices = args.empty_bins.then_some(&bin_indices[..]);
+                print_named_solution(&input, &names, solution, bin_indices);
             }
         }
     };
 
+    if let Some(format) = args.stats {
+        let lower_bound = bounds::MartelloToth.lower_bound(&input, &bin_capacity);
+        let elapsed = solve_start.elapsed();
+        print_stats(format, &solution, stats, elapsed, Some(lower_bound));
+    }
+
+    Ok(())
+}
+
+/// Packs the items into exactly the bins on the first line, each with its own capacity. With
+/// `--minimize` the search then leaves as many of them empty as it can.
+fn solve_heterogeneous(
+    capacities: Vec<u32>,
+    items: Vec<knapsack::Item>,
+    names: &[Option<String>],
+    args: &Args,
+) -> anyhow::Result<()> {
+    if args.warm_start.is_some() || args.balance || args.bins.is_some() {
+        anyhow::bail!("--warm-start, --balance and --bins need a single bin capacity");
+    }
+
+    let weights: Vec<u32> = items.iter().map(|item| item.weight).collect();
+    let bins: Vec<_> = capacities.into_iter().map(fitter::Bin::new).collect();
+    let solve_start = time::Instant::now();
+    let deadline = solve_deadline(args, solve_start);
+
+    let mut engine = engine(args.algorithm, weights.clone(), bins.clone(), args.seed);
+    let mut solution = match engine.solve(budget(deadline)) {
+        SolveOutcome::Solved if args.anytime => {
+            print_incumbent(args, &weights, names, engine.bins());
+            SolutionState::Solved(engine.bins().to_vec())
+        }
+        SolveOutcome::Solved => SolutionState::Solved(engine.bins().to_vec()),
+        SolveOutcome::Exhausted if args.algorithm.is_exact() => SolutionState::Unsolvable,
+        _ => SolutionState::Unknown,
+    };
+
+    let mut stats = None;
+    while let (SolutionState::Solved(best), true) = (&solution, args.minimize) {
+        let max_used = best
+            .iter()
+            .filter(|bin| !bin.is_empty())
+            .count()
+            .saturating_sub(1);
+        if max_used == 0 || args.min_bins.is_some_and(|min_bins| max_used < min_bins) {
+            break;
+        }
+
+        log::info!("Trying to use at most {max_used} bins");
+        let mut solver = fitter::Fitter::with_bins(weights.clone(), bins.clone());
+        solver.set_progress(progress_reporter(args));
+        solver.add_pruner(move |bins: &[fitter::Bin<u32>], _: &[u32], _| {
+            bins.iter().filter(|bin| !bin.is_empty()).count() > max_used
+        });
+
+        let outcome = solver.solve(budget(deadline));
+        stats = Some(*solver.stats());
+        if outcome != SolveOutcome::Solved {
+            break;
+        }
+
+        if args.anytime {
+            print_incumbent(args, &weights, names, &solver.bins);
+        }
+
+        solution = SolutionState::Solved(solver.bins);
+    }
+
+    let mut bin_indices = Vec::new();
+    if let SolutionState::Solved(bins) = &mut solution {
+        bin_indices = arrange_bins(args, bins);
+    }
+
+    if print_structured(
+        args,
+        &weights,
+        names,
+        &solution,
+        stats.as_ref(),
+        solve_start.elapsed(),
+    ) {
+        return Ok(());
+    }
+
+    match &solution {
+        SolutionState::Unknown => print_status("UNKNOWN"),
+        SolutionState::Unsolvable if args.partial.is_some() => {
+            print_status("UNSAT");
+            pack_partially(&items, &mut bins.clone(), deadline, args);
+        }
+        SolutionState::Unsolvable => print_status("UNSAT"),
+        SolutionState::Solved(bins) => {
+            print_status("SAT");
+
+            if args.values {
+                // a line for each bin, the empty ones too
+                let bin_indices = args.empty_bins.then_some(&bin_indices[..]);
+                print_named_solution(&weights, names, bins, bin_indices);
+            }
+        }
+    }
+
+    if let Some(format) = args.stats {
+        // the bins differ, there is no lower bound on how many are needed
+        print_stats(
+            format,
+            &solution,
+            stats.as_ref(),
+            solve_start.elapsed(),
+            None,
+        );
+    }
+
+    Ok(())
+}
+
+/// Packs as many of `items` as possible into the remaining capacity of `bins` and prints the
+/// packing, followed by the items left out.
+fn pack_partially(
+    items: &[knapsack::Item],
+    bins: &mut [fitter::Bin<u32>],
+    deadline: Option<time::Instant>,
+    args: &Args,
+) {
+    let objective = args.partial.unwrap_or(PartialObjective::Count);
+    let candidates = items
+        .iter()
+        .map(|item| match objective {
+            PartialObjective::Count => knapsack::Item::new(item.weight, 1),
+            PartialObjective::Value => *item,
+        })
+        .collect();
+
+    let capacities = bins.iter().map(|bin| bin.capacity).collect();
+    let knapsack = knapsack::Knapsack::with_capacities(candidates, capacities);
+    let budget = budget(deadline);
+    let selection = knapsack.solve_until(|| budget.remains());
+
+    for (bin, selected) in bins.iter_mut().zip(&selection.bins) {
+        selected.iter().for_each(|&idx| bin.push(items[idx].weight));
+    }
+
+    log::info!(
+        "Packed {} of {} items (optimal={})",
+        selection.items.len(),
+        items.len(),
+        selection.optimal
+    );
+
+    match objective {
+        PartialObjective::Count => {
+            println!(
+                "o {}",
+                bins.iter().map(|bin| bin.items.len()).sum::<usize>()
+            )
+        }
+        PartialObjective::Value => println!("o {}", selection.value),
+    }
+
+    if args.values {
+        let bins: Vec<_> = bins.iter().filter(|bin| !bin.is_empty()).cloned().collect();
+        print_solution(&bins);
+    }
+
+    let rejected: Vec<knapsack::Item> = (0..items.len())
+        .filter(|idx| selection.items.binary_search(idx).is_err())
+        .map(|idx| items[idx])
+        .collect();
+
+    match objective {
+        PartialObjective::Count => print_items('u', rejected.iter().map(|item| item.weight)),
+        PartialObjective::Value => print_rejected(&rejected),
+    }
+}
+
+fn print_items(prefix: char, items: impl IntoIterator<Item = impl ToString>) {
+    let line = items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    println!("{prefix} {line}");
+}
+
+/// Prints the rejected items on a `u` line and their total value on an `r` line.
+fn print_rejected(rejected: &[knapsack::Item]) {
+    print_items('u', rejected);
+    println!(
+        "r {}",
+        rejected.iter().map(|item| item.value as u128).sum::<u128>()
+    );
+}
+
+/// Solves the instance at the start of `stream`, or all of them with `--multi-mode`.
+fn solve_stream(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    if args.jobs > 1 {
+        return cli::jobs::solve(stream, args);
+    }
+
+    loop {
+        if !has_instance_left(stream)? {
+            break;
+        }
+
+        solve_instance(stream, args)?;
+        if !args.multi_mode || interrupted() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Solves the instance at the start of `stream` in the mode picked by `args`.
+fn solve_instance(stream: &mut impl BufRead, args: &Args) -> anyhow::Result<()> {
+    let start = time::Instant::now();
+    INSTANCE_DEADLINE.set(args.instance_timeout.map(|timeout| start + timeout.into()));
+    match args.geom2d {
+        Some(method) => cli::geom2d::solve(stream, method, args)?,
+        None if args.bound_only => cli::bounds::print(stream, args)?,
+        None if args.check.is_some() => {
+            cli::check::run(stream, args.check.as_ref().unwrap(), &args.input)?
+        }
+        None if args.split.is_some() => cli::split::solve(stream, args.split.unwrap(), args)?,
+        None if args.repack.is_some() => {
+            cli::repack::solve(stream, args.repack.as_ref().unwrap(), args)?
+        }
+        None if args.groups => cli::constrained::solve_groups(stream, args)?,
+        None if args.labels => cli::labels::solve(stream, args)?,
+        None if args.nested.is_some() => cli::nested::solve(stream, args.nested.unwrap(), args)?,
+        None if args.cover => cli::cover::solve(stream, args)?,
+        None if args.makespan => cli::makespan::solve(stream, args)?,
+        None if args.overflow.is_some() => {
+            cli::overflow::solve(stream, args.overflow.unwrap(), args)?
+        }
+        None if args.min_capacity.is_some() => {
+            cli::min_capacity::solve(stream, args.min_capacity.unwrap(), args)?
+        }
+        None if args.max_classes.is_some() => {
+            cli::constrained::solve_classes(stream, args.max_classes.unwrap(), args)?
+        }
+        None if args.min_fill.is_some() => {
+            cli::constrained::solve_min_fill(stream, args.min_fill.unwrap(), args)?
+        }
+        None if args.fragile => cli::constrained::solve_fragile(stream, args)?,
+        None if args.temporal => cli::constrained::solve_temporal(stream, args)?,
+        None if args.max_per_color.is_some() || args.alternate_colors => {
+            cli::constrained::solve_colors(stream, args)?
+        }
+        None if args.float => cli::weights::solve::<Float>(stream, args)?,
+        None if args.rational => cli::weights::solve::<Rational>(stream, args)?,
+        None if args.durations => cli::weights::solve::<Span>(stream, args)?,
+        None if args.sizes => cli::weights::solve::<Size>(stream, args)?,
+        #[cfg(feature = "bigint")]
+        None if args.bigint => cli::weights::solve::<fitter::bigint::BigUint>(stream, args)?,
+        None if args.decimal => cli::decimal::solve(stream, args)?,
+        None if args.count.is_some() => cli::count::solve(stream, args.count.unwrap(), args)?,
+        None if args.priorities => cli::priorities::solve(stream, args)?,
+        None if args.knapsack => cli::knapsack::solve(stream, args)?,
+        None => solve_single_input(stream, args)?,
+    }
+
     Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+    let mut argv: Vec<ffi::OsString> = env::args_os().collect();
+    if let Some(path) = config::path(&argv) {
+        argv.splice(1..1, config::args(&path)?);
+    }
+
+    let args = Args::parse_from(argv);
 
     let mut builder = env_logger::Builder::from_default_env();
     builder.filter_level(args.verbose.log_level_filter());
@@ -210,23 +1879,102 @@ fn main() -> anyhow::Result<()> {
 
     // Initialize the logger
     builder.init();
+    handle_interrupts();
+    if let Some(limit) = args.memory_limit {
+        limit_memory(limit);
+    }
 
-    let mut stream: Box<dyn BufRead> = if let Some(path) = &args.input_file {
-        Box::new(io::BufReader::new(fs::File::open(path)?))
-    } else {
-        Box::new(io::stdin().lock())
-    };
+    match &args.command {
+        &Some(Command::Generate { class, n }) => {
+            print!("{}", generator::generate(class.with_count(n), args.seed));
+            return Ok(());
+        }
+        Some(Command::Stats { file }) => {
+            let mut stream: Box<dyn BufRead> = match file {
+                Some(path) => Box::new(io::BufReader::new(fs::File::open(path)?)),
+                None => Box::new(io::stdin().lock()),
+            };
 
-    loop {
-        if !stream.has_data_left()? {
-            break;
+            while has_instance_left(&mut stream)? {
+                cli::stats::print(&mut stream, &args)?;
+            }
+
+            return Ok(());
+        }
+        Some(Command::Shrink { file, interesting }) => {
+            let mut stream: Box<dyn BufRead> = match file {
+                Some(path) => Box::new(io::BufReader::new(fs::File::open(path)?)),
+                None => Box::new(io::stdin().lock()),
+            };
+
+            let (capacity, items) = parse_instance::<u32>(&mut stream, &args.input)?;
+            let timeout = args.timeout.map(Into::into);
+            let instance = cli::shrink::run(batch::Instance { capacity, items }, |instance| {
+                cli::shrink::is_interesting(interesting, timeout, instance)
+            })?;
+
+            let items: Vec<String> = instance.items.iter().map(u32::to_string).collect();
+            println!("{}\n{}\n0", instance.capacity, items.join(" "));
+            return Ok(());
         }
+        Some(Command::Crosscheck { file }) => {
+            let mut stream: Box<dyn BufRead> = match file {
+                Some(path) => Box::new(io::BufReader::new(fs::File::open(path)?)),
+                None => Box::new(io::stdin().lock()),
+            };
 
-        solve_single_input(&mut stream, &args)?;
+            let mut inconsistent = 0;
+            while has_instance_left(&mut stream)? && !interrupted() {
+                if !cli::crosscheck::run(&mut stream, &args)? {
+                    inconsistent += 1;
+                }
+            }
+
+            if inconsistent > 0 {
+                anyhow::bail!("the engines disagree on {inconsistent} instances");
+            }
 
-        if !args.multi_mode {
+            return Ok(());
+        }
+        &Some(Command::Completions { shell }) => {
+            completions::print(shell, <Args as clap::CommandFactory>::command());
+            return Ok(());
+        }
+        Some(Command::Validate { file }) => {
+            return cli::validate::run(file.as_deref(), &args.input)
+        }
+        Some(Command::Bench { dir, timeout, jobs }) => {
+            return cli::bench::run(dir, (*timeout).into(), *jobs)
+        }
+        None => {}
+    }
+
+    if args.files.is_empty() {
+        let mut stream: Box<dyn BufRead> = if let Some(path) = &args.input_file {
+            Box::new(io::BufReader::new(fs::File::open(path)?))
+        } else {
+            Box::new(io::stdin().lock())
+        };
+
+        solve_stream(&mut stream, &args)?;
+    }
+
+    for path in &args.files {
+        if interrupted() {
             break;
         }
+
+        // the other formats are parsed by tools that wouldn't expect comments
+        if args.format == Format::Text {
+            println!("c file {}", path.display());
+        }
+
+        solve_stream(&mut io::BufReader::new(fs::File::open(path)?), &args)?;
+    }
+
+    if args.exit_codes {
+        io::stdout().flush()?;
+        process::exit(exit_code());
     }
 
     anyhow::Ok(())
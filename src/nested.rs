@@ -0,0 +1,280 @@
+use std::fmt;
+
+use crate::{
+    constrained::{ConstrainedPacker, Constraint},
+    Fitter, SolutionState,
+};
+
+/// Items are packed into boxes of `box_capacity`, the boxes are stacked on pallets that carry
+/// up to `pallet_capacity` and, optionally, up to `boxes_per_pallet` boxes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NestedInstance {
+    pub items: Vec<u32>,
+    pub box_capacity: u32,
+    pub pallet_capacity: u32,
+    pub boxes_per_pallet: Option<usize>,
+}
+
+/// The weights in each box on each pallet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NestedSolution {
+    pub pallets: Vec<Vec<Vec<u32>>>,
+}
+
+impl NestedSolution {
+    pub fn num_boxes(&self) -> usize {
+        self.pallets.iter().map(Vec::len).sum()
+    }
+}
+
+/// One `v` line per pallet, with the items of each box in brackets.
+impl fmt::Display for NestedSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pallet in &self.pallets {
+            write!(f, "v")?;
+            for items in pallet {
+                let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+                write!(f, " [{}]", items.join(" "))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NestedInstance {
+    /// Minimizes the number of boxes first, then packs the boxes on as few pallets as possible.
+    pub fn solve_pipeline(
+        &self,
+        mut predicate: impl FnMut() -> bool,
+    ) -> SolutionState<NestedSolution> {
+        let boxes = match minimize_bins(&self.items, self.box_capacity, &mut predicate) {
+            SolutionState::Solved(boxes) => boxes,
+            SolutionState::Unsolvable => return SolutionState::Unsolvable,
+            SolutionState::Unknown => return SolutionState::Unknown,
+        };
+
+        // added up in u64, a box holds at most its capacity so the load fits back in a u32
+        let loads: Vec<u32> = boxes
+            .iter()
+            .map(|items| {
+                let load: u64 = items.iter().map(|&item| item as u64).sum();
+                u32::try_from(load).expect("a box holds at most its capacity")
+            })
+            .collect();
+        let constraint = BoxLimit(self.boxes_per_pallet);
+        let pallets =
+            minimize_constrained(&loads, self.pallet_capacity, &constraint, &mut predicate);
+
+        match pallets {
+            SolutionState::Solved(pallets) => {
+                let pallets = pallets
+                    .into_iter()
+                    .map(|pallet| pallet.into_iter().map(|idx| boxes[idx].clone()).collect())
+                    .collect();
+
+                SolutionState::Solved(NestedSolution { pallets })
+            }
+            SolutionState::Unsolvable => SolutionState::Unsolvable,
+            SolutionState::Unknown => SolutionState::Unknown,
+        }
+    }
+
+    /// Minimizes the number of pallets, with the items on each pallet fitting in its boxes,
+    /// then the number of boxes on each pallet.
+    pub fn solve_joint(
+        &self,
+        mut predicate: impl FnMut() -> bool,
+    ) -> SolutionState<NestedSolution> {
+        let constraint = Boxable { instance: self };
+
+        let pallets = minimize_constrained(
+            &self.items,
+            self.pallet_capacity,
+            &constraint,
+            &mut predicate,
+        );
+        let pallets = match pallets {
+            SolutionState::Solved(pallets) => pallets,
+            SolutionState::Unsolvable => return SolutionState::Unsolvable,
+            SolutionState::Unknown => return SolutionState::Unknown,
+        };
+
+        let mut solution = NestedSolution::default();
+        for pallet in pallets {
+            let weights: Vec<u32> = pallet.iter().map(|&idx| self.items[idx]).collect();
+            match minimize_bins(&weights, self.box_capacity, &mut predicate) {
+                SolutionState::Solved(boxes) => solution.pallets.push(boxes),
+                SolutionState::Unsolvable => return SolutionState::Unsolvable,
+                SolutionState::Unknown => return SolutionState::Unknown,
+            }
+        }
+
+        SolutionState::Solved(solution)
+    }
+}
+
+/// Limits the number of boxes on a pallet.
+struct BoxLimit(Option<usize>);
+
+impl Constraint for BoxLimit {
+    fn allows(&self, bin: &[usize], _item: usize) -> bool {
+        self.0.is_none_or(|limit| bin.len() < limit)
+    }
+}
+
+/// The items on a pallet have to fit in the boxes it can carry.
+struct Boxable<'a> {
+    instance: &'a NestedInstance,
+}
+
+impl Constraint for Boxable<'_> {
+    fn allows(&self, bin: &[usize], item: usize) -> bool {
+        let instance = self.instance;
+        if instance.items[item] > instance.box_capacity {
+            return false;
+        }
+
+        let Some(limit) = instance.boxes_per_pallet else {
+            return true;
+        };
+
+        let weights: Vec<u32> = bin
+            .iter()
+            .chain([&item])
+            .map(|&idx| instance.items[idx])
+            .collect();
+        let mut solver = Fitter::new(weights, vec![instance.box_capacity; limit]);
+        solver.solve_until(|| true);
+        solver.is_solved()
+    }
+}
+
+/// Minimizes the number of bins, starting with one bin per item.
+fn minimize_bins(
+    weights: &[u32],
+    capacity: u32,
+    predicate: &mut impl FnMut() -> bool,
+) -> SolutionState<Vec<Vec<u32>>> {
+    let mut solution = SolutionState::Unknown;
+    let mut max_bins = weights.len();
+    loop {
+        let mut solver = Fitter::new(weights.to_vec(), vec![capacity; max_bins]);
+        if !solver.solve_until(&mut *predicate) {
+            break;
+        }
+
+        if !solver.is_solved() {
+            solution.insert(SolutionState::Unsolvable);
+            break;
+        }
+
+        let bins: Vec<Vec<u32>> = solver
+            .bins
+            .into_iter()
+            .filter(|bin| !bin.is_empty())
+            .map(|bin| bin.items)
+            .collect();
+
+        max_bins = bins.len().saturating_sub(1);
+        solution = SolutionState::Solved(bins);
+        if max_bins == 0 {
+            break;
+        }
+    }
+
+    solution
+}
+
+/// [`minimize_bins`] over a [`ConstrainedPacker`], returning the item indices in each bin.
+fn minimize_constrained(
+    weights: &[u32],
+    capacity: u32,
+    constraint: &impl Constraint,
+    predicate: &mut impl FnMut() -> bool,
+) -> SolutionState<Vec<Vec<usize>>> {
+    let mut solution = SolutionState::Unknown;
+    let mut max_bins = weights.len();
+    loop {
+        let packer = ConstrainedPacker::new(weights.to_vec(), vec![capacity; max_bins], constraint);
+        match packer.solve_until(&mut *predicate) {
+            SolutionState::Solved(bins) => {
+                let bins: Vec<_> = bins.into_iter().filter(|bin| !bin.is_empty()).collect();
+                max_bins = bins.len().saturating_sub(1);
+                solution = SolutionState::Solved(bins);
+                if max_bins == 0 {
+                    break;
+                }
+            }
+            SolutionState::Unsolvable => {
+                solution.insert(SolutionState::Unsolvable);
+                break;
+            }
+            SolutionState::Unknown => break,
+        }
+    }
+
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(items: &[u32], box_capacity: u32, pallet_capacity: u32) -> NestedInstance {
+        NestedInstance {
+            items: items.to_vec(),
+            box_capacity,
+            pallet_capacity,
+            boxes_per_pallet: None,
+        }
+    }
+
+    #[test]
+    fn packs_boxes_on_pallets() {
+        let instance = instance(&[6, 4, 5, 5, 3], 10, 20);
+        let SolutionState::Solved(solution) = instance.solve_pipeline(|| true) else {
+            panic!("the items fit in three boxes on two pallets");
+        };
+        assert_eq!(solution.num_boxes(), 3);
+        assert_eq!(solution.pallets.len(), 2);
+
+        let SolutionState::Solved(solution) = instance.solve_joint(|| true) else {
+            panic!("the items fit on two pallets");
+        };
+        assert_eq!(solution.pallets.len(), 2);
+        assert_eq!(instance.solve_pipeline(|| false), SolutionState::Unknown);
+    }
+
+    #[test]
+    fn limits_the_boxes_per_pallet() {
+        let mut instance = instance(&[6, 6, 6], 10, 30);
+        instance.boxes_per_pallet = Some(2);
+        let SolutionState::Solved(solution) = instance.solve_pipeline(|| true) else {
+            panic!("three boxes fit on two pallets");
+        };
+        assert_eq!(solution.pallets.len(), 2);
+
+        let instance = NestedInstance {
+            items: vec![11],
+            ..instance
+        };
+        assert_eq!(instance.solve_pipeline(|| true), SolutionState::Unsolvable);
+    }
+
+    #[test]
+    fn box_loads_near_the_largest_weight_do_not_overflow() {
+        let instance = instance(
+            &[3_000_000_000, 1_000_000_000, 294_967_295],
+            u32::MAX,
+            u32::MAX,
+        );
+        let SolutionState::Solved(solution) = instance.solve_pipeline(|| true) else {
+            panic!("the items fill a box to the brim");
+        };
+        assert_eq!(solution.num_boxes(), 1);
+        assert_eq!(solution.pallets.len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    bounds::{self, Trivial},
+    repack::Repacker,
+    Fitter, SolutionState,
+};
+
+/// One criterion of a lexicographic optimization, see [`optimize`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Objective {
+    /// The number of bins.
+    Bins,
+    /// The load of the fullest bin.
+    MaxLoad,
+    /// The number of items that leave their bin in a reference packing.
+    Moves(Vec<Vec<u32>>),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lexicographic {
+    /// The weights in each bin. After a [`Objective::Moves`] the bins of the reference come
+    /// first in their order.
+    pub bins: Vec<Vec<u32>>,
+    /// The value reached for each objective in the order they were given, cut short at the
+    /// objective the search was stopped in.
+    pub values: Vec<u64>,
+    /// Whether the search finished, proving every value minimal given the ones before it.
+    pub optimal: bool,
+}
+
+enum Probe {
+    Fits(Vec<Vec<u32>>),
+    Infeasible,
+    Stopped,
+}
+
+/// Packs `weights` into bins of `capacity`, minimizing `objectives` one after the other: each
+/// objective is minimized while keeping the ones before it at their minimum.
+///
+/// The number of bins always comes first, since the load and the moves are only comparable
+/// between packings in the same number of bins, so [`Objective::Bins`] just reports it. Stops
+/// early when `predicate` returns false.
+///
+/// # Panics
+///
+/// Panics if there is more than one [`Objective::Moves`], or its reference packing has more
+/// bins than needed.
+pub fn optimize(
+    weights: &[u32],
+    capacity: u32,
+    objectives: &[Objective],
+    mut predicate: impl FnMut() -> bool,
+) -> SolutionState<Lexicographic> {
+    if weights.iter().any(|&weight| weight > capacity) {
+        return SolutionState::Unsolvable;
+    }
+
+    let Some(mut bins) = fewest_bins(weights, capacity, &mut predicate) else {
+        return SolutionState::Unknown;
+    };
+
+    let num_bins = bins.len();
+    let mut max_load = capacity;
+    let mut reference: Option<(&[Vec<u32>], usize)> = None;
+    let mut values = Vec::with_capacity(objectives.len());
+    let mut optimal = true;
+    for objective in objectives {
+        match objective {
+            Objective::Bins => values.push(num_bins as u64),
+            Objective::MaxLoad => {
+                let total_weight: u64 = weights.iter().map(|&weight| weight as u64).sum();
+                let max_weight = weights.iter().copied().max().unwrap_or(0);
+                let mut lower =
+                    max_weight.max(total_weight.div_ceil(num_bins.max(1) as u64) as u32);
+                max_load = load_of_fullest(&bins);
+
+                while lower < max_load {
+                    let limit = lower + (max_load - lower) / 2;
+                    log::info!("Trying to fit in {num_bins} bins of capacity {limit}");
+
+                    match probe(weights, num_bins, limit, reference, &mut predicate) {
+                        Probe::Fits(fitted) => {
+                            max_load = load_of_fullest(&fitted);
+                            bins = fitted;
+                        }
+                        Probe::Infeasible => lower = limit + 1,
+                        Probe::Stopped => {
+                            optimal = false;
+                            break;
+                        }
+                    }
+                }
+
+                if !optimal {
+                    break;
+                }
+
+                values.push(max_load as u64);
+            }
+            Objective::Moves(current) => {
+                assert!(reference.is_none(), "only one moves objective is supported");
+                assert!(
+                    current.len() <= num_bins,
+                    "the reference packing has more bins than needed"
+                );
+
+                let repacker = Repacker::new(max_load, current.clone(), weights.to_vec());
+                match repacker.solve_until(num_bins, &mut predicate) {
+                    SolutionState::Solved(repacking) if repacking.optimal => {
+                        bins = repacking.bins;
+                        reference = Some((current, repacking.moves));
+                        values.push(repacking.moves as u64);
+                    }
+                    SolutionState::Solved(repacking) => {
+                        bins = repacking.bins;
+                        optimal = false;
+                        break;
+                    }
+                    // the packing so far rules out the other outcomes, so the search stopped
+                    _ => {
+                        optimal = false;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    SolutionState::Solved(Lexicographic {
+        bins,
+        values,
+        optimal,
+    })
+}
+
+fn load_of_fullest(bins: &[Vec<u32>]) -> u32 {
+    bins.iter().map(|bin| bin.iter().sum()).max().unwrap_or(0)
+}
+
+/// The packing in the fewest bins, trying one more bin at a time from the lower bound up.
+fn fewest_bins(
+    weights: &[u32],
+    capacity: u32,
+    mut predicate: impl FnMut() -> bool,
+) -> Option<Vec<Vec<u32>>> {
+    let lower_bound = bounds::lower_bound::<_, Trivial>(weights, &capacity, &[]);
+    for num_bins in lower_bound..=weights.len() {
+        log::info!("Trying to fit in {num_bins} bins");
+
+        let mut solver = Fitter::new(weights.to_vec(), vec![capacity; num_bins]);
+        if !solver.solve_until(&mut predicate) {
+            return None;
+        }
+
+        if solver.is_solved() {
+            return Some(solver.bins.into_iter().map(|bin| bin.items).collect());
+        }
+    }
+
+    None
+}
+
+/// Looks for a packing in `num_bins` bins of `capacity`, with at most as many moves from the
+/// reference packing as allowed, if there is one.
+fn probe(
+    weights: &[u32],
+    num_bins: usize,
+    capacity: u32,
+    reference: Option<(&[Vec<u32>], usize)>,
+    mut predicate: impl FnMut() -> bool,
+) -> Probe {
+    let Some((current, max_moves)) = reference else {
+        let mut solver = Fitter::new(weights.to_vec(), vec![capacity; num_bins]);
+        return match solver.solve_until(&mut predicate) {
+            false => Probe::Stopped,
+            true if solver.is_solved() => {
+                Probe::Fits(solver.bins.into_iter().map(|bin| bin.items).collect())
+            }
+            true => Probe::Infeasible,
+        };
+    };
+
+    let repacker = Repacker::new(capacity, current.to_vec(), weights.to_vec());
+    match repacker.solve_until(num_bins, &mut predicate) {
+        SolutionState::Solved(repacking) if repacking.moves <= max_moves => {
+            Probe::Fits(repacking.bins)
+        }
+        SolutionState::Solved(repacking) if repacking.optimal => Probe::Infeasible,
+        SolutionState::Unsolvable => Probe::Infeasible,
+        _ => Probe::Stopped,
+    }
+}
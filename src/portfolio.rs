@@ -0,0 +1,109 @@
+use std::{sync::mpsc, thread, time};
+
+use crate::{
+    solver::{Budget, CancellationToken, SolveOutcome, Solver},
+    Bin,
+};
+
+/// Builds an engine for the items and bins of a [`Portfolio`].
+pub type Engine<T> = Box<dyn Fn(Vec<T>, Vec<Bin<T>>) -> Box<dyn Solver<T>> + Send + Sync>;
+
+/// Runs several engines at once on the same items and bins, each on its own thread, and stops
+/// them all as soon as one packs every item, or an exact one proves there is no packing.
+pub struct Portfolio<T> {
+    pub items: Vec<T>,
+    pub bins: Vec<Bin<T>>,
+    /// Each engine with whether it's exact, only exact engines are trusted to have run out of
+    /// options.
+    engines: Vec<(Engine<T>, bool)>,
+}
+
+impl<T> Portfolio<T> {
+    pub fn new(items: Vec<T>, bins: Vec<Bin<T>>) -> Self {
+        Self {
+            items,
+            bins,
+            engines: Vec::new(),
+        }
+    }
+
+    /// Adds an engine that is `exact` when it only reports [`SolveOutcome::Exhausted`] for
+    /// instances that can't be packed.
+    pub fn add<E>(&mut self, engine: E, exact: bool)
+    where
+        E: Fn(Vec<T>, Vec<Bin<T>>) -> Box<dyn Solver<T>> + Send + Sync + 'static,
+    {
+        self.engines.push((Box::new(engine), exact));
+    }
+}
+
+impl<T: Clone + Send + Sync> Solver<T> for Portfolio<T> {
+    fn solve(&mut self, budget: Budget) -> SolveOutcome {
+        let token = CancellationToken::new();
+        let shared = budget.clone().with_cancellation(token.clone());
+
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for (idx, (engine, _)) in self.engines.iter().enumerate() {
+                let (items, bins) = (self.items.clone(), self.bins.clone());
+                let (sender, budget) = (sender.clone(), shared.clone());
+                scope.spawn(move || {
+                    let mut solver = engine(items, bins);
+                    let outcome = solver.solve(budget);
+                    let bins = (outcome == SolveOutcome::Solved).then(|| solver.bins().to_vec());
+                    // the receiver only hangs up once every engine is done
+                    let _ = sender.send((idx, outcome, bins));
+                });
+            }
+
+            drop(sender);
+            let mut result = SolveOutcome::Exhausted;
+            let mut running = self.engines.len();
+            while running > 0 {
+                let (idx, outcome, bins) = match receiver.recv_timeout(POLL_INTERVAL) {
+                    Ok(finished) => finished,
+                    Err(mpsc::RecvTimeoutError::Timeout) if budget.remains() => continue,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        token.cancel();
+                        result = SolveOutcome::Budget;
+                        break;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                };
+
+                running -= 1;
+                match outcome {
+                    SolveOutcome::Solved => {
+                        self.bins = bins.expect("solved engines send their bins");
+                        self.items.clear();
+                        result = SolveOutcome::Solved;
+                        token.cancel();
+                    }
+                    SolveOutcome::Exhausted if self.engines[idx].1 => {
+                        result = SolveOutcome::Exhausted;
+                        token.cancel();
+                    }
+                    SolveOutcome::Exhausted => {}
+                    SolveOutcome::Budget if !token.is_cancelled() => {
+                        result = SolveOutcome::Budget;
+                    }
+                    SolveOutcome::Budget => {}
+                }
+
+                if token.is_cancelled() {
+                    // the others stop at their next budget check, their outcomes don't matter
+                    break;
+                }
+            }
+
+            result
+        })
+    }
+
+    fn bins(&self) -> &[Bin<T>] {
+        &self.bins
+    }
+}
+
+/// How often the outer budget is checked while the engines run.
+const POLL_INTERVAL: time::Duration = time::Duration::from_millis(10);
@@ -0,0 +1,110 @@
+use crate::{
+    knapsack::{Item, Knapsack},
+    SolutionState,
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TieredPacking {
+    /// Indices of the items assigned to each bin.
+    pub bins: Vec<Vec<usize>>,
+    /// Indices of the items left out.
+    pub rejected: Vec<usize>,
+    /// The number of packed items of each priority, from the most important one.
+    pub packed: Vec<(u32, usize)>,
+    /// Whether the search finished, proving the packing is optimal.
+    pub optimal: bool,
+}
+
+/// Packs items by priority: all items of priority 1 have to be packed, then as many of
+/// priority 2 as possible, then of priority 3 and so on.
+pub struct TieredPacker {
+    pub weights: Vec<u32>,
+    pub priorities: Vec<u32>,
+    pub capacities: Vec<u32>,
+}
+
+impl TieredPacker {
+    pub fn new(weights: Vec<u32>, priorities: Vec<u32>, capacities: Vec<u32>) -> Self {
+        Self {
+            weights,
+            priorities,
+            capacities,
+        }
+    }
+
+    /// Values every item of a priority above all the items of lower priorities together, so
+    /// maximizing the value packs the tiers in order. Returns `None` if the values overflow.
+    pub fn values(&self) -> Option<Vec<u64>> {
+        let mut tiers = self.priorities.clone();
+        tiers.sort_unstable();
+        tiers.dedup();
+
+        let mut values = vec![0; self.weights.len()];
+        let mut lower_total: u64 = 0;
+        for &tier in tiers.iter().rev() {
+            let value = lower_total.checked_add(1)?;
+            for (idx, _) in self
+                .priorities
+                .iter()
+                .enumerate()
+                .filter(|(_, &p)| p == tier)
+            {
+                values[idx] = value;
+                lower_total = lower_total.checked_add(value)?;
+            }
+        }
+
+        Some(values)
+    }
+
+    /// Stops early when `predicate` returns false. The instance is unsolvable when the items of
+    /// priority 1 don't fit, and unknown when the values of the tiers overflow.
+    pub fn solve_until(&self, predicate: impl FnMut() -> bool) -> SolutionState<TieredPacking> {
+        let Some(values) = self.values() else {
+            return SolutionState::Unknown;
+        };
+
+        let items = self
+            .weights
+            .iter()
+            .zip(values)
+            .map(|(&weight, value)| Item::new(weight, value))
+            .collect();
+
+        let knapsack = Knapsack::with_capacities(items, self.capacities.clone());
+        let selection = knapsack.solve_until(predicate);
+
+        let rejected: Vec<usize> = (0..self.weights.len())
+            .filter(|idx| selection.items.binary_search(idx).is_err())
+            .collect();
+
+        if rejected.iter().any(|&idx| self.priorities[idx] <= 1) {
+            return if selection.optimal {
+                SolutionState::Unsolvable
+            } else {
+                SolutionState::Unknown
+            };
+        }
+
+        let mut packed: Vec<(u32, usize)> = Vec::new();
+        let mut tiers = self.priorities.clone();
+        tiers.sort_unstable();
+        tiers.dedup();
+        for tier in tiers {
+            let count = selection
+                .items
+                .iter()
+                .filter(|&&idx| self.priorities[idx] == tier)
+                .count();
+
+            packed.push((tier, count));
+        }
+
+        SolutionState::Solved(TieredPacking {
+            bins: selection.bins,
+            rejected,
+            packed,
+            optimal: selection.optimal,
+        })
+    }
+}
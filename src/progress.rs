@@ -0,0 +1,181 @@
+use alloc::boxed::Box;
+use core::time;
+
+use crate::solver::SolveStats;
+
+/// Receives the progress of [`Fitter::solve_until`](crate::Fitter::solve_until), see
+/// [`Fitter::set_progress`](crate::Fitter::set_progress). Does nothing by default.
+pub trait ProgressReporter {
+    /// The fewest items left unpacked so far in this solve call, out of the `total` unpacked
+    /// when it started. Called when that improves, and every so often in between.
+    fn progress(&mut self, _unpacked: usize, _total: usize) {}
+
+    /// The search statistics so far, called right before each [`ProgressReporter::progress`].
+    fn stats(&mut self, _stats: &SolveStats) {}
+
+    /// The solve call returned after `iterations` steps.
+    fn finish(&mut self, _iterations: u32, _elapsed: time::Duration) {}
+}
+
+impl<P: ProgressReporter + ?Sized> ProgressReporter for Box<P> {
+    fn progress(&mut self, unpacked: usize, total: usize) {
+        (**self).progress(unpacked, total)
+    }
+
+    fn stats(&mut self, stats: &SolveStats) {
+        (**self).stats(stats)
+    }
+
+    fn finish(&mut self, iterations: u32, elapsed: time::Duration) {
+        (**self).finish(iterations, elapsed)
+    }
+}
+
+/// A guess at the fraction of the search that is done, from how many of the `total` items are
+/// still `unpacked`: the search slows down as it packs more of them.
+#[cfg(feature = "std")]
+fn solved_amount(unpacked: usize, total: usize) -> f64 {
+    let m = unpacked as f64;
+    let l = total as f64;
+    1. - (m * (l - 1.) / l + 1.).log(l)
+}
+
+/// Reports nothing.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {}
+
+/// Logs an estimate of how much is solved, at most once per `interval`.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct LogProgress {
+    pub interval: time::Duration,
+    next_print_time: Option<std::time::Instant>,
+    prev_print_amount: f64,
+}
+
+#[cfg(feature = "std")]
+impl LogProgress {
+    pub fn new(interval: time::Duration) -> Self {
+        Self {
+            interval,
+            next_print_time: None,
+            prev_print_amount: 0.,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for LogProgress {
+    fn default() -> Self {
+        Self::new(time::Duration::from_millis(200))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ProgressReporter for LogProgress {
+    fn progress(&mut self, unpacked: usize, total: usize) {
+        let now = std::time::Instant::now();
+        let next_print_time = *self.next_print_time.get_or_insert(now + self.interval);
+
+        let solved_amount = solved_amount(unpacked, total);
+
+        if now > next_print_time && self.prev_print_amount != solved_amount {
+            self.next_print_time = Some(now + self.interval);
+            self.prev_print_amount = solved_amount;
+            log::info!("{:.2}% solved (items={unpacked})", solved_amount * 100.);
+        }
+    }
+
+    fn finish(&mut self, iterations: u32, elapsed: time::Duration) {
+        self.next_print_time = None;
+        self.prev_print_amount = 0.;
+        if iterations > 0 {
+            let time_per_iteration = elapsed / iterations;
+            log::debug!(
+                "{iterations} iterations in {elapsed:?} ({time_per_iteration:?} per iteration)"
+            );
+        } else {
+            log::warn!("No iterations");
+        }
+    }
+}
+
+/// Draws a bar on stderr with the items packed, the nodes searched per second, the time taken
+/// and a rough estimate of the time left, redrawn at most once per `interval`. Meant for
+/// terminals, it redraws in place.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct BarProgress {
+    pub interval: time::Duration,
+    /// When the solve started, and the nodes searched by then.
+    start: Option<(std::time::Instant, u64)>,
+    next_draw_time: Option<std::time::Instant>,
+    nodes: u64,
+}
+
+#[cfg(feature = "std")]
+impl BarProgress {
+    const WIDTH: usize = 30;
+
+    pub fn new(interval: time::Duration) -> Self {
+        Self {
+            interval,
+            start: None,
+            next_draw_time: None,
+            nodes: 0,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for BarProgress {
+    fn default() -> Self {
+        Self::new(time::Duration::from_millis(100))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ProgressReporter for BarProgress {
+    fn stats(&mut self, stats: &SolveStats) {
+        self.nodes = stats.nodes;
+    }
+
+    fn progress(&mut self, unpacked: usize, total: usize) {
+        let now = std::time::Instant::now();
+        let (start, start_nodes) = *self.start.get_or_insert((now, self.nodes));
+        if self.next_draw_time.is_some_and(|next| now < next) {
+            return;
+        }
+
+        self.next_draw_time = Some(now + self.interval);
+        let elapsed = now - start;
+        let packed = total - unpacked;
+        let filled = (packed * Self::WIDTH)
+            .checked_div(total)
+            .unwrap_or(Self::WIDTH);
+        let rate = (self.nodes - start_nodes) as f64 / elapsed.as_secs_f64().max(1e-3);
+
+        // the estimate only means something once some of the search is done
+        let solved = solved_amount(unpacked, total);
+        let left = match solved > 0.01 {
+            true => format!("~{:.0?} left", elapsed.mul_f64((1. - solved) / solved)),
+            false => "?".to_string(),
+        };
+
+        eprint!(
+            "\r\x1b[2K[{}{}] {packed}/{total} items  {rate:.0} nodes/s  {elapsed:.1?}  {left}",
+            "#".repeat(filled),
+            " ".repeat(Self::WIDTH - filled),
+        );
+    }
+
+    fn finish(&mut self, _iterations: u32, _elapsed: time::Duration) {
+        if self.start.take().is_some() {
+            eprint!("\r\x1b[2K");
+        }
+
+        self.next_draw_time = None;
+    }
+}
@@ -0,0 +1,21 @@
+use crate::Bin;
+
+/// Cuts branches of a [`Fitter`](crate::Fitter) search, for constraints the search doesn't
+/// know about, see [`Fitter::add_pruner`](crate::Fitter::add_pruner).
+///
+/// With a pruner the search no longer skips bins that only differ from ones already tried by
+/// their contents, so pruners may tell any two bins apart.
+pub trait Pruner<T> {
+    /// Whether to give up on the branch where the last item of `bins[bin]` was just put there,
+    /// `items` are still unpacked.
+    fn prune(&mut self, bins: &[Bin<T>], items: &[T], bin: usize) -> bool;
+}
+
+impl<T, F> Pruner<T> for F
+where
+    F: FnMut(&[Bin<T>], &[T], usize) -> bool,
+{
+    fn prune(&mut self, bins: &[Bin<T>], items: &[T], bin: usize) -> bool {
+        self(bins, items, bin)
+    }
+}
@@ -0,0 +1,213 @@
+use std::{cmp, fmt, hash, iter, num, ops, str};
+
+/// An exact non-negative fraction, so capacities like `2/3` can be packed without scaling.
+///
+/// Fractions are kept in lowest terms. The arithmetic panics if a reduced numerator or
+/// denominator no longer fits in a `u64`.
+#[derive(Copy, Clone, Debug)]
+pub struct Rational {
+    numer: u64,
+    denom: u64,
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+impl Rational {
+    /// Panics if `denom` is zero.
+    pub fn new(numer: u64, denom: u64) -> Self {
+        assert!(denom != 0, "zero denominator");
+        Self::reduced(numer as u128, denom as u128)
+    }
+
+    fn reduced(numer: u128, denom: u128) -> Self {
+        let divisor = gcd(numer, denom);
+        let (numer, denom) = (numer / divisor, denom / divisor);
+        Self {
+            numer: numer.try_into().expect("rational weight overflow"),
+            denom: denom.try_into().expect("rational weight overflow"),
+        }
+    }
+
+    pub fn numer(&self) -> u64 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> u64 {
+        self.denom
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::from(0)
+    }
+}
+
+impl From<u64> for Rational {
+    fn from(value: u64) -> Self {
+        Self {
+            numer: value,
+            denom: 1,
+        }
+    }
+}
+
+impl PartialEq for Rational {
+    fn eq(&self, other: &Self) -> bool {
+        // lowest terms are unique
+        self.numer == other.numer && self.denom == other.denom
+    }
+}
+
+impl Eq for Rational {}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        let lhs = self.numer as u128 * other.denom as u128;
+        let rhs = other.numer as u128 * self.denom as u128;
+        lhs.cmp(&rhs)
+    }
+}
+
+impl hash::Hash for Rational {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.numer.hash(state);
+        self.denom.hash(state);
+    }
+}
+
+impl<'a> iter::Sum<&'a Rational> for Rational {
+    fn sum<I: Iterator<Item = &'a Rational>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut sum, value| {
+            sum += value;
+            sum
+        })
+    }
+}
+
+impl ops::AddAssign<&Rational> for Rational {
+    fn add_assign(&mut self, rhs: &Rational) {
+        let numer = self.numer as u128 * rhs.denom as u128 + rhs.numer as u128 * self.denom as u128;
+        *self = Self::reduced(numer, self.denom as u128 * rhs.denom as u128);
+    }
+}
+
+/// Panics if `rhs` is larger, weights are never negative.
+impl ops::SubAssign<&Rational> for Rational {
+    fn sub_assign(&mut self, rhs: &Rational) {
+        let numer = self.numer as u128 * rhs.denom as u128 - rhs.numer as u128 * self.denom as u128;
+        *self = Self::reduced(numer, self.denom as u128 * rhs.denom as u128);
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.denom {
+            1 => write!(f, "{}", self.numer),
+            denom => write!(f, "{}/{denom}", self.numer),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRationalError {
+    Invalid(num::ParseIntError),
+    ZeroDenominator,
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => err.fmt(f),
+            Self::ZeroDenominator => f.write_str("zero denominator"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+/// Parses `numer/denom`, or a bare integer.
+impl str::FromStr for Rational {
+    type Err = ParseRationalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numer, denom) = s.split_once('/').unwrap_or((s, "1"));
+        let numer = numer.parse().map_err(ParseRationalError::Invalid)?;
+        let denom = denom.parse().map_err(ParseRationalError::Invalid)?;
+        if denom == 0 {
+            return Err(ParseRationalError::ZeroDenominator);
+        }
+
+        Ok(Self::new(numer, denom))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rational(s: &str) -> Rational {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn keeps_lowest_terms() {
+        let value = Rational::new(4, 6);
+        assert_eq!((value.numer(), value.denom()), (2, 3));
+        assert_eq!(value, rational("2/3"));
+        assert_eq!(Rational::new(0, 5), Rational::default());
+        assert_eq!(rational("6/3").to_string(), "2");
+        assert_eq!(rational("3/6").to_string(), "1/2");
+        assert_eq!(rational("7"), Rational::from(7));
+    }
+
+    #[test]
+    fn adds_and_subtracts_exactly() {
+        let mut value = rational("1/3");
+        value += &rational("1/6");
+        assert_eq!(value, rational("1/2"));
+
+        value -= &rational("1/2");
+        assert_eq!(value, Rational::default());
+
+        // what floats get wrong
+        let total: Rational = ["1/10", "2/10"].map(rational).iter().sum();
+        assert_eq!(total, rational("3/10"));
+    }
+
+    #[test]
+    fn orders_by_value() {
+        assert!(rational("1/3") < rational("1/2"));
+        assert!(rational("2/3") > rational("3/5"));
+        assert!(rational("5/1") > rational("9/2"));
+        assert_eq!(rational("2/4").cmp(&rational("1/2")), cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(
+            "1/0".parse::<Rational>(),
+            Err(ParseRationalError::ZeroDenominator)
+        );
+        assert!(matches!(
+            "1/x".parse::<Rational>(),
+            Err(ParseRationalError::Invalid(_))
+        ));
+        assert!(matches!(
+            "-1/2".parse::<Rational>(),
+            Err(ParseRationalError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+use std::cmp;
+
+use crate::SolutionState;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Repacking {
+    /// The weights in each bin, the bins of the current packing come first in their order.
+    pub bins: Vec<Vec<u32>>,
+    /// The number of items that left their current bin.
+    pub moves: usize,
+    /// Whether the search finished, proving the number of moves is minimal.
+    pub optimal: bool,
+}
+
+/// Repacks `items` into bins of `capacity` starting from the `current` packing, moving as few
+/// items as possible out of their current bin.
+///
+/// Items of the current packing that are missing from `items` were removed, the items of
+/// `items` beyond the current packing were added and may go anywhere.
+pub struct Repacker {
+    pub capacity: u32,
+    pub current: Vec<Vec<u32>>,
+    pub items: Vec<u32>,
+}
+
+impl Repacker {
+    pub fn new(capacity: u32, current: Vec<Vec<u32>>, items: Vec<u32>) -> Self {
+        Self {
+            capacity,
+            current,
+            items,
+        }
+    }
+
+    /// Matches the items against the current packing, returning each item with the bin it
+    /// currently is in.
+    pub fn homes(&self) -> Vec<(u32, Option<usize>)> {
+        let mut unmatched = self.items.clone();
+        let mut homes = Vec::with_capacity(self.items.len());
+        for (bin_idx, bin) in self.current.iter().enumerate() {
+            for &item in bin {
+                if let Some(idx) = unmatched.iter().position(|&weight| weight == item) {
+                    unmatched.swap_remove(idx);
+                    homes.push((item, Some(bin_idx)));
+                }
+            }
+        }
+
+        homes.extend(unmatched.into_iter().map(|item| (item, None)));
+        homes
+    }
+
+    /// Searches for the packing in `num_bins` bins, at least as many as the current packing
+    /// has, with the fewest moves.
+    pub fn solve_until(
+        &self,
+        num_bins: usize,
+        predicate: impl FnMut() -> bool,
+    ) -> SolutionState<Repacking> {
+        let num_bins = num_bins.max(self.current.len());
+        let mut items = self.homes();
+        items.sort_by_key(|&(weight, _)| cmp::Reverse(weight));
+
+        let mut homeless = vec![true; num_bins];
+        for &(_, home) in &items {
+            if let Some(home) = home {
+                homeless[home] = false;
+            }
+        }
+
+        let mut search = RepackSearch {
+            remaining_weight: items.iter().map(|&(weight, _)| weight as u64).sum(),
+            items,
+            homeless,
+            remaining: vec![self.capacity as u64; num_bins],
+            bins: vec![Vec::new(); num_bins],
+            moves: 0,
+            best: None,
+            predicate,
+            aborted: false,
+        };
+
+        search.search(0);
+
+        match search.best {
+            Some((bins, moves)) => SolutionState::Solved(Repacking {
+                bins,
+                moves,
+                optimal: !search.aborted,
+            }),
+            None if search.aborted => SolutionState::Unknown,
+            None => SolutionState::Unsolvable,
+        }
+    }
+}
+
+struct RepackSearch<P> {
+    items: Vec<(u32, Option<usize>)>,
+    /// Bins no item is currently in, empty ones of them are interchangeable.
+    homeless: Vec<bool>,
+    remaining: Vec<u64>,
+    remaining_weight: u64,
+    bins: Vec<Vec<u32>>,
+    moves: usize,
+    best: Option<(Vec<Vec<u32>>, usize)>,
+    predicate: P,
+    aborted: bool,
+}
+
+impl<P: FnMut() -> bool> RepackSearch<P> {
+    fn search(&mut self, idx: usize) {
+        if self.aborted {
+            return;
+        }
+
+        if !(self.predicate)() {
+            self.aborted = true;
+            return;
+        }
+
+        if self
+            .best
+            .as_ref()
+            .is_some_and(|(_, best)| self.moves >= *best)
+            || self.remaining.iter().sum::<u64>() < self.remaining_weight
+        {
+            return;
+        }
+
+        let Some(&(weight, home)) = self.items.get(idx) else {
+            self.best = Some((self.bins.clone(), self.moves));
+            return;
+        };
+
+        // staying in the current bin is free, so it goes first
+        let bins = home
+            .into_iter()
+            .chain((0..self.bins.len()).filter(|&bin| Some(bin) != home));
+        for bin_idx in bins.collect::<Vec<_>>() {
+            if self.remaining[bin_idx] < weight as u64 {
+                continue;
+            }
+
+            if self.homeless[bin_idx]
+                && self.bins[bin_idx].is_empty()
+                && (0..bin_idx).any(|prev| self.homeless[prev] && self.bins[prev].is_empty())
+            {
+                continue;
+            }
+
+            let moved = home.is_some_and(|home| home != bin_idx);
+            self.moves += moved as usize;
+            self.remaining[bin_idx] -= weight as u64;
+            self.remaining_weight -= weight as u64;
+            self.bins[bin_idx].push(weight);
+
+            self.search(idx + 1);
+
+            self.bins[bin_idx].pop();
+            self.remaining_weight += weight as u64;
+            self.remaining[bin_idx] += weight as u64;
+            self.moves -= moved as usize;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::{fmt, iter, num, ops, str};
+
+/// An amount of bytes, for packing files onto media. Parses `700MiB`, `4.5GB` or a bare byte
+/// count, and prints in the largest unit that divides it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Size(pub u64);
+
+/// The units of a [`Size`] with the bytes in one, largest first.
+const UNITS: [(&str, u64); 8] = [
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+];
+
+impl<'a> iter::Sum<&'a Size> for Size {
+    fn sum<I: Iterator<Item = &'a Size>>(iter: I) -> Self {
+        Self(iter.map(|size| size.0).sum())
+    }
+}
+
+impl ops::AddAssign<&Size> for Size {
+    fn add_assign(&mut self, rhs: &Size) {
+        self.0 += rhs.0;
+    }
+}
+
+impl ops::SubAssign<&Size> for Size {
+    fn sub_assign(&mut self, rhs: &Size) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = UNITS
+            .iter()
+            .find(|&&(_, bytes)| self.0 != 0 && self.0.is_multiple_of(bytes));
+
+        match unit {
+            Some((unit, bytes)) => write!(f, "{}{unit}", self.0 / bytes),
+            None => write!(f, "{}B", self.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseSizeError {
+    Invalid(num::ParseFloatError),
+    UnknownUnit(String),
+}
+
+impl fmt::Display for ParseSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => err.fmt(f),
+            Self::UnknownUnit(unit) => write!(f, "unknown unit {unit:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseSizeError {}
+
+/// Parses an amount with an optional unit, bytes by default. The unit is case insensitive, and
+/// `K`, `M`, `G` and `T` are the decimal units.
+impl str::FromStr for Size {
+    type Err = ParseSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (amount, unit) = s.split_at(split);
+        let unit = unit.trim().to_ascii_lowercase();
+        let bytes = match unit.as_str() {
+            "" | "b" => 1,
+            "k" => 1_000,
+            "m" => 1_000_000,
+            "g" => 1_000_000_000,
+            "t" => 1_000_000_000_000,
+            _ => UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&unit))
+                .map(|&(_, bytes)| bytes)
+                .ok_or(ParseSizeError::UnknownUnit(unit))?,
+        };
+
+        // whole amounts are exact past the precision of a float
+        if let Ok(amount) = amount.parse::<u64>() {
+            return Ok(Self(amount * bytes));
+        }
+
+        // a fraction of a byte is rounded off
+        let amount: f64 = amount.parse().map_err(ParseSizeError::Invalid)?;
+        Ok(Self((amount * bytes as f64).round() as u64))
+    }
+}
@@ -0,0 +1,144 @@
+use crate::SolutionState;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SoftPacking {
+    /// The weights in each bin.
+    pub bins: Vec<Vec<u32>>,
+    /// Total load over the capacity across all bins.
+    pub overflow: u64,
+    pub penalty: u64,
+    /// Whether the search finished, proving the penalty is minimal.
+    pub optimal: bool,
+}
+
+/// Bin packing with a soft capacity: bins may be loaded up to `overflow` units over `capacity`,
+/// every unit over costs `penalty`. The total penalty is minimized.
+pub struct SoftPacker {
+    pub weights: Vec<u32>,
+    pub capacity: u32,
+    pub overflow: u32,
+    pub penalty: u64,
+}
+
+impl SoftPacker {
+    pub fn new(weights: Vec<u32>, capacity: u32, overflow: u32) -> Self {
+        Self {
+            weights,
+            capacity,
+            overflow,
+            penalty: 1,
+        }
+    }
+
+    pub fn with_penalty(mut self, penalty: u64) -> Self {
+        self.penalty = penalty;
+        self
+    }
+
+    fn overflow_of(&self, load: u64) -> u64 {
+        load.saturating_sub(self.capacity as u64)
+    }
+
+    pub fn solve_until(
+        &self,
+        num_bins: usize,
+        predicate: impl FnMut() -> bool,
+    ) -> SolutionState<SoftPacking> {
+        let mut weights = self.weights.clone();
+        weights.sort_by(|a, b| b.cmp(a));
+
+        let mut search = SoftSearch {
+            packer: self,
+            remaining_weight: weights.iter().map(|&w| w as u64).sum(),
+            weights,
+            loads: vec![0; num_bins],
+            bins: vec![Vec::new(); num_bins],
+            best: None,
+            predicate,
+            aborted: false,
+        };
+
+        search.search(0);
+
+        match search.best {
+            Some((bins, overflow)) => SolutionState::Solved(SoftPacking {
+                bins,
+                overflow,
+                penalty: overflow * self.penalty,
+                optimal: !search.aborted,
+            }),
+            None if search.aborted => SolutionState::Unknown,
+            None => SolutionState::Unsolvable,
+        }
+    }
+}
+
+struct SoftSearch<'a, P> {
+    packer: &'a SoftPacker,
+    weights: Vec<u32>,
+    remaining_weight: u64,
+    loads: Vec<u64>,
+    bins: Vec<Vec<u32>>,
+    best: Option<(Vec<Vec<u32>>, u64)>,
+    predicate: P,
+    aborted: bool,
+}
+
+impl<P: FnMut() -> bool> SoftSearch<'_, P> {
+    /// Overflow of the current loads plus whatever the remaining weight can't fit under the
+    /// capacity.
+    fn lower_bound(&self) -> u64 {
+        let capacity = self.packer.capacity as u64;
+        let overflow: u64 = self
+            .loads
+            .iter()
+            .map(|&load| self.packer.overflow_of(load))
+            .sum();
+        let free: u64 = self
+            .loads
+            .iter()
+            .map(|&load| capacity.saturating_sub(load))
+            .sum();
+        overflow + self.remaining_weight.saturating_sub(free)
+    }
+
+    fn search(&mut self, idx: usize) {
+        if self.aborted {
+            return;
+        }
+
+        if !(self.predicate)() {
+            self.aborted = true;
+            return;
+        }
+
+        let bound = self.lower_bound();
+        if self.best.as_ref().is_some_and(|(_, best)| bound >= *best) {
+            return;
+        }
+
+        let Some(&weight) = self.weights.get(idx) else {
+            self.best = Some((self.bins.clone(), bound));
+            return;
+        };
+
+        let limit = self.packer.capacity as u64 + self.packer.overflow as u64;
+        for bin_idx in 0..self.loads.len() {
+            let load = self.loads[bin_idx];
+            // bins with the same load are interchangeable
+            if load + weight as u64 > limit || self.loads[..bin_idx].contains(&load) {
+                continue;
+            }
+
+            self.loads[bin_idx] += weight as u64;
+            self.remaining_weight -= weight as u64;
+            self.bins[bin_idx].push(weight);
+
+            self.search(idx + 1);
+
+            self.bins[bin_idx].pop();
+            self.remaining_weight += weight as u64;
+            self.loads[bin_idx] -= weight as u64;
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::sync::Arc;
+use core::{
+    hash, iter, ops,
+    sync::atomic::{AtomicBool, Ordering},
+    time,
+};
+
+use crate::{Bin, Fitter};
+
+/// Lets another thread (a signal handler, a supervisor, a GUI) stop a solve, clones share the
+/// same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How long a solve may run. Without the `std` feature there is no deadline, only cancellation.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    #[cfg(feature = "std")]
+    pub deadline: Option<std::time::Instant>,
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn until(deadline: std::time::Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..Self::default()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn with_timeout(timeout: time::Duration) -> Self {
+        Self::until(std::time::Instant::now() + timeout)
+    }
+
+    /// Also stops when `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Whether there is time left and the solve wasn't cancelled.
+    pub fn remains(&self) -> bool {
+        let cancelled = self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled);
+
+        #[cfg(feature = "std")]
+        let expired = self
+            .deadline
+            .is_some_and(|deadline| std::time::Instant::now() >= deadline);
+        #[cfg(not(feature = "std"))]
+        let expired = false;
+
+        !cancelled && !expired
+    }
+}
+
+/// Deterministic limits on a solve, besides the wall-clock `max_time`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SolveLimits {
+    /// Items put in a bin.
+    pub max_nodes: Option<u64>,
+    /// Items taken back out of a bin.
+    pub max_backtracks: Option<u64>,
+    /// Ignored without the `std` feature.
+    pub max_time: Option<time::Duration>,
+}
+
+/// What a search did, see [`Fitter::stats`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SolveStats {
+    /// Items put in a bin.
+    pub nodes: u64,
+    /// Items taken back out of a bin.
+    pub backtracks: u64,
+    /// The most items placed on one branch.
+    pub max_depth: usize,
+    /// Times the search gave up on the packing kept by
+    /// [`Fitter::add_item`](crate::Fitter::add_item) and started over from scratch.
+    pub restarts: u64,
+    /// Time spent searching, zero without the `std` feature.
+    pub wall_time: time::Duration,
+    /// The fewest items left unpacked at any point.
+    pub fewest_unpacked: usize,
+}
+
+/// How a solve ended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SolveOutcome {
+    /// All items are packed.
+    Solved,
+    /// The solver ran out of options without packing all items.
+    Exhausted,
+    /// The budget ran out, or the solve was cancelled, first.
+    Budget,
+}
+
+/// A packing engine, so callers can swap engines without knowing which one runs.
+pub trait Solver<T> {
+    fn solve(&mut self, budget: Budget) -> SolveOutcome;
+
+    /// The bins as packed so far.
+    fn bins(&self) -> &[Bin<T>];
+}
+
+impl<T> Solver<T> for Fitter<T>
+where
+    T: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    fn solve(&mut self, budget: Budget) -> SolveOutcome {
+        if !self.solve_until(|| budget.remains()) {
+            SolveOutcome::Budget
+        } else if self.is_solved() {
+            SolveOutcome::Solved
+        } else {
+            SolveOutcome::Exhausted
+        }
+    }
+
+    fn bins(&self) -> &[Bin<T>] {
+        &self.bins
+    }
+}
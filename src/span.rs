@@ -0,0 +1,46 @@
+use std::{fmt, iter, ops, str, time::Duration};
+
+/// A [`Duration`] weight, for packing tasks into fixed-length shifts. Parses and prints
+/// `90m`, `1h30m` style values.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span(pub Duration);
+
+impl From<Duration> for Span {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl<'a> iter::Sum<&'a Span> for Span {
+    fn sum<I: Iterator<Item = &'a Span>>(iter: I) -> Self {
+        Self(iter.map(|span| span.0).sum())
+    }
+}
+
+impl ops::AddAssign<&Span> for Span {
+    fn add_assign(&mut self, rhs: &Span) {
+        self.0 += rhs.0;
+    }
+}
+
+impl ops::SubAssign<&Span> for Span {
+    fn sub_assign(&mut self, rhs: &Span) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // spaces would split the tokens of a `v` line
+        let formatted = humantime::format_duration(self.0).to_string();
+        f.write_str(&formatted.replace(' ', ""))
+    }
+}
+
+impl str::FromStr for Span {
+    type Err = humantime::DurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        humantime::parse_duration(s).map(Self)
+    }
+}
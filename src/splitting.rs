@@ -0,0 +1,170 @@
+use crate::SolutionState;
+
+/// Part of an item placed in a bin.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fragment {
+    /// Index of the item this fragment belongs to.
+    pub item: usize,
+    pub amount: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SplitPacking {
+    pub bins: Vec<Vec<Fragment>>,
+    pub splits: usize,
+    pub penalty: u64,
+    /// Whether the search finished, proving the penalty is minimal.
+    pub optimal: bool,
+}
+
+/// Bin packing where items may be split across bins, each item into at most `max_fragments`
+/// fragments. Every split costs `split_penalty`, the total penalty is minimized.
+///
+/// The search only splits an item by filling the remaining capacity of a bin, so every
+/// fragment except the last one of each item fills its bin.
+pub struct SplitPacker {
+    pub items: Vec<u32>,
+    pub capacities: Vec<u32>,
+    pub max_fragments: usize,
+    pub split_penalty: u64,
+}
+
+impl SplitPacker {
+    pub fn new(items: Vec<u32>, capacities: Vec<u32>, max_fragments: usize) -> Self {
+        Self {
+            items,
+            capacities,
+            max_fragments: max_fragments.max(1),
+            split_penalty: 1,
+        }
+    }
+
+    pub fn with_split_penalty(mut self, split_penalty: u64) -> Self {
+        self.split_penalty = split_penalty;
+        self
+    }
+
+    pub fn solve_until(&self, predicate: impl FnMut() -> bool) -> SolutionState<SplitPacking> {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by_key(|&idx| std::cmp::Reverse(self.items[idx]));
+
+        let mut search = SplitSearch {
+            items: &self.items,
+            order,
+            max_fragments: self.max_fragments,
+            remaining: self.capacities.iter().map(|&c| c as u64).collect(),
+            remaining_weight: self.items.iter().map(|&w| w as u64).sum(),
+            bins: vec![Vec::new(); self.capacities.len()],
+            splits: 0,
+            best: None,
+            predicate,
+            aborted: false,
+        };
+
+        if let Some(&first) = search.order.first() {
+            search.search(0, self.items[first], search.max_fragments);
+        } else {
+            search.best = Some((Vec::new(), 0));
+        }
+
+        match search.best {
+            Some((bins, splits)) => SolutionState::Solved(SplitPacking {
+                bins,
+                splits,
+                penalty: splits as u64 * self.split_penalty,
+                optimal: !search.aborted,
+            }),
+            None if search.aborted => SolutionState::Unknown,
+            None => SolutionState::Unsolvable,
+        }
+    }
+}
+
+struct SplitSearch<'a, P> {
+    items: &'a [u32],
+    order: Vec<usize>,
+    max_fragments: usize,
+    remaining: Vec<u64>,
+    remaining_weight: u64,
+    bins: Vec<Vec<Fragment>>,
+    splits: usize,
+    best: Option<(Vec<Vec<Fragment>>, usize)>,
+    predicate: P,
+    aborted: bool,
+}
+
+impl<P: FnMut() -> bool> SplitSearch<'_, P> {
+    fn put(&mut self, bin_idx: usize, item: usize, amount: u32) {
+        self.remaining[bin_idx] -= amount as u64;
+        self.remaining_weight -= amount as u64;
+        self.bins[bin_idx].push(Fragment { item, amount });
+    }
+
+    fn take(&mut self, bin_idx: usize) {
+        let fragment = self.bins[bin_idx].pop().unwrap();
+        self.remaining[bin_idx] += fragment.amount as u64;
+        self.remaining_weight += fragment.amount as u64;
+    }
+
+    /// Places what is left of the `pos`th item in order, `amount` of it is still unplaced.
+    fn search(&mut self, pos: usize, amount: u32, fragments_left: usize) {
+        if self.aborted {
+            return;
+        }
+
+        if !(self.predicate)() {
+            self.aborted = true;
+            return;
+        }
+
+        if let Some((_, best_splits)) = self.best {
+            if self.splits >= best_splits {
+                return;
+            }
+        }
+
+        if self.remaining.iter().sum::<u64>() < self.remaining_weight {
+            return;
+        }
+
+        let item = self.order[pos];
+        for bin_idx in 0..self.remaining.len() {
+            let remaining = self.remaining[bin_idx];
+            // bins with the same remaining capacity are interchangeable
+            if remaining < amount as u64 || self.remaining[..bin_idx].contains(&remaining) {
+                continue;
+            }
+
+            self.put(bin_idx, item, amount);
+            match self.order.get(pos + 1) {
+                Some(&next) => self.search(pos + 1, self.items[next], self.max_fragments),
+                None => self.best = Some((self.bins.clone(), self.splits)),
+            }
+            self.take(bin_idx);
+
+            if self.best.as_ref().is_some_and(|(_, splits)| *splits == 0) {
+                return;
+            }
+        }
+
+        if fragments_left <= 1 {
+            return;
+        }
+
+        for bin_idx in 0..self.remaining.len() {
+            let remaining = self.remaining[bin_idx];
+            if remaining == 0
+                || remaining >= amount as u64
+                || self.remaining[..bin_idx].contains(&remaining)
+            {
+                continue;
+            }
+
+            self.put(bin_idx, item, remaining as u32);
+            self.splits += 1;
+            self.search(pos, amount - remaining as u32, fragments_left - 1);
+            self.splits -= 1;
+            self.take(bin_idx);
+        }
+    }
+}
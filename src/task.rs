@@ -0,0 +1,191 @@
+//! Runs a solve on its own thread and awaits it, without tying the crate to an async runtime:
+//! the [`SolveTask`] future works with any executor.
+
+use std::{
+    future::Future,
+    hash, iter, ops,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use crate::{
+    solver::{Budget, CancellationToken, SolveOutcome, SolveStats, Solver},
+    watch::{Snapshot, WatchHandle},
+    Bin, Fitter,
+};
+
+/// How a [`SolveTask`] ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskResult<T> {
+    pub outcome: SolveOutcome,
+    /// The bins as the search left them.
+    pub bins: Vec<Bin<T>>,
+    pub stats: SolveStats,
+}
+
+struct Shared<T> {
+    result: Option<TaskResult<T>>,
+    waker: Option<Waker>,
+    /// Whether the solve ended, even once the result is taken.
+    done: bool,
+}
+
+/// A [`Fitter`] solving on another thread, resolves to its [`TaskResult`]. Dropping the task
+/// before it resolves cancels the solve like [`SolveTask::cancel`] without waiting for it, the
+/// thread ends at the next check of its budget; [`SolveTask::join`] waits for it instead.
+pub struct SolveTask<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    cancellation: CancellationToken,
+    watch: WatchHandle<T>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl<T> SolveTask<T>
+where
+    T: Ord + Clone + hash::Hash + Send + 'static + for<'a> iter::Sum<&'a T>,
+    T: for<'a> ops::AddAssign<&'a T> + for<'a> ops::SubAssign<&'a T>,
+{
+    /// Starts packing `items` around the items already in `bins`, see [`Fitter::with_bins`],
+    /// within `budget`.
+    pub fn spawn(items: Vec<T>, bins: Vec<Bin<T>>, budget: Budget) -> Self {
+        Self::from_fitter(Fitter::with_bins(items, bins), budget)
+    }
+
+    /// Continues the search of `fitter` on another thread within `budget`, with its hooks,
+    /// pruners and progress reporter.
+    pub fn from_fitter(mut fitter: Fitter<T>, mut budget: Budget) -> Self {
+        let cancellation = budget
+            .cancellation
+            .get_or_insert_with(Default::default)
+            .clone();
+        let shared = Arc::new(Mutex::new(Shared {
+            result: None,
+            waker: None,
+            done: false,
+        }));
+
+        let watch = fitter.watch();
+        let mut task = Self {
+            shared: Arc::clone(&shared),
+            cancellation,
+            watch: watch.clone(),
+            thread: None,
+        };
+
+        task.thread = Some(thread::spawn(move || {
+            let outcome = fitter.solve(budget);
+            let result = TaskResult {
+                outcome,
+                stats: *fitter.stats(),
+                bins: fitter.bins,
+            };
+
+            let mut shared = shared.lock().unwrap();
+            shared.result = Some(result);
+            shared.done = true;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }));
+
+        task
+    }
+}
+
+impl<T: Clone> SolveTask<T> {
+    /// Stops the solve, the task then resolves with [`SolveOutcome::Budget`].
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// The best packing and the stats so far, see [`Fitter::watch`].
+    pub fn snapshot(&self) -> Snapshot<T> {
+        self.watch.snapshot()
+    }
+}
+
+impl<T> SolveTask<T> {
+    /// Blocks until the solve ends, for callers without an executor. Panics if the task
+    /// already resolved as a future.
+    pub fn join(mut self) -> TaskResult<T> {
+        if let Some(thread) = self.thread.take() {
+            if let Err(panic) = thread.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+
+        let result = self.shared.lock().unwrap().result.take();
+        result.expect("the solve thread stores its result before ending")
+    }
+}
+
+impl<T> Drop for SolveTask<T> {
+    fn drop(&mut self) {
+        // the token may be shared through the budget, only a running solve is cancelled
+        if self.shared.lock().is_ok_and(|shared| !shared.done) {
+            self.cancellation.cancel();
+        }
+    }
+}
+
+impl<T> Future for SolveTask<T> {
+    type Output = TaskResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate, Family};
+
+    /// A triplet instance one bin short of its optimum, the search can't prove it quickly.
+    fn one_bin_short() -> Vec<u32> {
+        generate(Family::falkenauer_t(60), 1).items
+    }
+
+    fn spawn_short(budget: Budget) -> SolveTask<u32> {
+        let bins = (0..19).map(|_| Bin::new(1000)).collect();
+        SolveTask::spawn(one_bin_short(), bins, budget)
+    }
+
+    #[test]
+    fn join_waits_for_the_cancelled_solve() {
+        let task = spawn_short(Budget::unlimited());
+        task.cancel();
+        assert_eq!(task.join().outcome, SolveOutcome::Budget);
+    }
+
+    #[test]
+    fn dropping_a_running_task_cancels_it() {
+        let token = CancellationToken::new();
+        drop(spawn_short(
+            Budget::unlimited().with_cancellation(token.clone()),
+        ));
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn dropping_a_finished_task_keeps_a_shared_token() {
+        let token = CancellationToken::new();
+        let bins = vec![Bin::new(10), Bin::new(10)];
+        let task = SolveTask::spawn(
+            vec![6, 4, 5],
+            bins,
+            Budget::unlimited().with_cancellation(token.clone()),
+        );
+        assert_eq!(task.join().outcome, SolveOutcome::Solved);
+        assert!(!token.is_cancelled());
+    }
+}
@@ -0,0 +1,221 @@
+use std::{cmp, error, fmt};
+
+use crate::{batch::Instance, Bin};
+
+/// The first thing wrong with a packing found by [`verify`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Violation<T> {
+    /// The items in the bin at this position weigh more than the capacity, with a load of
+    /// `None` when it is larger than any `T`.
+    Overfull { bin: usize, load: Option<T> },
+    /// An item of the instance that isn't in any bin, or in fewer bins than it's in the instance.
+    Missing { item: T },
+    /// An item in a bin that isn't in the instance, or is in more bins than it's in the instance.
+    Unexpected { item: T },
+}
+
+impl<T: fmt::Display> fmt::Display for Violation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overfull {
+                bin,
+                load: Some(load),
+            } => write!(f, "bin {bin} is overfull with a load of {load}"),
+            Self::Overfull { bin, load: None } => {
+                write!(f, "bin {bin} is overfull with a load too large to count")
+            }
+            Self::Missing { item } => write!(f, "an item of weight {item} is not packed"),
+            Self::Unexpected { item } => write!(
+                f,
+                "an item of weight {item} is packed but not in the instance"
+            ),
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> error::Error for Violation<T> {}
+
+/// Weights that can be added up without overflowing, for the load of a bin.
+pub trait CheckedSum: Sized {
+    /// The sum of `weights`, `None` if it is larger than any `Self`.
+    fn checked_sum<'a>(weights: impl IntoIterator<Item = &'a Self>) -> Option<Self>
+    where
+        Self: 'a;
+}
+
+macro_rules! impl_checked_sum {
+    ($($ty:ty),*) => {
+        $(impl CheckedSum for $ty {
+            fn checked_sum<'a>(weights: impl IntoIterator<Item = &'a Self>) -> Option<Self> {
+                weights.into_iter().try_fold(0, |sum: Self, weight| sum.checked_add(*weight))
+            }
+        })*
+    };
+}
+
+impl_checked_sum!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(feature = "bigint")]
+impl CheckedSum for crate::bigint::BigUint {
+    fn checked_sum<'a>(weights: impl IntoIterator<Item = &'a Self>) -> Option<Self> {
+        Some(weights.into_iter().sum())
+    }
+}
+
+/// Checks that `bins` is a packing of `instance`: no bin holds more than the capacity, and the
+/// items in the bins are exactly the items of the instance, counting duplicates. The
+/// remaining capacity and the labels of the bins aren't looked at.
+pub fn verify<T>(bins: &[Bin<T>], instance: &Instance<T>) -> Result<(), Violation<T>>
+where
+    T: Ord + Clone + CheckedSum,
+{
+    match violations(bins, instance).into_iter().next() {
+        Some(violation) => Err(violation),
+        None => Ok(()),
+    }
+}
+
+/// Everything [`verify`] would find wrong with `bins`: the overfull bins in order, then each
+/// copy of an item that is missing or unexpected, smallest first.
+pub fn violations<T>(bins: &[Bin<T>], instance: &Instance<T>) -> Vec<Violation<T>>
+where
+    T: Ord + Clone + CheckedSum,
+{
+    let mut violations = Vec::new();
+    for (bin, items) in bins.iter().enumerate() {
+        match T::checked_sum(&items.items) {
+            Some(load) if load <= instance.capacity => {}
+            load => violations.push(Violation::Overfull { bin, load }),
+        }
+    }
+
+    let mut packed: Vec<&T> = bins.iter().flat_map(|bin| &bin.items).collect();
+    let mut expected: Vec<&T> = instance.items.iter().collect();
+    packed.sort();
+    expected.sort();
+
+    let mut packed = packed.into_iter().peekable();
+    let mut expected = expected.into_iter().peekable();
+    loop {
+        let violation = match (packed.peek(), expected.peek()) {
+            (None, None) => return violations,
+            (Some(&item), None) => Violation::Unexpected { item: item.clone() },
+            (None, Some(&item)) => Violation::Missing { item: item.clone() },
+            (Some(&item), Some(&other)) => match item.cmp(other) {
+                cmp::Ordering::Less => Violation::Unexpected { item: item.clone() },
+                cmp::Ordering::Greater => Violation::Missing {
+                    item: other.clone(),
+                },
+                cmp::Ordering::Equal => {
+                    packed.next();
+                    expected.next();
+                    continue;
+                }
+            },
+        };
+
+        match violation {
+            Violation::Unexpected { .. } => packed.next(),
+            _ => expected.next(),
+        };
+
+        violations.push(violation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bin(items: &[u32]) -> Bin<u32> {
+        let mut bin = Bin::new(10);
+        for &item in items {
+            bin.push(item);
+        }
+
+        bin
+    }
+
+    fn instance(items: &[u32]) -> Instance<u32> {
+        Instance {
+            capacity: 10,
+            items: items.to_vec(),
+        }
+    }
+
+    #[test]
+    fn accepts_a_packing() {
+        let bins = [bin(&[6, 4]), bin(&[4, 3])];
+        assert_eq!(verify(&bins, &instance(&[3, 4, 4, 6])), Ok(()));
+        assert_eq!(violations(&bins, &instance(&[3, 4, 4, 6])), []);
+    }
+
+    #[test]
+    fn finds_overfull_bins_then_items() {
+        // the capacity of the instance counts, not the bin's
+        let mut overfull = Bin::new(20);
+        overfull.push(6);
+        overfull.push(6);
+        let bins = [bin(&[4]), overfull, bin(&[9, 1])];
+
+        let expected = [
+            Violation::Overfull {
+                bin: 1,
+                load: Some(12),
+            },
+            Violation::Unexpected { item: 1 },
+            Violation::Missing { item: 4 },
+            Violation::Unexpected { item: 6 },
+        ];
+
+        let instance = instance(&[9, 6, 4, 4]);
+        assert_eq!(violations(&bins, &instance), expected);
+        assert_eq!(verify(&bins, &instance), Err(expected[0].clone()));
+    }
+
+    #[test]
+    fn counts_duplicates() {
+        let bins = [bin(&[5, 5]), bin(&[5])];
+        let expected = [Violation::Missing { item: 5 }];
+        assert_eq!(violations(&bins, &instance(&[5, 5, 5, 5])), expected);
+
+        let expected = [Violation::Unexpected { item: 5 }];
+        assert_eq!(violations(&bins, &instance(&[5, 5])), expected);
+    }
+
+    #[test]
+    fn overfull_loads_do_not_overflow() {
+        // a bin can't hold more than any `u32`, so it's made by hand like a packing read from a file
+        let bin = Bin {
+            size: 4_000_000_000u32,
+            capacity: 0,
+            items: vec![3_000_000_000; 2],
+            label: None,
+        };
+        let instance = Instance {
+            capacity: 4_000_000_000,
+            items: vec![3_000_000_000; 2],
+        };
+
+        let violation = Violation::Overfull { bin: 0, load: None };
+        assert_eq!(verify(&[bin], &instance), Err(violation.clone()));
+        assert_eq!(
+            violation.to_string(),
+            "bin 0 is overfull with a load too large to count"
+        );
+
+        let mut bin = Bin::new(u64::MAX);
+        bin.push(3_000_000_000u64);
+        bin.push(3_000_000_000);
+        let instance = Instance {
+            capacity: 4_000_000_000,
+            items: vec![3_000_000_000; 2],
+        };
+        let expected = Violation::Overfull {
+            bin: 0,
+            load: Some(6_000_000_000),
+        };
+        assert_eq!(verify(&[bin], &instance), Err(expected));
+    }
+}
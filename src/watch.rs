@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{solver::SolveStats, Bin};
+
+/// What a search looked like when it was last published, see [`WatchHandle::snapshot`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot<T> {
+    /// The bins when the fewest items were left unpacked.
+    pub bins: Vec<Bin<T>>,
+    /// The items left out of `bins`.
+    pub unpacked: usize,
+    /// The stats, `wall_time` only counts the solve calls that returned.
+    pub stats: SolveStats,
+}
+
+/// Reads the progress of a search from another thread, see [`Fitter::watch`](crate::Fitter::watch).
+/// Clones share the same snapshot.
+#[derive(Debug)]
+pub struct WatchHandle<T>(Arc<Mutex<Snapshot<T>>>);
+
+impl<T> Clone for WatchHandle<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Default for WatchHandle<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Snapshot {
+            bins: Vec::new(),
+            unpacked: 0,
+            stats: SolveStats::default(),
+        })))
+    }
+}
+
+impl<T: Clone> WatchHandle<T> {
+    pub fn snapshot(&self) -> Snapshot<T> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the stats, and the packing too if `bins` is given.
+    pub(crate) fn publish(&self, stats: &SolveStats, packing: Option<(&[Bin<T>], usize)>) {
+        let mut snapshot = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        snapshot.stats = *stats;
+        if let Some((bins, unpacked)) = packing {
+            snapshot.bins = bins.to_vec();
+            snapshot.unpacked = unpacked;
+        }
+    }
+}
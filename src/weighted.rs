@@ -0,0 +1,64 @@
+use std::{hash, iter, ops};
+
+use crate::Fitter;
+
+/// Anything that can be packed by its weight.
+pub trait Weighted<W> {
+    fn weight(&self) -> W;
+}
+
+impl<W: Clone> Weighted<W> for W {
+    fn weight(&self) -> W {
+        self.clone()
+    }
+}
+
+impl Weighted<u32> for crate::knapsack::Item {
+    fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+/// Packs payloads with a [`Fitter`] over their weights, handing the payloads back by bin.
+pub struct PayloadFitter<P, W> {
+    pub payloads: Vec<P>,
+    pub fitter: Fitter<W>,
+}
+
+impl<P, W> PayloadFitter<P, W>
+where
+    P: Weighted<W>,
+    W: Ord + Clone + hash::Hash + for<'a> iter::Sum<&'a W>,
+    W: for<'a> ops::AddAssign<&'a W> + for<'a> ops::SubAssign<&'a W>,
+{
+    pub fn new(payloads: Vec<P>, bin_capacities: impl IntoIterator<Item = W>) -> Self {
+        let weights = payloads.iter().map(Weighted::weight).collect();
+        Self {
+            payloads,
+            fitter: Fitter::new(weights, bin_capacities),
+        }
+    }
+
+    /// See [`Fitter::solve_until`].
+    pub fn solve_until(&mut self, predicate: impl FnMut() -> bool) -> bool {
+        self.fitter.solve_until(predicate)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.fitter.is_solved()
+    }
+
+    /// The payloads in each bin, in input order, followed by the payloads left out.
+    pub fn into_bins(self) -> (Vec<Vec<P>>, Vec<P>) {
+        let solution = self.fitter.into_solution();
+        let mut payloads: Vec<Option<P>> = self.payloads.into_iter().map(Some).collect();
+
+        let bins = solution
+            .bins
+            .iter()
+            .map(|bin| bin.iter().filter_map(|&idx| payloads[idx].take()).collect())
+            .collect();
+
+        (bins, payloads.into_iter().flatten().collect())
+    }
+}
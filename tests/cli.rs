@@ -0,0 +1,852 @@
+use std::{
+    io::Write,
+    process::{Child, Command, Output, Stdio},
+};
+
+/// Runs the command line tool with `args` and `input` on stdin.
+fn fitter(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(args)
+        .env_remove("XDG_CONFIG_HOME")
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary is built for the tests");
+
+    // the tool may exit on bad arguments without reading its input
+    let mut stdin = child.stdin.take().unwrap();
+    let _ = stdin.write_all(input.as_bytes());
+    drop(stdin);
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Waits for `child` to exit and collects its output, killing it and failing with `stuck` if it
+/// takes more than 10 seconds.
+fn finish_within_10s(mut child: Child, stuck: &str) -> Output {
+    let start = std::time::Instant::now();
+    while child.try_wait().unwrap().is_none() {
+        if start.elapsed() > std::time::Duration::from_secs(10) {
+            child.kill().unwrap();
+            panic!("{stuck}");
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    child.wait_with_output().unwrap()
+}
+
+/// The number of bins in the packing printed with `--values`.
+fn packed_bins(output: &Output) -> usize {
+    stdout(output)
+        .lines()
+        .filter(|line| line.starts_with("v "))
+        .count()
+}
+
+#[test]
+fn plain_with_a_weight_per_line_is_not_bpplib() {
+    let input = "10\n1\n2\n3\n1\n2\n3\n1\n2\n3\n1\n2\n3\n0\n";
+    let auto = fitter(&["--values", "--minimize"], input);
+    let plain = fitter(
+        &["--values", "--minimize", "--input-format", "plain"],
+        input,
+    );
+    assert!(auto.status.success());
+    assert_eq!(stdout(&auto), stdout(&plain));
+    assert_eq!(packed_bins(&auto), 3);
+}
+
+#[test]
+fn bpplib_when_asked_for() {
+    let output = fitter(
+        &["--values", "--minimize", "--input-format", "bpplib"],
+        "4\n10\n6\n4\n5\n5\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(packed_bins(&output), 2);
+}
+
+#[test]
+fn constrained_bound_does_not_overflow() {
+    let input = "2147483648\n1073741824 1073741824\n0\n";
+    for args in [["--max-classes", "5"], ["--min-fill", "0"]] {
+        let output = fitter(&args, input);
+        assert!(output.status.success(), "{args:?}");
+        assert_eq!(stdout(&output), "s SAT\n", "{args:?}");
+    }
+}
+
+#[test]
+fn total_weight_does_not_overflow() {
+    let output = fitter(&["--values", "--minimize"], "4294967295\n4294967295 1 0\n");
+    assert!(output.status.success());
+    assert_eq!(packed_bins(&output), 2);
+}
+
+#[test]
+fn partial_packs_into_the_bins_given() {
+    let output = fitter(&["--bins", "2", "--partial", "--values"], "10\n6 6 6 0\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s UNSAT\no 2\nv 6\nv 6\nu 6\n");
+}
+
+#[test]
+fn soft_capacity_does_not_overflow() {
+    let output = fitter(&["--overflow", "10"], "4294967295\n4294967295 1 0\n");
+    assert!(!output.status.success());
+
+    let output = fitter(
+        &["--overflow", "10", "--values"],
+        "4000000000\n4000000000 1 0\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s SAT\no 1\nv 4000000000 1\n");
+}
+
+#[test]
+fn splitting_bound_does_not_overflow() {
+    let output = fitter(
+        &["--split", "2", "--values"],
+        "4294967295\n4294967295 4294967295 1 0\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(packed_bins(&output), 3);
+}
+
+#[test]
+fn lines_longer_than_the_buffer_are_not_swallowed() {
+    let input = format!("{}10\n4 0\n", " ".repeat(100_000));
+    let output = fitter(&["--values"], &input);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s SAT\nv 4\n");
+
+    let input = format!(
+        "c {}\n10\n4 0\n#{}\n",
+        "x".repeat(100_000),
+        "#".repeat(100_000)
+    );
+    let output = fitter(&["--values", "--multi-mode"], &input);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s SAT\nv 4\n");
+}
+
+#[test]
+fn huge_counts_are_rejected() {
+    let output = fitter(&[], "10\n5x4000000000 0\n");
+    assert!(!output.status.success());
+
+    let output = fitter(&[], "10\n5 x 4000000000 0\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("copies would make more than"), "{stderr}");
+
+    let output = fitter(&["--values", "--minimize"], "10\n5 x 3 0\n");
+    assert_eq!(packed_bins(&output), 2);
+}
+
+#[test]
+fn exact_weights_report_stats_and_reject_formats() {
+    let output = fitter(&["--rational", "--stats"], "1\n1/2 1/3 0\n");
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("s SAT\nc stats nodes="), "{stdout}");
+
+    for flag in ["--float", "--rational", "--durations", "--sizes"] {
+        let output = fitter(&[flag, "--format", "json"], "1\n1 0\n");
+        assert!(!output.status.success(), "{flag}");
+    }
+}
+
+#[test]
+fn exact_rectangles_stop_at_the_timeout() {
+    let input = "10x10\n\
+        4x7 3x5 3x6 6x6 6x4 3x6 3x6 6x7 3x6 5x4 7x3 5x3 3x3 7x3 6x4 6x3 7x4 6x6 7x4 5x4 \
+        4x6 5x3 6x7 3x4 5x3 5x7 6x7 4x5 5x7 6x7 6x7 3x6 4x6 6x4 5x7 5x3 6x7 3x4 7x6 5x6 0\n";
+    let start = std::time::Instant::now();
+    let output = fitter(&["--geom2d", "exact", "--timeout", "200ms"], input);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s SAT\n");
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[test]
+fn bigint_weights_past_u64() {
+    let output = fitter(
+        &["--bigint", "--values", "--minimize"],
+        "36893488147419103232\n18446744073709551616 18446744073709551616 1 0\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "s SAT\nv 18446744073709551616 18446744073709551616\nv 1\n"
+    );
+}
+
+#[test]
+fn config_words_keep_their_underscores() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("config_words");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("my_instance.txt"), "10\n6 4 0\n").unwrap();
+    std::fs::write(
+        dir.join("fitter.toml"),
+        "input_file = my_instance.txt\nvalues = true\nbins = 1_0 # at most\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(["--config", "fitter.toml"])
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(stdout(&output), "s SAT\nv 6 4\n");
+}
+
+#[test]
+fn completions_list_the_options() {
+    for (shell, line) in [
+        ("bash", "complete -F _fitter fitter"),
+        ("zsh", "#compdef fitter"),
+        (
+            "fish",
+            "complete -c fitter -n '__fish_use_subcommand' -l geom2d",
+        ),
+    ] {
+        let output = fitter(&["completions", shell], "");
+        assert!(output.status.success(), "{shell}");
+        let stdout = stdout(&output);
+        assert!(stdout.contains(line), "{shell}: {stdout}");
+        assert!(stdout.contains("bpplib"), "{shell}");
+    }
+}
+
+#[test]
+fn csv_assignment_quotes_labels() {
+    let output = fitter(
+        &["--labels", "--format", "csv"],
+        "10#a,\"b\" 5#c\n6 5 4 0\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "item,weight,bin,label\n0,6,0,\"a,\"\"b\"\"\"\n1,5,1,c\n2,4,0,\"a,\"\"b\"\"\"\n"
+    );
+
+    let output = fitter(&["--format", "csv"], "10\n6 5 11 0\n");
+    assert_eq!(
+        stdout(&output),
+        "item,weight,bin,label\n0,6,,\n1,5,,\n2,11,,\n"
+    );
+}
+
+#[test]
+fn csv_input_reads_the_columns_of_the_header() {
+    let input = "weight,id\n6,\"big, heavy\"\n5,b\n\n4,c\n";
+    let output = fitter(
+        &["--input-format", "csv", "--capacity", "10", "--values"],
+        input,
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(packed_bins(&output), 2);
+
+    let output = fitter(&["--input-format", "csv"], input);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn bounds_near_the_largest_weight_do_not_overflow() {
+    let output = fitter(&["--bound-only"], "4000000000\n3000000000 3000000000 0\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "b L1 2\nb L2 2\nb FFD 2\nb BFD 2\n");
+
+    let input = "4000000000\n2100000000 2100000000 2100000000 2100000000 1900000000 1900000000 0\n";
+    let output = fitter(&["--stats", "--minimize"], input);
+    assert!(output.status.success());
+    assert!(stdout(&output).ends_with("bins=4 lower_bound=4\n"));
+}
+
+#[test]
+fn engines_compare_weights_near_the_largest_without_overflow() {
+    let input = "4000000000\n3000000000 3000000000 0\n";
+    for algorithm in ["bin-completion", "lns", "portfolio"] {
+        let output = fitter(&["--algorithm", algorithm, "--values", "--minimize"], input);
+        assert!(output.status.success(), "{algorithm}: {output:?}");
+        assert_eq!(packed_bins(&output), 2, "{algorithm}");
+    }
+}
+
+#[test]
+fn check_counts_overfull_loads_in_full() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("overfull.txt");
+    std::fs::write(&path, "v 3000000000 3000000000\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = fitter(&["--check", path], "4000000000\n3000000000 3000000000 0\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "s INVALID\ne bin 0 is overfull with a load of 6000000000\n"
+    );
+
+    let output = fitter(&["--check", path], "4294967295\n3000000000 3000000000 0\n");
+    assert!(stdout(&output).starts_with("s INVALID\n"));
+}
+
+#[test]
+fn capacity_search_does_not_overflow() {
+    let output = fitter(&["--makespan", "--values"], "1\n3000000000 3000000000 0\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "s SAT\no 6000000000\nv 3000000000 3000000000\n"
+    );
+
+    let output = fitter(&["--min-capacity", "1"], "2\n3000000000 3000000000 0\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s UNSAT\n");
+}
+
+#[test]
+fn cover_does_not_overflow() {
+    let output = fitter(
+        &["--cover", "--values"],
+        "4000000000\n3000000000 3000000000 0\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "s SAT\no 1\nv 3000000000 3000000000\n");
+}
+
+#[test]
+fn jobs_stop_reading_after_a_failed_instance() {
+    let mut input = String::from("10\n5 4 0\n10\nabc 0\n");
+    input.push_str(&"10\n5 4 0\n".repeat(30));
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(["--multi-mode", "--jobs", "2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let _ = stdin.write_all(input.as_bytes());
+    drop(stdin);
+
+    let output = finish_within_10s(child, "the reader is stuck after the failed instance");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "s SAT\n");
+}
+
+/// Runs the command line tool like [`fitter`], sending it a Ctrl-C after `delay`, and fails if
+/// it doesn't stop within 10 seconds after.
+fn interrupted_fitter(args: &[&str], input: &str, delay: std::time::Duration) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let _ = stdin.write_all(input.as_bytes());
+    drop(stdin);
+
+    std::thread::sleep(delay);
+    let pid = child.id().to_string();
+    Command::new("kill").args(["-INT", &pid]).status().unwrap();
+
+    finish_within_10s(child, &format!("{args:?} kept running after Ctrl-C"))
+}
+
+/// 45 weights from 100 to 699, too many to cover bins of 1000 with quickly.
+fn hard_cover_instance() -> String {
+    let mut state = 12345u32;
+    let weights: Vec<String> = (0..45)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (100 + (state >> 16) % 600).to_string()
+        })
+        .collect();
+
+    format!("1000\n{} 0\n", weights.join(" "))
+}
+
+#[test]
+fn ctrl_c_stops_the_deadline_modes() {
+    let delay = std::time::Duration::from_millis(500);
+    let output = interrupted_fitter(&["--cover"], &hard_cover_instance(), delay);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout(&output).starts_with("s SAT\no "));
+}
+
+/// A triplet instance from the generator, slow to prove optimal.
+fn hard_bench_instance() -> String {
+    let args = [
+        "generate",
+        "--class",
+        "falkenauer-t",
+        "--n",
+        "60",
+        "--seed",
+        "1",
+    ];
+    stdout(&fitter(&args, ""))
+}
+
+#[test]
+fn memory_limit_stops_every_mode() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(["--memory-limit", "1", "--cover"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let _ = stdin.write_all(hard_cover_instance().as_bytes());
+    drop(stdin);
+
+    let output = finish_within_10s(child, "--cover ignored --memory-limit");
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("s SAT\no "));
+
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("bench_memory");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("hard.txt"), hard_bench_instance()).unwrap();
+
+    let child = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(["--memory-limit", "1", "bench", "--timeout", "1h"])
+        .arg(&dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let output = finish_within_10s(child, "bench ignored --memory-limit");
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("hard.txt"));
+}
+
+#[test]
+fn text_only_modes_reject_formats() {
+    for mode in [
+        &["--cover"][..],
+        &["--makespan"],
+        &["--knapsack"],
+        &["--min-fill", "1"],
+        &["--nested", "20"],
+        &["--count"],
+        &["--split", "2"],
+        &["--labels"],
+    ] {
+        let output = fitter(&[mode, &["--format", "json"]].concat(), "10\n5 4 0\n");
+        assert!(!output.status.success(), "{mode:?}");
+        assert_eq!(stdout(&output), "", "{mode:?}");
+    }
+
+    let output = fitter(&["--bound-only", "--format", "jsonl"], "10\n5 4 0\n");
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("{\"lower\":"));
+
+    let output = fitter(&["--bound-only", "--format", "csv"], "10\n5 4 0\n");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn knapsack_values_do_not_overflow() {
+    let input = "10\n4,18446744073709551615 5,18446744073709551615 6,1 0\n";
+    let output = fitter(&["--knapsack", "--values"], input);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        stdout(&output),
+        "s SAT\no 36893488147419103230\nv 4,18446744073709551615 5,18446744073709551615\nu 6,1\nr 1\n"
+    );
+}
+
+#[test]
+fn every_algorithm_packs_the_instance() {
+    for algorithm in [
+        "backtracking",
+        "bin-completion",
+        "ffd",
+        "bfd",
+        "lns",
+        "portfolio",
+    ] {
+        let output = fitter(
+            &["--algorithm", algorithm, "--values", "--timeout", "1s"],
+            "10\n6 4 5 5 0\n",
+        );
+        assert!(output.status.success(), "{algorithm}");
+        assert_eq!(stdout(&output), "s SAT\nv 6 4\nv 5 5\n", "{algorithm}");
+    }
+}
+
+#[test]
+fn json_lists_the_bins_with_their_loads() {
+    let output = fitter(&["--format", "json"], "10\n6 4 5 0\n");
+    assert!(output.status.success());
+    let json = stdout(&output);
+    assert!(json.contains(r#""status": "SAT""#), "{json}");
+    assert!(json.contains(r#""items": [6, 4]"#), "{json}");
+    assert!(json.contains(r#""load": 5"#), "{json}");
+    assert!(json.contains(r#""stats": {"#), "{json}");
+}
+
+#[test]
+fn jsonl_writes_a_line_per_instance() {
+    let output = fitter(
+        &["--multi-mode", "--format", "jsonl"],
+        "10\n6 4 5 5 0\n10\n9 9 0\n",
+    );
+    assert!(output.status.success());
+    let lines: Vec<String> = stdout(&output).lines().map(String::from).collect();
+    assert_eq!(lines.len(), 2, "{lines:?}");
+    assert!(lines[0].starts_with(r#"{"status":"SAT","bins":[{"items":[6,4]"#));
+    assert!(lines[1].starts_with(r#"{"status":"SAT","bins":[{"items":[9]"#));
+}
+
+#[test]
+fn bins_limits_the_packing() {
+    let input = "10\n6 4 5 5 0\n";
+    assert_eq!(stdout(&fitter(&["--bins", "2"], input)), "s SAT\n");
+    assert_eq!(stdout(&fitter(&["--bins", "1"], input)), "s UNSAT\n");
+}
+
+#[test]
+fn bins_of_different_capacities() {
+    let output = fitter(&["--values"], "5 7\n6 5 0\n");
+    assert_eq!(stdout(&output), "s SAT\nv 5\nv 6\n");
+
+    let output = fitter(&["--values"], "5 7\n6 6 0\n");
+    assert_eq!(stdout(&output), "s UNSAT\n");
+}
+
+#[test]
+fn generate_repeats_a_seed() {
+    let generate = |seed: &str| {
+        let args = [
+            "generate", "--class", "weibull", "--n", "30", "--seed", seed,
+        ];
+        stdout(&fitter(&args, ""))
+    };
+
+    assert_eq!(generate("7"), generate("7"));
+    assert_ne!(generate("7"), generate("8"));
+
+    let output = fitter(&["--values"], &generate("7"));
+    assert!(stdout(&output).starts_with("s SAT\n"));
+}
+
+#[test]
+fn bench_writes_a_row_per_instance() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("bench_rows");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("fits.txt"), "10\n6 4 5 5 0\n").unwrap();
+    std::fs::write(dir.join("apart.txt"), "10\n9 9 9 0\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(["bench", "--timeout", "1s"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let table = stdout(&output);
+    let row = |name: &str| {
+        let line = table.lines().find(|line| line.starts_with(name));
+        line.unwrap_or_else(|| panic!("no row for {name} in {table}"))
+            .split_whitespace()
+            .take(3)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(table.lines().count(), 3, "{table}");
+    assert_eq!(row("apart.txt"), ["apart.txt", "OPTIMAL", "3"]);
+    assert_eq!(row("fits.txt"), ["fits.txt", "OPTIMAL", "2"]);
+}
+
+#[test]
+fn files_are_solved_one_after_the_other() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("input_files");
+    std::fs::create_dir_all(&dir).unwrap();
+    let (first, second) = (dir.join("first.txt"), dir.join("second.txt"));
+    std::fs::write(&first, "10\n6 4 5 5 0\n").unwrap();
+    std::fs::write(&second, "10\n9 9 0\n").unwrap();
+
+    let args = [
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+        "--values",
+    ];
+    let output = fitter(&args, "");
+    assert!(output.status.success());
+    let expected = format!(
+        "c file {}\ns SAT\nv 6 4\nv 5 5\nc file {}\ns SAT\nv 9\nv 9\n",
+        first.display(),
+        second.display()
+    );
+    assert_eq!(stdout(&output), expected);
+}
+
+#[test]
+fn json_input_with_a_capacity_or_bins() {
+    let input = r#"{"capacity": 10, "items": [6, {"weight": 4, "label": "lid"}, 5, 5]}"#;
+    let output = fitter(&["--values"], input);
+    assert_eq!(stdout(&output), "s SAT\nv 6 lid\nv 5 5\n");
+
+    let input = r#"{"bins": [5, {"capacity": 7}], "items": [6, 5]}"#;
+    let output = fitter(&["--values", "--input-format", "json"], input);
+    assert_eq!(stdout(&output), "s SAT\nv 5\nv 6\n");
+}
+
+#[test]
+fn named_items_are_written_by_name() {
+    let output = fitter(&["--values"], "10\ndisk-a:6 disk-b:4 5 5 0\n");
+    assert_eq!(stdout(&output), "s SAT\nv disk-a disk-b\nv 5 5\n");
+}
+
+#[test]
+fn min_bins_stops_minimizing_early() {
+    let input = "10\n6 4 5 5 3 0\n";
+    let output = fitter(&["--minimize", "--min-bins", "3", "--values"], input);
+    assert_eq!(packed_bins(&output), 3);
+
+    let output = fitter(&["--minimize", "--max-bins", "2"], input);
+    assert_eq!(stdout(&output), "s UNSAT\n");
+}
+
+#[test]
+fn stats_line_in_text_and_json() {
+    let input = "10\n6 4 5 5 0\n";
+    let text = stdout(&fitter(&["--stats"], input));
+    let line = text.lines().find(|line| line.starts_with("c stats "));
+    let line = line.unwrap_or_else(|| panic!("no stats line in {text}"));
+    assert!(line.starts_with("c stats nodes=4 backtracks=0 restarts=0 time="));
+    assert!(line.ends_with(" bins=2 lower_bound=2"), "{line}");
+
+    let json = stdout(&fitter(&["--stats=json"], input));
+    assert!(json.contains(r#"c stats {"nodes":4,"#), "{json}");
+    assert!(json.contains(r#""bins":2,"lower_bound":2}"#), "{json}");
+}
+
+#[test]
+fn exit_codes_follow_the_sat_competition() {
+    let code = |input: &str| fitter(&["--exit-codes"], input).status.code();
+    assert_eq!(code("10\n6 4 5 5 0\n"), Some(10));
+    assert_eq!(code("10\n11 0\n"), Some(20));
+    assert_eq!(fitter(&[], "10\n11 0\n").status.code(), Some(0));
+}
+
+#[test]
+fn the_same_seed_repeats_the_search() {
+    let instance = stdout(&fitter(&["generate", "--n", "24", "--seed", "4"], ""));
+    let run = |seed: &str| {
+        let args = [
+            "--algorithm",
+            "lns",
+            "--bins",
+            "10",
+            "--values",
+            "--seed",
+            seed,
+        ];
+        let output = fitter(&args, &instance);
+        assert!(stdout(&output).starts_with("s SAT\n"));
+        stdout(&output)
+    };
+
+    assert_eq!(run("1"), run("1"));
+    assert_ne!(run("1"), run("2"));
+}
+
+#[test]
+fn anytime_writes_each_better_packing() {
+    let output = fitter(&["--minimize", "--anytime"], "10\n6 4 5 5 3 0\n");
+    assert_eq!(stdout(&output), "o 3\ns SAT\n");
+}
+
+#[test]
+fn svg_draws_each_item() {
+    let output = fitter(&["--format", "svg"], "10\n6 4 5 0\n");
+    assert!(output.status.success());
+    let svg = stdout(&output);
+    assert!(svg.starts_with("<svg "), "{svg}");
+    assert!(svg.trim_end().ends_with("</svg>"), "{svg}");
+    assert_eq!(svg.matches("<title>").count(), 3, "{svg}");
+}
+
+#[test]
+fn dot_clusters_the_items_of_a_bin() {
+    let output = fitter(&["--format", "dot"], "10\n6 4 5 0\n");
+    assert!(output.status.success());
+    let dot = stdout(&output);
+    assert!(dot.starts_with("graph packing {"), "{dot}");
+    assert_eq!(dot.matches("subgraph cluster_").count(), 2, "{dot}");
+    assert!(dot.contains(r#"label="bin 0 (10/10)""#), "{dot}");
+}
+
+#[test]
+fn bins_and_items_are_sorted_as_asked() {
+    let input = "10\n6 1 5 3 0\n";
+    let output = fitter(&["--values", "--sort-bins", "fill"], input);
+    assert_eq!(stdout(&output), "s SAT\nv 6 3 1\nv 5\n");
+
+    let args = [
+        "--values",
+        "--sort-bins",
+        "fill",
+        "--sort-items",
+        "ascending",
+    ];
+    assert_eq!(stdout(&fitter(&args, input)), "s SAT\nv 1 3 6\nv 5\n");
+}
+
+#[test]
+fn stats_describes_the_instance() {
+    let output = fitter(&["stats"], "10\n6 4 5 5 0\n");
+    assert!(output.status.success());
+    let stats = stdout(&output);
+    for line in [
+        "capacity 10",
+        "items 4",
+        "total_weight 20",
+        "L1 2",
+        "duplicates 1",
+    ] {
+        assert!(
+            stats.lines().any(|other| other == line),
+            "{line} in {stats}"
+        );
+    }
+}
+
+#[test]
+fn validate_lists_the_problems_with_their_lines() {
+    let output = fitter(&["validate"], "10\n6 4 5 5 0\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "<stdin>: ok\n");
+
+    let output = fitter(&["validate"], "10\n6 4 5 11 0\n");
+    assert!(!output.status.success());
+    let problems = stdout(&output);
+    assert!(
+        problems.starts_with("<stdin>:2: error: item \"11\""),
+        "{problems}"
+    );
+}
+
+#[test]
+fn shrink_keeps_what_makes_the_instance_interesting() {
+    let output = fitter(
+        &["shrink", "--command", "! grep -qw 9"],
+        "10\n6 4 5 5 3 9 0\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10\n9\n0\n");
+
+    let output = fitter(&["shrink", "--command", "true"], "10\n6 4 0\n");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn crosscheck_runs_every_engine() {
+    let output = fitter(&["--timeout", "1s", "crosscheck"], "10\n6 4 5 5 3 0\n");
+    assert!(output.status.success());
+    let lines = stdout(&output);
+    for engine in [
+        "backtracking",
+        "bin-completion",
+        "ffd",
+        "bfd",
+        "lns",
+        "portfolio",
+    ] {
+        let line = lines
+            .lines()
+            .find(|line| line.starts_with(&format!("r {engine} ")));
+        let line = line.unwrap_or_else(|| panic!("no line for {engine} in {lines}"));
+        assert_eq!(line.split_whitespace().nth(3), Some("3"), "{line}");
+    }
+
+    assert!(lines.ends_with("s CONSISTENT\n"), "{lines}");
+}
+
+#[test]
+fn instance_timeout_moves_on_to_the_next_instance() {
+    let input = format!("{}10\n9 9 0\n", hard_bench_instance());
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fitter"))
+        .args(["--multi-mode", "--minimize", "--instance-timeout", "1s"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let _ = stdin.write_all(input.as_bytes());
+    drop(stdin);
+
+    let output = finish_within_10s(child, "--instance-timeout didn't stop the search");
+    assert!(output.status.success());
+    // the first instance stops at its timeout with the packing found so far
+    assert!(
+        stdout(&output).ends_with("s SAT\ns SAT\n"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn sizes_with_units() {
+    let output = fitter(&["--sizes", "--values"], "4.7GB\n2GB 2.5GB 700MiB 0\n");
+    assert_eq!(stdout(&output), "s SAT\nv 2500MB 2GB\nv 700MiB\n");
+}
+
+#[test]
+fn decimals_are_scaled_to_whole_numbers() {
+    let output = fitter(&["--decimal", "--values"], "2.5\n1.5 1 1.25 0\n");
+    assert_eq!(stdout(&output), "c scale 100\ns SAT\nv 1.5 1\nv 1.25\n");
+}
+
+#[test]
+fn comments_and_the_header_line() {
+    let input = "c a comment\np bpp 4 10\n10\n6 4 5 5 0\n";
+    assert_eq!(
+        stdout(&fitter(&["--values"], input)),
+        "s SAT\nv 6 4\nv 5 5\n"
+    );
+
+    let output = fitter(&["--values"], "p bpp 3 10\n10\n6 4 5 5 0\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("the header declares 3 items, found 4"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn weights_with_a_count() {
+    let output = fitter(&["--values"], "10\n5 x 4 0\n");
+    assert_eq!(stdout(&output), "s SAT\nv 5 5\nv 5 5\n");
+
+    let output = fitter(&["--minimize"], "10\n5 x 100000 0\n");
+    assert!(stdout(&output).starts_with("s SAT\n"));
+}
+
+#[test]
+fn progress_without_a_terminal_leaves_the_output_alone() {
+    let input = "10\n6 4 5 5 0\n";
+    let output = fitter(&["--progress", "--values"], input);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), stdout(&fitter(&["--values"], input)));
+    assert!(output.stderr.is_empty());
+}